@@ -0,0 +1,430 @@
+// Simbologias aceitas nos modelos, validação dos dados (com dígitos verificadores)
+// e codificação em módulos (true = barra), usada pelo emulador e pelas métricas do layout
+
+// Padrões de 7 módulos dos dígitos do EAN-13 (conjuntos L, G e R)
+const EAN_L: [u8; 10] = [0x0D, 0x19, 0x13, 0x3D, 0x23, 0x31, 0x2F, 0x3B, 0x37, 0x0B];
+const EAN_G: [u8; 10] = [0x27, 0x33, 0x1B, 0x21, 0x1D, 0x39, 0x05, 0x11, 0x09, 0x17];
+const EAN_R: [u8; 10] = [0x72, 0x66, 0x6C, 0x42, 0x5C, 0x4E, 0x50, 0x44, 0x48, 0x74];
+
+// Conjunto (L ou G) de cada dígito da esquerda, definido pelo primeiro dígito
+const EAN13_PARITY: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL", "LGGLGL",
+];
+
+// Total de módulos de um EAN-13 (guardas + 12 dígitos codificados)
+pub const EAN13_MODULES: u32 = 95;
+
+// Total de módulos de um EAN-8 (guardas + 8 dígitos codificados)
+pub const EAN8_MODULES: u32 = 67;
+
+// Larguras (barra, espaço, barra...) dos símbolos 0 a 105 do Code 128
+const CODE128_PATTERNS: [u32; 106] = [
+    212222, 222122, 222221, 121223, 121322, 131222, 122213, 122312, 132212, 221213,
+    221312, 231212, 112232, 122132, 122231, 113222, 123122, 123221, 223211, 221132,
+    221231, 213212, 223112, 312131, 311222, 321122, 321221, 312212, 322112, 322211,
+    212123, 212321, 232121, 111323, 131123, 131321, 112313, 132113, 132311, 211313,
+    231113, 231311, 112133, 112331, 132131, 113123, 113321, 133121, 313121, 211331,
+    231131, 213113, 213311, 213131, 311123, 311321, 331121, 312113, 312311, 332111,
+    314111, 221411, 431111, 111224, 111422, 121124, 121421, 141122, 141221, 112214,
+    112412, 122114, 122411, 142112, 142211, 241211, 221114, 413111, 241112, 134111,
+    111242, 121142, 121241, 114212, 124112, 124211, 411212, 421112, 421211, 212141,
+    214121, 412121, 111143, 111341, 131141, 114113, 114311, 411113, 411311, 113141,
+    114131, 311141, 411131, 211412, 211214, 211232,
+];
+const CODE128_START_B: usize = 104;
+const CODE128_STOP: u32 = 2331112;
+
+// Barras/espaços largos (w) e finos (n) de cada dígito do Interleaved 2 of 5
+const ITF_PATTERNS: [&str; 10] = ["nnwwn", "wnnnw", "nwnnw", "wwnnn", "nnwnw", "wnwnn", "nwwnn", "nnnww", "wnnwn", "nwnwn"];
+
+// Limites de dados dos códigos 2D (modo byte, correção M no QR)
+const QR_MAX_BYTES: usize = 2331;
+const DATAMATRIX_MAX_BYTES: usize = 1556;
+const CODE128_MAX_CHARS: usize = 80;
+
+// Bytes por versão do QR Code (1 a 40) em modo byte com correção M
+const QR_CAPACITY: [usize; 40] = [
+    14, 26, 42, 62, 84, 106, 122, 152, 180, 213, 251, 287, 331, 362, 412, 450, 504, 560, 624, 666,
+    711, 779, 857, 911, 997, 1059, 1125, 1190, 1264, 1370, 1452, 1538, 1628, 1722, 1809, 1911, 1989, 2099, 2213, 2331,
+];
+
+// (lado em módulos, palavras de dados) dos símbolos quadrados do DataMatrix ECC 200
+const DATAMATRIX_SIZES: [(u32, usize); 24] = [
+    (10, 3), (12, 5), (14, 8), (16, 12), (18, 18), (20, 22), (22, 30), (24, 36), (26, 44), (32, 62), (36, 86), (40, 114),
+    (44, 144), (48, 174), (52, 204), (64, 280), (72, 368), (80, 456), (88, 576), (96, 696), (104, 816), (120, 1050),
+    (132, 1304), (144, 1558),
+];
+
+// Simbologia de um elemento de código de barras
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symbology {
+    Ean13,
+    Ean8,
+    UpcA,
+    Code128,
+    Itf14,
+    Qr,
+    DataMatrix,
+}
+
+impl Symbology {
+    pub fn parse(value: &str) -> Result<Symbology, String> {
+        match value.to_lowercase().replace(['-', '_'], "").as_str() {
+            "ean13" => Ok(Symbology::Ean13),
+            "ean8" => Ok(Symbology::Ean8),
+            "upca" => Ok(Symbology::UpcA),
+            "code128" => Ok(Symbology::Code128),
+            "itf14" => Ok(Symbology::Itf14),
+            "qr" | "qrcode" => Ok(Symbology::Qr),
+            "datamatrix" => Ok(Symbology::DataMatrix),
+            _ => Err(format!(
+                "Tipo de código de barras não suportado: {} (use ean13, ean8, upca, code128, itf14, qr ou datamatrix)",
+                value
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symbology::Ean13 => "EAN-13",
+            Symbology::Ean8 => "EAN-8",
+            Symbology::UpcA => "UPC-A",
+            Symbology::Code128 => "Code 128",
+            Symbology::Itf14 => "ITF-14",
+            Symbology::Qr => "QR",
+            Symbology::DataMatrix => "DataMatrix",
+        }
+    }
+
+    // Códigos 2D: tamanho do módulo vem da barra fina; altura e dígitos legíveis não se aplicam
+    pub fn is_2d(&self) -> bool {
+        matches!(self, Symbology::Qr | Symbology::DataMatrix)
+    }
+}
+
+fn push_pattern(modules: &mut Vec<bool>, pattern: u8, width: u32) {
+    for bit in (0..width).rev() {
+        modules.push(pattern & (1 << bit) != 0);
+    }
+}
+
+// Dígito verificador GS1 (EAN-8, UPC-A, ITF-14): pesos 3 e 1 a partir do último dígito
+pub fn gs1_check_digit(data: &str) -> char {
+    let sum: u32 = data
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(index, b)| (b - b'0') as u32 * if index % 2 == 0 { 3 } else { 1 })
+        .sum();
+
+    char::from_digit((10 - sum % 10) % 10, 10).unwrap_or('0')
+}
+
+// Completa (len - 1 dígitos) ou confere (len dígitos) o dígito verificador GS1
+fn gs1_digits(name: &str, data: &str, len: usize) -> Result<String, String> {
+    if !data.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("{} aceita apenas dígitos: \"{}\"", name, data));
+    }
+
+    if data.len() == len - 1 {
+        return Ok(format!("{}{}", data, gs1_check_digit(data)));
+    }
+
+    if data.len() != len {
+        return Err(format!("{} deve ter {} ou {} dígitos (recebido {}): \"{}\"", name, len - 1, len, data.len(), data));
+    }
+
+    let expected = gs1_check_digit(&data[..len - 1]);
+    if data.ends_with(expected) {
+        Ok(data.to_string())
+    } else {
+        Err(format!("Dígito verificador do {} \"{}\" deveria ser {}", name, data, expected))
+    }
+}
+
+pub fn ean8_digits(data: &str) -> Result<String, String> {
+    gs1_digits("EAN-8", data, 8)
+}
+
+pub fn upca_digits(data: &str) -> Result<String, String> {
+    gs1_digits("UPC-A", data, 12)
+}
+
+pub fn itf14_digits(data: &str) -> Result<String, String> {
+    gs1_digits("ITF-14", data, 14)
+}
+
+// Code 128 (subconjunto B): caracteres ASCII imprimíveis; o verificador (módulo 103) é calculado na codificação
+pub fn code128_data(data: &str) -> Result<String, String> {
+    if data.is_empty() || data.chars().count() > CODE128_MAX_CHARS {
+        return Err(format!("Code 128 deve ter de 1 a {} caracteres: \"{}\"", CODE128_MAX_CHARS, data.escape_debug()));
+    }
+
+    match data.chars().find(|c| !(' '..='~').contains(c)) {
+        Some(c) => Err(format!("Code 128 não aceita o caractere '{}' em \"{}\"", c.escape_debug(), data.escape_debug())),
+        None => Ok(data.to_string()),
+    }
+}
+
+// Dados dos códigos 2D: qualquer texto, limitado à capacidade do maior símbolo
+fn data_2d(name: &str, data: &str, max_bytes: usize) -> Result<String, String> {
+    if data.is_empty() {
+        return Err(format!("{} sem dados", name));
+    }
+    if data.len() > max_bytes {
+        return Err(format!("{} aceita até {} bytes (recebido {})", name, max_bytes, data.len()));
+    }
+    Ok(data.to_string())
+}
+
+// Confere os dados para a simbologia e devolve o conteúdo completo (com dígito verificador nos códigos GS1)
+pub fn validate(symbology: Symbology, data: &str) -> Result<String, String> {
+    match symbology {
+        Symbology::Ean13 => ean13_digits(data),
+        Symbology::Ean8 => ean8_digits(data),
+        Symbology::UpcA => upca_digits(data),
+        Symbology::Code128 => code128_data(data),
+        Symbology::Itf14 => itf14_digits(data),
+        Symbology::Qr => data_2d("QR Code", data, QR_MAX_BYTES),
+        Symbology::DataMatrix => data_2d("DataMatrix", data, DATAMATRIX_MAX_BYTES),
+    }
+}
+
+// Completa (12 dígitos) ou confere (13 dígitos) o dígito verificador
+pub fn ean13_digits(data: &str) -> Result<String, String> {
+    if !data.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("EAN-13 aceita apenas dígitos: \"{}\"", data));
+    }
+
+    match data.len() {
+        12 => Ok(format!("{}{}", data, crate::calculate_ean13_check_digit(data)?)),
+        13 => {
+            let expected = crate::calculate_ean13_check_digit(&data[..12])?;
+            if data.ends_with(expected) {
+                Ok(data.to_string())
+            } else {
+                Err(format!("Dígito verificador do EAN-13 \"{}\" deveria ser {}", data, expected))
+            }
+        }
+        len => Err(format!("EAN-13 deve ter 12 ou 13 dígitos (recebido {}): \"{}\"", len, data)),
+    }
+}
+
+// Módulos do EAN-13 (95 no total)
+pub fn ean13_modules(data: &str) -> Result<Vec<bool>, String> {
+    let digits: Vec<usize> = ean13_digits(data)?
+        .bytes()
+        .map(|b| (b - b'0') as usize)
+        .collect();
+    let parity = EAN13_PARITY[digits[0]].as_bytes();
+
+    let mut modules = Vec::with_capacity(EAN13_MODULES as usize);
+    push_pattern(&mut modules, 0b101, 3);
+
+    for (index, digit) in digits[1..7].iter().enumerate() {
+        let table = if parity[index] == b'L' { &EAN_L } else { &EAN_G };
+        push_pattern(&mut modules, table[*digit], 7);
+    }
+
+    push_pattern(&mut modules, 0b01010, 5);
+
+    for digit in &digits[7..13] {
+        push_pattern(&mut modules, EAN_R[*digit], 7);
+    }
+
+    push_pattern(&mut modules, 0b101, 3);
+    Ok(modules)
+}
+
+// Módulos do EAN-8 (67 no total)
+pub fn ean8_modules(data: &str) -> Result<Vec<bool>, String> {
+    let digits: Vec<usize> = ean8_digits(data)?.bytes().map(|b| (b - b'0') as usize).collect();
+
+    let mut modules = Vec::with_capacity(EAN8_MODULES as usize);
+    push_pattern(&mut modules, 0b101, 3);
+    for digit in &digits[..4] {
+        push_pattern(&mut modules, EAN_L[*digit], 7);
+    }
+    push_pattern(&mut modules, 0b01010, 5);
+    for digit in &digits[4..] {
+        push_pattern(&mut modules, EAN_R[*digit], 7);
+    }
+    push_pattern(&mut modules, 0b101, 3);
+    Ok(modules)
+}
+
+// Módulos do UPC-A: é um EAN-13 que começa com 0
+pub fn upca_modules(data: &str) -> Result<Vec<bool>, String> {
+    ean13_modules(&format!("0{}", upca_digits(data)?))
+}
+
+// Acrescenta barras e espaços alternados (começando por barra) com as larguras dadas
+fn push_widths(modules: &mut Vec<bool>, widths: impl IntoIterator<Item = u32>) {
+    for (index, width) in widths.into_iter().enumerate() {
+        modules.extend(std::iter::repeat_n(index % 2 == 0, width as usize));
+    }
+}
+
+// Larguras de um símbolo do Code 128 (o número 212222 vira 2,1,2,2,2,2)
+fn code128_widths(pattern: u32) -> Vec<u32> {
+    pattern.to_string().bytes().map(|b| (b - b'0') as u32).collect()
+}
+
+// Módulos do Code 128 no subconjunto B, com o verificador módulo 103
+pub fn code128_modules(data: &str) -> Result<Vec<bool>, String> {
+    let values: Vec<usize> = code128_data(data)?.bytes().map(|b| (b - b' ') as usize).collect();
+    let checksum = values
+        .iter()
+        .enumerate()
+        .fold(CODE128_START_B, |sum, (index, value)| sum + (index + 1) * value)
+        % 103;
+
+    let mut modules = Vec::new();
+    for symbol in std::iter::once(CODE128_START_B).chain(values).chain(std::iter::once(checksum)) {
+        push_widths(&mut modules, code128_widths(CODE128_PATTERNS[symbol]));
+    }
+    push_widths(&mut modules, code128_widths(CODE128_STOP));
+    Ok(modules)
+}
+
+// Módulos do ITF-14 (Interleaved 2 of 5); `ratio` é a largura da barra larga em módulos
+pub fn itf14_modules(data: &str, ratio: u32) -> Result<Vec<bool>, String> {
+    let digits: Vec<usize> = itf14_digits(data)?.bytes().map(|b| (b - b'0') as usize).collect();
+    let width = |c: u8| if c == b'w' { ratio } else { 1 };
+
+    let mut modules = Vec::new();
+    push_widths(&mut modules, [1, 1, 1, 1]);
+
+    for pair in digits.chunks(2) {
+        // Primeiro dígito nas barras, segundo nos espaços
+        let bars = ITF_PATTERNS[pair[0]].as_bytes();
+        let spaces = ITF_PATTERNS[pair[1]].as_bytes();
+        push_widths(&mut modules, (0..5).flat_map(|i| [width(bars[i]), width(spaces[i])]));
+    }
+
+    push_widths(&mut modules, [ratio, 1, 1]);
+    Ok(modules)
+}
+
+// Largura da barra larga em módulos (ITF): entre 2 e 3 vezes a barra fina
+pub fn wide_ratio(narrow: u32, wide: u32) -> u32 {
+    (wide / narrow.max(1)).clamp(2, 3)
+}
+
+// Módulos de um código linear; códigos 2D não têm módulos lineares
+pub fn modules(symbology: Symbology, data: &str, ratio: u32) -> Result<Option<Vec<bool>>, String> {
+    match symbology {
+        Symbology::Ean13 => ean13_modules(data).map(Some),
+        Symbology::Ean8 => ean8_modules(data).map(Some),
+        Symbology::UpcA => upca_modules(data).map(Some),
+        Symbology::Code128 => code128_modules(data).map(Some),
+        Symbology::Itf14 => itf14_modules(data, ratio).map(Some),
+        Symbology::Qr | Symbology::DataMatrix => Ok(None),
+    }
+}
+
+// Lado, em módulos, do menor símbolo 2D que comporta os dados
+pub fn side_2d(symbology: Symbology, data: &str) -> Option<u32> {
+    match symbology {
+        Symbology::Qr => QR_CAPACITY
+            .iter()
+            .position(|capacity| data.len() <= *capacity)
+            .map(|version| 21 + 4 * version as u32),
+        Symbology::DataMatrix => {
+            // Pares de dígitos ocupam uma palavra; os demais bytes, uma cada
+            let mut codewords = 0;
+            let mut bytes = data.bytes().peekable();
+            while let Some(b) = bytes.next() {
+                if b.is_ascii_digit() && bytes.peek().is_some_and(|next| next.is_ascii_digit()) {
+                    bytes.next();
+                }
+                codewords += 1;
+            }
+            DATAMATRIX_SIZES
+                .iter()
+                .find(|(_, capacity)| codewords <= *capacity)
+                .map(|(side, _)| *side)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "11010010000" -> módulos (1 = barra)
+    fn bits(pattern: &str) -> Vec<bool> {
+        pattern.bytes().map(|b| b == b'1').collect()
+    }
+
+    #[test]
+    fn digito_verificador_gs1_dos_exemplos() {
+        assert_eq!(ean8_digits("9638507").unwrap(), "96385074");
+        assert_eq!(upca_digits("03600029145").unwrap(), "036000291452");
+        // Pesos 3 e 1 a partir do último dígito: 97 -> 3
+        assert_eq!(itf14_digits("1540014128876").unwrap(), "15400141288763");
+        assert_eq!(ean13_digits("789123456789").unwrap(), "7891234567895");
+    }
+
+    #[test]
+    fn digito_verificador_errado_e_recusado() {
+        assert_eq!(ean8_digits("96385075").unwrap_err(), "Dígito verificador do EAN-8 \"96385075\" deveria ser 4");
+        assert_eq!(upca_digits("036000291450").unwrap_err(), "Dígito verificador do UPC-A \"036000291450\" deveria ser 2");
+        assert_eq!(itf14_digits("15400141288767").unwrap_err(), "Dígito verificador do ITF-14 \"15400141288767\" deveria ser 3");
+        assert_eq!(ean13_digits("7891234567890").unwrap_err(), "Dígito verificador do EAN-13 \"7891234567890\" deveria ser 5");
+    }
+
+    #[test]
+    fn tamanho_e_caracteres_invalidos_sao_recusados() {
+        assert_eq!(ean8_digits("963850").unwrap_err(), "EAN-8 deve ter 7 ou 8 dígitos (recebido 6): \"963850\"");
+        assert_eq!(ean8_digits("9638S07").unwrap_err(), "EAN-8 aceita apenas dígitos: \"9638S07\"");
+        assert!(code128_data("").is_err());
+        assert!(code128_data(&"A".repeat(81)).is_err());
+        assert_eq!(code128_data("Aço").unwrap_err(), "Code 128 não aceita o caractere 'ç' em \"Aço\"");
+    }
+
+    #[test]
+    fn code128_com_verificador_modulo_103() {
+        // Start B (104) + P*1 + J*2 + J*3 + 1*4 + 2*5 + 3*6 + C*7 = 879; 879 % 103 = 55 (311321)
+        let modules = code128_modules("PJJ123C").unwrap();
+
+        assert_eq!(modules.len(), 11 * 9 + 13);
+        assert_eq!(modules[..11], bits("11010010000")); // Start B
+        assert_eq!(modules[11 * 8..11 * 9], bits("11101000110")); // Verificador 55
+        assert_eq!(modules[11 * 9..], bits("1100011101011")); // Stop
+    }
+
+    #[test]
+    fn quantidade_de_modulos_dos_codigos_lineares() {
+        assert_eq!(ean13_modules("7891234567895").unwrap().len(), EAN13_MODULES as usize);
+        assert_eq!(ean8_modules("9638507").unwrap().len(), EAN8_MODULES as usize);
+        assert_eq!(upca_modules("03600029145").unwrap().len(), EAN13_MODULES as usize);
+        // Início (4) + 7 pares (cada dígito com 2 largas e 3 finas) + fim (razão 3 + 2)
+        assert_eq!(itf14_modules("1540014128876", 3).unwrap().len(), 4 + 7 * (4 * 3 + 6) + 5);
+    }
+
+    #[test]
+    fn codigos_2d_acima_da_capacidade_sao_recusados() {
+        assert!(validate(Symbology::Qr, &"x".repeat(QR_MAX_BYTES)).is_ok());
+        assert_eq!(
+            validate(Symbology::Qr, &"x".repeat(QR_MAX_BYTES + 1)).unwrap_err(),
+            "QR Code aceita até 2331 bytes (recebido 2332)"
+        );
+        assert_eq!(
+            validate(Symbology::DataMatrix, &"x".repeat(DATAMATRIX_MAX_BYTES + 1)).unwrap_err(),
+            "DataMatrix aceita até 1556 bytes (recebido 1557)"
+        );
+        assert_eq!(validate(Symbology::Qr, "").unwrap_err(), "QR Code sem dados");
+    }
+
+    #[test]
+    fn lado_do_menor_simbolo_2d() {
+        assert_eq!(side_2d(Symbology::Qr, &"x".repeat(14)), Some(21));
+        assert_eq!(side_2d(Symbology::Qr, &"x".repeat(15)), Some(25));
+        assert_eq!(side_2d(Symbology::Qr, &"x".repeat(QR_MAX_BYTES)), Some(177));
+        // Seis dígitos ocupam três palavras: cabem no 10 x 10
+        assert_eq!(side_2d(Symbology::DataMatrix, "123456"), Some(10));
+        assert_eq!(side_2d(Symbology::DataMatrix, "ABCD"), Some(12));
+    }
+}
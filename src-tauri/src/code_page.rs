@@ -0,0 +1,253 @@
+// Páginas de código das impressoras. Os textos do sistema são UTF-8, mas a impressora
+// recebe um byte por caractere na página de código selecionada no início do trabalho.
+
+// Caracteres 0x80 a 0xFF da página 850 (DOS Latin-1)
+const CP850_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅ\
+                          ÉæÆôöòûùÿÖÜø£Ø×ƒ\
+                          áíóúñÑªº¿®¬½¼¡«»\
+                          ░▒▓│┤ÁÂÀ©╣║╗╝¢¥┐\
+                          └┴┬├─┼ãÃ╚╔╩╦╠═╬¤\
+                          ðÐÊËÈıÍÎÏ┘┌█▄¦Ì▀\
+                          ÓßÔÒõÕµþÞÚÛÙýÝ¯´\
+                          \u{AD}±‗¾¶§÷¸°¨·¹³²■\u{A0}";
+
+// Caracteres 0x80 a 0x9F da página 1252 (o restante é igual ao ISO-8859-1); '\0' = posição sem caractere
+const CP1252_C1: [char; 32] = [
+    '€', '\0', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\0', 'Ž', '\0',
+    '\0', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\0', 'ž', 'Ÿ',
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CodePage {
+    Cp850,
+    Cp1252,
+    Iso8859_1,
+}
+
+impl CodePage {
+    pub fn parse(value: &str) -> Result<CodePage, String> {
+        match value.to_lowercase().replace('_', "-").as_str() {
+            "cp850" | "850" | "ibm850" => Ok(CodePage::Cp850),
+            "cp1252" | "1252" | "windows-1252" => Ok(CodePage::Cp1252),
+            "iso-8859-1" | "8859-1" | "latin1" | "latin-1" => Ok(CodePage::Iso8859_1),
+            _ => Err(format!(
+                "Página de código desconhecida: {} (use cp850, cp1252 ou iso-8859-1)",
+                value
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CodePage::Cp850 => "CP850",
+            CodePage::Cp1252 => "CP1252",
+            CodePage::Iso8859_1 => "ISO-8859-1",
+        }
+    }
+
+    // Tabela de 8 bits do comando I do PPLA/PPLB: 1 = DOS 850, A = Windows 1252.
+    // Não há tabela ISO-8859-1; a 1252 tem os mesmos caracteres imprimíveis.
+    pub fn epl_table(&self) -> &'static str {
+        match self {
+            CodePage::Cp850 => "1",
+            CodePage::Cp1252 | CodePage::Iso8859_1 => "A",
+        }
+    }
+
+    pub fn from_epl_table(table: &str) -> Option<CodePage> {
+        match table.trim() {
+            "1" => Some(CodePage::Cp850),
+            "A" | "a" => Some(CodePage::Cp1252),
+            _ => None,
+        }
+    }
+
+    // Número do ^CI do ZPL: 13 = Zebra 850, 27 = Zebra 1252 (também usado para ISO-8859-1)
+    pub fn zpl_ci(&self) -> u8 {
+        match self {
+            CodePage::Cp850 => 13,
+            CodePage::Cp1252 | CodePage::Iso8859_1 => 27,
+        }
+    }
+
+    // Parâmetro do comando CODEPAGE do TSPL
+    pub fn tspl_name(&self) -> &'static str {
+        match self {
+            CodePage::Cp850 => "850",
+            CodePage::Cp1252 => "1252",
+            CodePage::Iso8859_1 => "8859-1",
+        }
+    }
+
+    // Byte do caractere nesta página, se existir
+    pub fn encode_char(&self, c: char) -> Option<u8> {
+        if c.is_ascii() {
+            return Some(c as u8);
+        }
+
+        match self {
+            CodePage::Cp850 => CP850_HIGH.chars().position(|high| high == c).map(|index| 0x80 + index as u8),
+            CodePage::Cp1252 => match CP1252_C1.iter().position(|high| *high == c) {
+                Some(index) => Some(0x80 + index as u8),
+                None => latin1(c).filter(|byte| *byte >= 0xA0),
+            },
+            CodePage::Iso8859_1 => latin1(c).filter(|byte| *byte >= 0xA0),
+        }
+    }
+
+    // Caractere de um byte nesta página
+    pub fn decode_byte(&self, byte: u8) -> char {
+        if byte < 0x80 {
+            return byte as char;
+        }
+
+        match self {
+            CodePage::Cp850 => CP850_HIGH.chars().nth((byte - 0x80) as usize).unwrap_or('?'),
+            CodePage::Cp1252 if byte < 0xA0 => match CP1252_C1[(byte - 0x80) as usize] {
+                '\0' => '?',
+                c => c,
+            },
+            _ => byte as char,
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| self.decode_byte(*byte)).collect()
+    }
+}
+
+fn latin1(c: char) -> Option<u8> {
+    u8::try_from(c as u32).ok()
+}
+
+// O que enviar no lugar de um caractere que não existe na página de código
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fallback {
+    StripAccents, // Letra sem acento, ou "?" se ainda assim não existir
+    QuestionMark, // Sempre "?"
+}
+
+impl Fallback {
+    pub fn parse(value: &str) -> Result<Fallback, String> {
+        match value.to_lowercase().as_str() {
+            "strip" | "strip_accents" | "remover_acentos" => Ok(Fallback::StripAccents),
+            "question" | "question_mark" | "?" => Ok(Fallback::QuestionMark),
+            _ => Err(format!(
+                "Substituição de caracteres desconhecida: {} (use strip ou question)",
+                value
+            )),
+        }
+    }
+}
+
+// Letra sem acento e pontuação tipográfica em ASCII
+pub fn base_char(c: char) -> char {
+    match c {
+        'á' | 'à' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'Á' | 'À' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'É' | 'È' | 'Ê' | 'Ë' => 'E',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'Í' | 'Ì' | 'Î' | 'Ï' => 'I',
+        'ó' | 'ò' | 'ô' | 'õ' | 'ö' => 'o',
+        'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => 'O',
+        'ú' | 'ù' | 'û' | 'ü' => 'u',
+        'Ú' | 'Ù' | 'Û' | 'Ü' => 'U',
+        'ç' => 'c',
+        'Ç' => 'C',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ª' => 'a',
+        'º' | '°' => 'o',
+        '–' | '—' => '-',
+        '‘' | '’' | '´' => '\'',
+        _ => c,
+    }
+}
+
+// Texto convertido para a página de código, com os caracteres que precisaram ser substituídos
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcoded {
+    pub bytes: Vec<u8>,
+    pub replaced: Vec<char>,
+}
+
+pub fn transcode(text: &str, page: CodePage, fallback: Fallback) -> Transcoded {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut replaced = Vec::new();
+
+    for c in text.chars() {
+        match page.encode_char(c) {
+            Some(byte) => bytes.push(byte),
+            None => {
+                let substitute = match fallback {
+                    Fallback::StripAccents => page.encode_char(base_char(c)).unwrap_or(b'?'),
+                    Fallback::QuestionMark => b'?',
+                };
+                bytes.push(substitute);
+                if !replaced.contains(&c) {
+                    replaced.push(c);
+                }
+            }
+        }
+    }
+
+    Transcoded { bytes, replaced }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Palavras do cadastro e os bytes esperados em cada página (CP850: ç = 0x87, ã = 0xC6;
+    // CP1252 e ISO-8859-1: ç = 0xE7, ã = 0xE3)
+    const WORDS: [(&str, &[u8], &[u8]); 3] = [
+        ("Conexão", b"Conex\xC6o", b"Conex\xE3o"),
+        ("Aço", b"A\x87o", b"A\xE7o"),
+        ("Niquelação", b"Niquela\x87\xC6o", b"Niquela\xE7\xE3o"),
+    ];
+
+    #[test]
+    fn acentos_viram_os_bytes_de_cada_pagina_e_voltam() {
+        for (word, cp850, latin1) in WORDS {
+            for (page, expected) in [(CodePage::Cp850, cp850), (CodePage::Cp1252, latin1), (CodePage::Iso8859_1, latin1)] {
+                let transcoded = transcode(word, page, Fallback::QuestionMark);
+
+                assert_eq!(transcoded.bytes, expected, "{} em {}", word, page.name());
+                assert!(transcoded.replaced.is_empty());
+                assert_eq!(page.decode(&transcoded.bytes), word, "{} em {}", word, page.name());
+            }
+        }
+    }
+
+    #[test]
+    fn euro_fora_da_cp850_nas_duas_substituicoes() {
+        // "€" não existe na CP850 nem tem letra base; o travessão vira "-" só ao remover acentos
+        let strip = transcode("€ 10 – Aço", CodePage::Cp850, Fallback::StripAccents);
+        assert_eq!(strip.bytes, b"? 10 - A\x87o");
+        assert_eq!(strip.replaced, ['€', '–']);
+
+        let question = transcode("€ 10 – Aço", CodePage::Cp850, Fallback::QuestionMark);
+        assert_eq!(question.bytes, b"? 10 ? A\x87o");
+        assert_eq!(question.replaced, ['€', '–']);
+    }
+
+    #[test]
+    fn euro_existe_na_cp1252() {
+        let transcoded = transcode("€", CodePage::Cp1252, Fallback::QuestionMark);
+
+        assert_eq!(transcoded.bytes, [0x80]);
+        assert!(transcoded.replaced.is_empty());
+        assert_eq!(transcode("€", CodePage::Iso8859_1, Fallback::StripAccents).bytes, b"?");
+    }
+
+    #[test]
+    fn nomes_das_paginas_e_das_substituicoes() {
+        assert_eq!(CodePage::parse("IBM850").unwrap(), CodePage::Cp850);
+        assert_eq!(CodePage::parse("windows_1252").unwrap(), CodePage::Cp1252);
+        assert_eq!(CodePage::parse("latin1").unwrap(), CodePage::Iso8859_1);
+        assert!(CodePage::parse("utf-8").is_err());
+        assert_eq!(Fallback::parse("remover_acentos").unwrap(), Fallback::StripAccents);
+        assert_eq!(Fallback::parse("?").unwrap(), Fallback::QuestionMark);
+    }
+}
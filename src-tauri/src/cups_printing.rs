@@ -0,0 +1,229 @@
+use std::ffi::OsStr;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::transport::PrinterTransport;
+
+// Executa o lpstat com saída em inglês, para não depender do idioma do sistema
+fn run_lpstat(lpstat: &OsStr, args: &[&str]) -> Result<std::process::Output, String> {
+    Command::new(lpstat)
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| format!("Erro ao executar lpstat (o CUPS está instalado?): {}", e))
+}
+
+// Lista as filas do CUPS
+pub fn list_cups_printers() -> Result<Vec<String>, String> {
+    list_printers_with(OsStr::new("lpstat"))
+}
+
+// Lista as filas com o lpstat indicado (os testes usam um lpstat falso)
+fn list_printers_with(lpstat: &OsStr) -> Result<Vec<String>, String> {
+    // "lpstat -e" lista um destino por linha (CUPS 2.x)
+    let output = run_lpstat(lpstat, &["-e"])?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect());
+    }
+
+    // Versões antigas do CUPS: "printer <fila> is idle.  enabled since ..."
+    let output = run_lpstat(lpstat, &["-p"])?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let printers: Vec<String> = stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("printer "))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .map(|name| name.to_string())
+        .collect();
+
+    if printers.is_empty() && !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // Sem nenhuma fila cadastrada o lpstat termina com erro
+        if stderr.contains("No destinations") {
+            return Ok(Vec::new());
+        }
+        return Err(format!("Falha ao listar impressoras do CUPS: {}", stderr.trim()));
+    }
+
+    Ok(printers)
+}
+
+// Envia um trabalho RAW para a fila do CUPS usando "lp -o raw"
+pub fn print_to_cups_printer(queue: &str, document_name: &str, data: &[u8]) -> Result<(), String> {
+    print_with(OsStr::new("lp"), queue, document_name, data)
+}
+
+// Envia com o lp indicado (os testes usam um lp falso)
+fn print_with(lp: &OsStr, queue: &str, document_name: &str, data: &[u8]) -> Result<(), String> {
+    let mut child = Command::new(lp)
+        .args(["-o", "raw", "-d", queue, "-t", document_name])
+        .env("LC_ALL", "C")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Erro ao executar lp (o CUPS está instalado?): {}", e))?;
+
+    // Os dados vão pela entrada padrão, que é fechada em seguida para o lp terminar
+    let written = match child.stdin.take() {
+        Some(mut stdin) => stdin
+            .write_all(data)
+            .map_err(|e| format!("Erro ao enviar dados para o lp: {}", e)),
+        None => Err("Erro ao abrir entrada do comando lp".to_string()),
+    };
+
+    // Sem os dados completos o trabalho não pode seguir: encerra o lp e recolhe o processo
+    if let Err(e) = written {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(e);
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Erro ao aguardar o comando lp: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Falha ao imprimir na fila '{}': {}",
+            queue,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // Ex.: "request id is Argox-12 (0 file(s))"
+    println!("CUPS: {}", String::from_utf8_lossy(&output.stdout).trim());
+    Ok(())
+}
+
+// Transporte pelo CUPS: cada envio vira um trabalho RAW na fila
+pub struct CupsTransport {
+    queue: String,
+    document_name: String,
+}
+
+impl CupsTransport {
+    pub fn new(queue: &str, document_name: &str) -> Self {
+        Self {
+            queue: queue.to_string(),
+            document_name: document_name.to_string(),
+        }
+    }
+}
+
+impl PrinterTransport for CupsTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        print_to_cups_printer(&self.queue, &self.document_name, data)
+    }
+
+    fn describe(&self) -> String {
+        format!("fila CUPS '{}'", self.queue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+
+    // Grava um script executável no diretório dos comandos falsos e devolve o caminho dele
+    fn write_script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn envia_os_bytes_pelo_lp_em_modo_raw() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().display();
+        let lp = write_script(
+            dir.path(),
+            "lp",
+            &format!(
+                "printf '%s\\n' \"$@\" > '{out}/args'\ncat > '{out}/stdin'\necho 'request id is Argox-12 (0 file(s))'"
+            ),
+        );
+
+        let data = b"N\r\nA10,10,0,3,1,1,N,\"A\\\"B\"\r\nP1\r\n\x00\xff";
+        print_with(lp.as_os_str(), "Argox_OS-2140", "Etiquetas", data).unwrap();
+
+        let args = fs::read_to_string(dir.path().join("args")).unwrap();
+        assert_eq!(args.lines().collect::<Vec<_>>(), ["-o", "raw", "-d", "Argox_OS-2140", "-t", "Etiquetas"]);
+        assert_eq!(fs::read(dir.path().join("stdin")).unwrap(), data);
+    }
+
+    #[test]
+    fn erro_do_lp_vira_mensagem_com_a_fila() {
+        let dir = tempfile::tempdir().unwrap();
+        let lp = write_script(dir.path(), "lp", "cat > /dev/null\necho 'lp: The printer or class does not exist.' >&2\nexit 1");
+
+        let error = print_with(lp.as_os_str(), "Inexistente", "Etiquetas", b"N\r\n").unwrap_err();
+
+        assert_eq!(error, "Falha ao imprimir na fila 'Inexistente': lp: The printer or class does not exist.");
+    }
+
+    #[test]
+    fn lp_que_fecha_a_entrada_e_encerrado_e_recolhido() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        // Fecha a entrada sem ler e fica esperando: a escrita falha com "broken pipe"
+        let lp = write_script(dir.path(), "lp", &format!("echo $$ > '{}'\nexec 0<&-\nsleep 30", pid_file.display()));
+
+        let data = vec![b'N'; 4 * 1024 * 1024];
+        let error = print_with(lp.as_os_str(), "Argox", "Etiquetas", &data).unwrap_err();
+
+        assert!(error.starts_with("Erro ao enviar dados para o lp"), "{}", error);
+        let pid: i32 = fs::read_to_string(&pid_file).unwrap().trim().parse().unwrap();
+        // O processo foi recolhido (wait): não existe mais nem como zumbi
+        assert_eq!(unsafe { libc::kill(pid, 0) }, -1);
+    }
+
+    #[test]
+    fn lp_inexistente_gera_erro() {
+        let error = print_with(OsStr::new("/nao/existe/lp"), "Argox", "Etiquetas", b"N\r\n").unwrap_err();
+
+        assert!(error.starts_with("Erro ao executar lp (o CUPS está instalado?)"), "{}", error);
+    }
+
+    #[test]
+    fn lista_as_filas_do_lpstat_e() {
+        let dir = tempfile::tempdir().unwrap();
+        let lpstat = write_script(dir.path(), "lpstat", "[ \"$1\" = \"-e\" ] || exit 2\nprintf 'Argox_OS-2140\\n\\nZebra_ZD220\\n'");
+
+        let printers = list_printers_with(lpstat.as_os_str()).unwrap();
+
+        assert_eq!(printers, ["Argox_OS-2140", "Zebra_ZD220"]);
+    }
+
+    #[test]
+    fn cups_antigo_usa_lpstat_p() {
+        let dir = tempfile::tempdir().unwrap();
+        let lpstat = write_script(
+            dir.path(),
+            "lpstat",
+            "if [ \"$1\" = \"-e\" ]; then echo 'lpstat: invalid option -- e' >&2; exit 1; fi\n\
+             echo 'printer Argox_OS-2140 is idle.  enabled since Mon 10 Mar 2025'\n\
+             echo 'printer Elgin_L42 disabled since Mon 10 Mar 2025 -'",
+        );
+
+        let printers = list_printers_with(lpstat.as_os_str()).unwrap();
+
+        assert_eq!(printers, ["Argox_OS-2140", "Elgin_L42"]);
+    }
+
+    #[test]
+    fn sem_filas_cadastradas_devolve_lista_vazia() {
+        let dir = tempfile::tempdir().unwrap();
+        let lpstat = write_script(dir.path(), "lpstat", "echo 'lpstat: No destinations added.' >&2\nexit 1");
+
+        assert_eq!(list_printers_with(lpstat.as_os_str()), Ok(Vec::new()));
+    }
+}
@@ -0,0 +1,174 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::transport::{PrintJobInfo, PrinterTransport};
+use crate::PrinterConfig;
+
+// Nome da impressora virtual na lista de impressoras
+pub const VIRTUAL_PRINTER_NAME: &str = "Impressora Virtual (Arquivo)";
+
+// Pasta padrão dos arquivos capturados (relativa, como o products.db)
+pub const DEFAULT_CAPTURE_DIR: &str = "impressoes";
+
+// Arquivo JSON gravado ao lado de cada .prn
+#[derive(Debug, Serialize)]
+struct JobSidecar<'a> {
+    job_id: &'a str,
+    document_name: &'a str,
+    created_at: String,
+    product_ids: &'a [i64],
+    bytes: usize,
+    prn_file: String,
+    printer_config: &'a PrinterConfig,
+}
+
+// Impressora virtual: grava os bytes exatos de cada trabalho em um .prn com data e hora,
+// mais um .json com a configuração, os produtos e o id do trabalho, para reenvio posterior
+pub struct VirtualFilePrinter {
+    prn_path: PathBuf,
+    sidecar_path: PathBuf,
+    config: PrinterConfig,
+    job: PrintJobInfo,
+    bytes_written: usize,
+}
+
+impl VirtualFilePrinter {
+    pub fn new(config: &PrinterConfig, job: &PrintJobInfo) -> Result<Self, String> {
+        let dir = PathBuf::from(
+            config
+                .capture_dir
+                .as_deref()
+                .filter(|dir| !dir.trim().is_empty())
+                .unwrap_or(DEFAULT_CAPTURE_DIR),
+        );
+
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Erro ao criar pasta da impressora virtual '{}': {}", dir.display(), e))?;
+
+        // Ex.: "20250314-101500-123_Etiquetas"
+        let document: String = job
+            .document_name
+            .trim()
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        let stem = format!("{}_{}", job.job_id, document);
+
+        Ok(Self {
+            prn_path: dir.join(format!("{}.prn", stem)),
+            sidecar_path: dir.join(format!("{}.json", stem)),
+            config: config.clone(),
+            job: job.clone(),
+            bytes_written: 0,
+        })
+    }
+
+    fn write_sidecar(&self) -> Result<(), String> {
+        let sidecar = JobSidecar {
+            job_id: &self.job.job_id,
+            document_name: &self.job.document_name,
+            created_at: chrono::Local::now().to_rfc3339(),
+            product_ids: &self.job.product_ids,
+            bytes: self.bytes_written,
+            prn_file: self.prn_path.display().to_string(),
+            printer_config: &self.config,
+        };
+
+        let json = serde_json::to_string_pretty(&sidecar)
+            .map_err(|e| format!("Erro ao gerar JSON do trabalho: {}", e))?;
+
+        fs::write(&self.sidecar_path, json)
+            .map_err(|e| format!("Erro ao gravar '{}': {}", self.sidecar_path.display(), e))
+    }
+}
+
+impl PrinterTransport for VirtualFilePrinter {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.prn_path)
+            .map_err(|e| format!("Erro ao abrir '{}': {}", self.prn_path.display(), e))?;
+
+        file.write_all(data)
+            .map_err(|e| format!("Erro ao gravar '{}': {}", self.prn_path.display(), e))?;
+        self.bytes_written += data.len();
+
+        self.write_sidecar()?;
+        println!("Trabalho {} gravado em '{}'", self.job.job_id, self.prn_path.display());
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        format!("impressora virtual ('{}')", self.prn_path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &std::path::Path) -> PrinterConfig {
+        PrinterConfig {
+            capture_dir: Some(dir.display().to_string()),
+            ..PrinterConfig::default()
+        }
+    }
+
+    fn job(document_name: &str) -> PrintJobInfo {
+        PrintJobInfo {
+            job_id: "20250314-101500-123".to_string(),
+            document_name: document_name.to_string(),
+            product_ids: vec![7, 42],
+        }
+    }
+
+    #[test]
+    fn grava_os_bytes_no_prn_com_o_id_do_trabalho() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut printer = VirtualFilePrinter::new(&config(dir.path()), &job("Etiquetas")).unwrap();
+
+        printer.send(b"N\r\nA10,10,0,2,1,1,N,\"\x87\"\r\n").unwrap();
+        printer.send(b"P1\r\n").unwrap();
+
+        let prn = dir.path().join("20250314-101500-123_Etiquetas.prn");
+        assert_eq!(fs::read(prn).unwrap(), b"N\r\nA10,10,0,2,1,1,N,\"\x87\"\r\nP1\r\n");
+    }
+
+    #[test]
+    fn json_ao_lado_traz_trabalho_produtos_e_configuracao() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path());
+        let mut printer = VirtualFilePrinter::new(&config, &job("Etiquetas")).unwrap();
+
+        printer.send(b"N\r\nP1\r\n").unwrap();
+
+        let json = fs::read_to_string(dir.path().join("20250314-101500-123_Etiquetas.json")).unwrap();
+        let sidecar: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(sidecar["job_id"], "20250314-101500-123");
+        assert_eq!(sidecar["product_ids"], serde_json::json!([7, 42]));
+        assert_eq!(sidecar["bytes"], 7);
+        assert_eq!(sidecar["printer_config"], serde_json::to_value(&config).unwrap());
+    }
+
+    #[test]
+    fn nome_do_documento_nao_sai_da_pasta() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut printer = VirtualFilePrinter::new(&config(dir.path()), &job(" ../Lote 1/2:*ação ")).unwrap();
+
+        printer.send(b"N\r\n").unwrap();
+
+        let mut files: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            ["20250314-101500-123____Lote_1_2__ação.json", "20250314-101500-123____Lote_1_2__ação.prn"]
+        );
+    }
+}
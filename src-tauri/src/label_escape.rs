@@ -0,0 +1,96 @@
+use crate::barcode::{self, Symbology};
+
+// Camada única de escape dos dados variáveis (nomes, códigos) colocados nos comandos gerados.
+// Um nome com aspas, barra invertida, CR/LF ou prefixos de comando não pode quebrar o trabalho
+// nem injetar comandos na impressora.
+
+// Caracteres de controle (CR, LF, TAB, ESC, STX...) viram espaço: nenhuma linguagem os aceita dentro de um campo
+fn printable(c: char) -> char {
+    if c.is_control() {
+        ' '
+    } else {
+        c
+    }
+}
+
+// Texto entre aspas do PPLA e do PPLB: aspas e barra invertida são escapadas com "\"
+pub fn epl_quoted(content: &str) -> String {
+    let mut result = String::with_capacity(content.len() + 2);
+    result.push('"');
+
+    for c in content.chars().map(printable) {
+        if c == '"' || c == '\\' {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+
+    result.push('"');
+    result
+}
+
+// Dados de um campo ZPL (^FD...^FS).
+// "^" e "~" iniciam comandos, então o campo usa ^FH e esses caracteres vão em hexadecimal ("_5E").
+pub fn zpl_field(content: &str) -> String {
+    let content: String = content.chars().map(printable).collect();
+
+    if !content.contains(['^', '~', '_']) {
+        return format!("^FD{}^FS", content);
+    }
+
+    let mut result = String::from("^FH_^FD");
+    for c in content.chars() {
+        match c {
+            '^' | '~' | '_' => result.push_str(&format!("_{:02X}", c as u32)),
+            _ => result.push(c),
+        }
+    }
+    result.push_str("^FS");
+    result
+}
+
+// Texto entre aspas do TSPL: aspas viram \["] (escape próprio do TSPL)
+pub fn tspl_quoted(content: &str) -> String {
+    let mut result = String::with_capacity(content.len() + 2);
+    result.push('"');
+
+    for c in content.chars().map(printable) {
+        if c == '"' {
+            result.push_str("\\[\"]");
+        } else {
+            result.push(c);
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+// Nome de gráfico gravado na impressora: só letras, dígitos, "_", "-" e "." (sem espaço, aspas ou prefixos)
+pub fn graphic_name(name: &str) -> Result<&str, String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+
+    if valid {
+        Ok(name)
+    } else {
+        Err(format!(
+            "Nome de gráfico inválido: \"{}\" (use letras, números, \"_\", \"-\" ou \".\")",
+            name
+        ))
+    }
+}
+
+// Dados de um código de barras: o símbolo não pode ser escapado, então dados inválidos são recusados.
+// O ITF-14 vai com o dígito verificador calculado; nos demais a impressora calcula (ou confere) o dígito.
+pub fn barcode_data(symbology: Symbology, content: &str) -> Result<String, String> {
+    let full = barcode::validate(symbology, content)?;
+
+    if symbology == Symbology::Itf14 {
+        Ok(full)
+    } else {
+        Ok(content.to_string())
+    }
+}
@@ -0,0 +1,205 @@
+use crate::barcode::{self, Symbology};
+use crate::label_template::TemplateElement;
+
+// Espaço em dots entre caracteres das fontes residentes (já incluído na largura da célula)
+pub const CHAR_SPACING: u32 = 2;
+
+// Distância em dots entre as barras e os dígitos impressos abaixo delas
+pub const HUMAN_READABLE_GAP: u32 = 2;
+
+// Fonte usada pela impressora para os dígitos abaixo das barras
+pub const HUMAN_READABLE_FONT: &str = "1";
+
+// Fontes residentes, da menor para a maior
+pub const FONTS: [&str; 5] = ["1", "2", "3", "4", "5"];
+
+// Medidas das fontes residentes 1 a 5 de uma linguagem, usadas no alinhamento e no ajuste dos textos
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontMetrics {
+    cells: [(u32, u32); 5], // Largura e altura em dots de cada caractere, já com o espaçamento
+    spacing: u32,           // Espaço depois de cada caractere, incluído na largura
+}
+
+// PPLA, PPLB e ZPL (que imita o tamanho das fontes do PPLA com a ^A0)
+pub const PPLA_FONTS: FontMetrics = FontMetrics {
+    cells: [(10, 12), (12, 16), (14, 20), (16, 24), (34, 48)],
+    spacing: CHAR_SPACING,
+};
+
+// TSPL: células de tamanho fixo, sem espaço extra entre caracteres
+pub const TSPL_FONTS: FontMetrics = FontMetrics {
+    cells: [(8, 12), (12, 20), (16, 24), (24, 32), (32, 48)],
+    spacing: 0,
+};
+
+impl FontMetrics {
+    // Tamanho (largura, altura) em dots de cada caractere da fonte
+    pub fn cell(&self, font: &str) -> Option<(u32, u32)> {
+        FONTS.iter().position(|name| *name == font).map(|index| self.cells[index])
+    }
+
+    // Largura e altura ocupadas por um texto, sem o espaçamento depois do último caractere
+    pub fn text_size(&self, font: &str, h_mult: u32, v_mult: u32, content: &str) -> Result<(u32, u32), String> {
+        let (cell_width, cell_height) = self.cell(font).ok_or_else(|| format!("Fonte inválida: {}", font))?;
+        let columns = content.chars().count() as u32;
+
+        Ok(((columns * cell_width).saturating_sub(self.spacing) * h_mult, cell_height * v_mult))
+    }
+
+    // Largura e altura ocupadas por um código de barras, incluindo os dígitos legíveis.
+    // A largura sai da codificação dos dados (sem zona de silêncio); códigos 2D são quadrados.
+    pub fn barcode_size(
+        &self,
+        symbology: &str,
+        narrow: u32,
+        wide: u32,
+        height: u32,
+        human_readable: bool,
+        content: &str,
+    ) -> Result<(u32, u32), String> {
+        let symbology = Symbology::parse(symbology)?;
+
+        if symbology.is_2d() {
+            let side = barcode::side_2d(symbology, content)
+                .ok_or_else(|| format!("Dados demais para o código 2D ({} bytes)", content.len()))?;
+            return Ok((side * narrow, side * narrow));
+        }
+
+        let modules = barcode::modules(symbology, content, barcode::wide_ratio(narrow, wide))?.unwrap_or_default();
+        let width = modules.len() as u32 * narrow;
+
+        if human_readable {
+            let (_, digits_height) = self.cell(HUMAN_READABLE_FONT).unwrap_or((0, 0));
+            Ok((width, height + HUMAN_READABLE_GAP + digits_height))
+        } else {
+            Ok((width, height))
+        }
+    }
+
+    // Largura e altura do elemento sem rotação (somente textos e códigos de barras têm tamanho calculado)
+    pub fn element_size(&self, element: &TemplateElement<u32>) -> Result<Option<(u32, u32)>, String> {
+        match element {
+            TemplateElement::Text { font, h_mult, v_mult, content, .. } => {
+                self.text_size(font, *h_mult, *v_mult, content).map(Some)
+            }
+            TemplateElement::Barcode { symbology, narrow, wide, height, human_readable, content, .. } => {
+                self.barcode_size(symbology, *narrow, *wide, *height, *human_readable, content).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+// Tamanho (largura, altura) em dots de cada caractere das fontes residentes 1 a 5 do PPLA,
+// já com o espaçamento entre caracteres
+pub fn font_cell(font: &str) -> Option<(u32, u32)> {
+    PPLA_FONTS.cell(font)
+}
+
+// Fonte e multiplicadores (1 a 8) com o tamanho mais próximo do texto original ampliado por `factor`.
+// As fontes residentes têm o mesmo tamanho em dots em qualquer resolução e os multiplicadores
+// são inteiros, então numa mudança de resolução a fonte pode mudar. Vale a altura mais próxima,
+// depois a largura mais próxima e, no empate, a fonte menos ampliada.
+pub fn scaled_font(font: &str, h_mult: u32, v_mult: u32, factor: f64) -> Option<(&'static str, u32, u32)> {
+    let (width, height) = font_cell(font)?;
+    let target_width = (width * h_mult) as f64 * factor;
+    let target_height = (height * v_mult) as f64 * factor;
+
+    FONTS
+        .iter()
+        .filter_map(|candidate| {
+            let (cell_width, cell_height) = font_cell(candidate)?;
+            let h_mult = ((target_width / cell_width as f64).round() as u32).clamp(1, 8);
+            let v_mult = ((target_height / cell_height as f64).round() as u32).clamp(1, 8);
+            let height_error = (cell_height as f64 * v_mult as f64 - target_height).abs();
+            let width_error = (cell_width as f64 * h_mult as f64 - target_width).abs();
+            Some((height_error, width_error, h_mult + v_mult, *candidate, h_mult, v_mult))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)).then(a.2.cmp(&b.2)))
+        .map(|(_, _, _, candidate, h_mult, v_mult)| (candidate, h_mult, v_mult))
+}
+
+// Área ocupada por um elemento rotacionado, relativa à origem do comando
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub left: i64, // Deslocamento da borda esquerda em relação ao X da origem
+    pub top: i64,  // Deslocamento da borda de cima em relação ao Y da origem
+    pub width: u32,
+    pub height: u32,
+}
+
+// A impressora gira o elemento em torno da origem (sentido horário), então em 90° o texto
+// cresce para baixo e à esquerda da origem, em 180° para cima e à esquerda etc.
+pub fn rotated_bounds(rotation: u16, width: u32, height: u32) -> Bounds {
+    // Último dot ocupado em cada direção
+    let (last_x, last_y) = ((width as i64 - 1).max(0), (height as i64 - 1).max(0));
+
+    match rotation {
+        90 => Bounds { left: -last_y, top: 0, width: height, height: width },
+        180 => Bounds { left: -last_x, top: -last_y, width, height },
+        270 => Bounds { left: 0, top: -last_x, width: height, height: width },
+        _ => Bounds { left: 0, top: 0, width, height },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tamanho_do_texto_sem_o_espaco_depois_do_ultimo_caractere() {
+        assert_eq!(PPLA_FONTS.text_size("3", 1, 1, "ESTRELA METAIS").unwrap(), (194, 20));
+        assert_eq!(PPLA_FONTS.text_size("2", 2, 3, "ARR-001").unwrap(), (164, 48));
+        assert_eq!(PPLA_FONTS.text_size("1", 1, 1, "").unwrap(), (0, 12));
+        assert_eq!(PPLA_FONTS.text_size("9", 1, 1, "X").unwrap_err(), "Fonte inválida: 9");
+    }
+
+    #[test]
+    fn fontes_do_tspl_tem_celulas_proprias() {
+        // Fonte 3 do TSPL: 16 x 24, contra 14 x 20 (com o espaçamento) no PPLA
+        assert_eq!(TSPL_FONTS.cell("3"), Some((16, 24)));
+        assert_eq!(TSPL_FONTS.text_size("3", 1, 1, "ESTRELA METAIS").unwrap(), (224, 24));
+        assert_eq!(TSPL_FONTS.text_size("1", 2, 1, "ARR").unwrap(), (48, 12));
+        assert_eq!(TSPL_FONTS.cell("6"), None);
+    }
+
+    #[test]
+    fn tamanho_do_ean13_com_os_digitos() {
+        // 95 módulos de 2 dots; os dígitos somam o espaço e a altura da fonte 1
+        assert_eq!(PPLA_FONTS.barcode_size("ean13", 2, 6, 45, true, "7891234567895").unwrap(), (190, 45 + 2 + 12));
+        assert_eq!(PPLA_FONTS.barcode_size("ean13", 2, 6, 45, false, "7891234567895").unwrap(), (190, 45));
+    }
+
+    #[test]
+    fn fonte_mais_proxima_ao_mudar_de_resolucao() {
+        // Fonte 2 (12 x 16) a 300 dpi: 18 x 24. A fonte 4 1x1 (16 x 24) e a fonte 1 2x2 (20 x 24) erram
+        // igualmente; fica a menos ampliada
+        assert_eq!(scaled_font("2", 1, 1, 1.5), Some(("4", 1, 1)));
+        // E de volta a 203 dpi: 10,7 x 16 -> fonte 2
+        assert_eq!(scaled_font("4", 1, 1, 2.0 / 3.0), Some(("2", 1, 1)));
+        // Fonte 5 (34 x 48) a 300 dpi: 51 x 72 -> fonte 1 ampliada 5 x 6 (50 x 72)
+        assert_eq!(scaled_font("5", 1, 1, 1.5), Some(("1", 5, 6)));
+        assert_eq!(scaled_font("3", 2, 2, 1.0), Some(("3", 2, 2)));
+    }
+
+    #[test]
+    fn sem_mudanca_de_resolucao_a_fonte_se_mantem() {
+        assert_eq!(scaled_font("1", 2, 2, 1.0), Some(("1", 2, 2)));
+        // Os multiplicadores ficam entre 1 e 8
+        assert_eq!(scaled_font("5", 8, 8, 1.5), Some(("5", 8, 8)));
+        assert_eq!(scaled_font("9", 1, 1, 1.5), None);
+    }
+
+    #[test]
+    fn area_girada_em_torno_da_origem() {
+        assert_eq!(rotated_bounds(0, 82, 16), Bounds { left: 0, top: 0, width: 82, height: 16 });
+        assert_eq!(rotated_bounds(90, 82, 16), Bounds { left: -15, top: 0, width: 16, height: 82 });
+        assert_eq!(rotated_bounds(180, 82, 16), Bounds { left: -81, top: -15, width: 82, height: 16 });
+        assert_eq!(rotated_bounds(270, 82, 16), Bounds { left: 0, top: -81, width: 16, height: 82 });
+    }
+
+    #[test]
+    fn area_vazia_nao_sai_da_origem() {
+        assert_eq!(rotated_bounds(180, 0, 0), Bounds { left: 0, top: 0, width: 0, height: 0 });
+    }
+}
@@ -0,0 +1,216 @@
+use base64::Engine;
+use serde::Serialize;
+
+use crate::ppla_emulator::{self, Bitmap, EmulatorSetup};
+use crate::ppla_parser::PplaDiagnostic;
+
+// Formato da imagem de pré-visualização
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreviewFormat {
+    Png,
+    Svg,
+}
+
+impl PreviewFormat {
+    pub fn parse(value: &str) -> Result<PreviewFormat, String> {
+        match value.to_lowercase().as_str() {
+            "png" => Ok(PreviewFormat::Png),
+            "svg" => Ok(PreviewFormat::Svg),
+            _ => Err(format!("Formato de pré-visualização desconhecido: {} (use png ou svg)", value)),
+        }
+    }
+}
+
+// Imagem de uma etiqueta (um comando P) pronta para uso em <img src>
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewImage {
+    pub data_url: String,
+    pub width: u32,  // Em dots
+    pub height: u32, // Em dots
+    pub copies: u32,
+}
+
+// Pré-visualização de um fluxo de comandos
+#[derive(Debug, Clone, Serialize)]
+pub struct LabelPreview {
+    pub format: String,
+    pub dpi: u32,
+    pub images: Vec<PreviewImage>,
+    pub warnings: Vec<PplaDiagnostic>, // Elementos cortados e outros avisos do emulador
+    pub layout_warnings: Vec<String>,  // Textos reduzidos, quebrados ou cortados para caber
+}
+
+// PNG de 1 bit por dot (0 = preto)
+fn encode_png(bitmap: &Bitmap) -> Result<Vec<u8>, String> {
+    let row_bytes = bitmap.width.div_ceil(8) as usize;
+    let mut data = vec![0xFFu8; row_bytes * bitmap.height as usize];
+
+    for y in 0..bitmap.height {
+        for x in 0..bitmap.width {
+            if bitmap.get(x, y) {
+                data[y as usize * row_bytes + x as usize / 8] &= !(0x80 >> (x % 8));
+            }
+        }
+    }
+
+    let mut png = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut png, bitmap.width, bitmap.height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::One);
+
+        // Resolução física, para que a imagem tenha o tamanho real quando impressa
+        let dots_per_meter = (bitmap.dpi as f64 / 0.0254).round() as u32;
+        encoder.set_pixel_dims(Some(png::PixelDimensions {
+            xppu: dots_per_meter,
+            yppu: dots_per_meter,
+            unit: png::Unit::Meter,
+        }));
+
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| format!("Erro ao gerar PNG: {}", e))?;
+        writer
+            .write_image_data(&data)
+            .map_err(|e| format!("Erro ao gerar PNG: {}", e))?;
+    }
+
+    Ok(png)
+}
+
+// SVG com um retângulo por trecho horizontal de dots pretos
+fn encode_svg(bitmap: &Bitmap) -> String {
+    let mut path = String::new();
+
+    for y in 0..bitmap.height {
+        let mut x = 0;
+        while x < bitmap.width {
+            if !bitmap.get(x, y) {
+                x += 1;
+                continue;
+            }
+
+            let start = x;
+            while x < bitmap.width && bitmap.get(x, y) {
+                x += 1;
+            }
+            path.push_str(&format!("M{} {}h{}v1h-{}z", start, y, x - start, x - start));
+        }
+    }
+
+    let width_mm = bitmap.width as f64 * 25.4 / bitmap.dpi as f64;
+    let height_mm = bitmap.height as f64 * 25.4 / bitmap.dpi as f64;
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.2}mm\" height=\"{:.2}mm\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\
+         <rect width=\"{}\" height=\"{}\" fill=\"white\"/><path d=\"{}\" fill=\"black\"/></svg>",
+        width_mm, height_mm, bitmap.width, bitmap.height, bitmap.width, bitmap.height, path
+    )
+}
+
+fn data_url(bitmap: &Bitmap, format: PreviewFormat) -> Result<String, String> {
+    let engine = base64::engine::general_purpose::STANDARD;
+
+    match format {
+        PreviewFormat::Png => Ok(format!("data:image/png;base64,{}", engine.encode(encode_png(bitmap)?))),
+        PreviewFormat::Svg => Ok(format!("data:image/svg+xml;base64,{}", engine.encode(encode_svg(bitmap)))),
+    }
+}
+
+// Desenha o fluxo de comandos no emulador e gera uma imagem por etiqueta.
+// A imagem sai dos mesmos bytes enviados à impressora, com os mesmos cortes e sobreposições.
+pub fn render_preview(data: &[u8], setup: EmulatorSetup, format: PreviewFormat) -> Result<LabelPreview, String> {
+    let emulation = ppla_emulator::emulate_bytes(data, setup).map_err(|diagnostics| {
+        format!("Comandos inválidos: {}", ppla_emulator::describe_diagnostics(&diagnostics))
+    })?;
+
+    let images = emulation
+        .labels
+        .iter()
+        .map(|label| {
+            Ok(PreviewImage {
+                data_url: data_url(&label.bitmap, format)?,
+                width: label.bitmap.width,
+                height: label.bitmap.height,
+                copies: label.copies,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(LabelPreview {
+        format: match format {
+            PreviewFormat::Png => "png".to_string(),
+            PreviewFormat::Svg => "svg".to_string(),
+        },
+        dpi: setup.dpi,
+        images,
+        warnings: emulation.warnings,
+        layout_warnings: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETUP: EmulatorSetup = EmulatorSetup { width: 840, height: 176, dpi: 203 };
+
+    // Etiqueta de 16 x 8 dots com um retângulo preto de 3 x 2 dots em (2, 1)
+    const SMALL_LABEL: &[u8] = b"N\r\nq16\r\nQ8,0\r\nLO2,1,3,2\r\nP1,2\r\n";
+
+    fn decode_data_url(data_url: &str, prefix: &str) -> Vec<u8> {
+        let encoded = data_url.strip_prefix(prefix).unwrap();
+        base64::engine::general_purpose::STANDARD.decode(encoded).unwrap()
+    }
+
+    #[test]
+    fn png_tem_assinatura_tamanho_e_dots_da_etiqueta() {
+        let preview = render_preview(SMALL_LABEL, SETUP, PreviewFormat::Png).unwrap();
+
+        assert_eq!(preview.format, "png");
+        assert_eq!(preview.images.len(), 1);
+        let image = &preview.images[0];
+        assert_eq!((image.width, image.height, image.copies), (16, 8, 2));
+
+        let png = decode_data_url(&image.data_url, "data:image/png;base64,");
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let mut reader = png::Decoder::new(std::io::Cursor::new(png)).read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height, info.bit_depth), (16, 8, png::BitDepth::One));
+        assert_eq!(info.pixel_dims.map(|dims| dims.xppu), Some(7992)); // 203 dpi em dots por metro
+
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        // Duas linhas por 16 dots: bits 0 (pretos) nas colunas 2 a 4 das linhas 1 e 2
+        assert_eq!(pixels, [0xFF, 0xFF, 0xC7, 0xFF, 0xC7, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn svg_tem_um_trecho_por_linha_de_dots_pretos() {
+        let preview = render_preview(SMALL_LABEL, SETUP, PreviewFormat::Svg).unwrap();
+
+        let svg = String::from_utf8(decode_data_url(&preview.images[0].data_url, "data:image/svg+xml;base64,")).unwrap();
+        assert_eq!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"2.00mm\" height=\"1.00mm\" viewBox=\"0 0 16 8\" shape-rendering=\"crispEdges\">\
+             <rect width=\"16\" height=\"8\" fill=\"white\"/><path d=\"M2 1h3v1h-3zM2 2h3v1h-3z\" fill=\"black\"/></svg>"
+        );
+    }
+
+    #[test]
+    fn comandos_invalidos_nao_geram_imagem() {
+        let error = render_preview(b"N\r\nK9\r\nP1\r\n", SETUP, PreviewFormat::Png).unwrap_err();
+
+        assert_eq!(error, "Comandos inválidos: linha 2, byte 3: Comando desconhecido em \"K9\"");
+    }
+
+    #[test]
+    fn avisos_do_emulador_acompanham_a_imagem() {
+        let preview = render_preview(b"N\r\nq16\r\nQ8,0\r\nLO10,0,10,1\r\nP1\r\n", SETUP, PreviewFormat::Svg).unwrap();
+
+        assert_eq!(preview.dpi, 203);
+        assert_eq!(preview.warnings.len(), 1);
+        assert_eq!(preview.warnings[0].message, "Elemento ultrapassa a área da etiqueta e será cortado");
+    }
+}
@@ -0,0 +1,556 @@
+use std::ops::RangeInclusive;
+
+use crate::code_page::{self, CodePage, Fallback};
+use crate::label_metrics::{FontMetrics, PPLA_FONTS};
+use crate::label_template::{LabelTemplate, TemplateElement};
+use crate::media_profile::Media;
+use crate::ppla_label::PplaWriter;
+use crate::pplb_label::PplbWriter;
+use crate::resolution::Resolution;
+use crate::tspl_label::TsplWriter;
+use crate::zpl_label::ZplWriter;
+use crate::{PrinterConfig, Product};
+
+// Faixas de densidade e velocidade aceitas pelas impressoras suportadas
+pub const DARKNESS_RANGE: RangeInclusive<u8> = 1..=15;
+pub const SPEED_RANGE: RangeInclusive<u8> = 1..=4;
+
+// Maior quantidade de cópias de um formulário: limite do P do PPLA/PPLB, o menor entre P, ^PQ e PRINT
+pub const MAX_COPIES: u32 = 9999;
+
+// Largura da cabeça de impressão: 108 mm (4,25 polegadas, Argox OS-2140 e similares),
+// 864 dots a 203 dpi e 1296 a 300 dpi
+pub const PRINTHEAD_WIDTH_MM: f64 = 108.0;
+
+// Linguagem de comandos da impressora
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LabelLanguage {
+    Ppla, // Argox PPLA (layout original)
+    Pplb, // Argox PPLB / Eltron EPL2
+    Zpl,  // Zebra ZPL II
+    Tspl, // TSC / Elgin TSPL
+}
+
+impl LabelLanguage {
+    pub fn parse(value: &str) -> Result<LabelLanguage, String> {
+        match value.to_lowercase().as_str() {
+            "ppla" => Ok(LabelLanguage::Ppla),
+            "pplb" | "epl" | "epl2" => Ok(LabelLanguage::Pplb),
+            "zpl" | "zpl2" | "zplii" => Ok(LabelLanguage::Zpl),
+            "tspl" | "tspl2" => Ok(LabelLanguage::Tspl),
+            _ => Err(format!("Linguagem de impressora desconhecida: {} (use ppla, pplb, zpl ou tspl)", value)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LabelLanguage::Ppla => "PPLA",
+            LabelLanguage::Pplb => "PPLB",
+            LabelLanguage::Zpl => "ZPL",
+            LabelLanguage::Tspl => "TSPL",
+        }
+    }
+
+    fn writer(&self) -> Box<dyn LabelWriter> {
+        match self {
+            LabelLanguage::Ppla => Box::new(PplaWriter),
+            LabelLanguage::Pplb => Box::new(PplbWriter),
+            LabelLanguage::Zpl => Box::new(ZplWriter),
+            LabelLanguage::Tspl => Box::new(TsplWriter),
+        }
+    }
+}
+
+// Gerador de comandos de uma linguagem.
+// Recebe os elementos já posicionados (coordenadas absolutas) e com os campos preenchidos.
+pub trait LabelWriter {
+    // Inicialização do formulário (tamanho da etiqueta, gap, sensor, densidade e velocidade)
+    fn begin(&self, out: &mut Vec<u8>, media: &Media, config: &PrinterConfig) -> Result<(), String>;
+
+    // Seleção da página de código usada pelos textos
+    fn select_code_page(&self, out: &mut Vec<u8>, page: CodePage) -> Result<(), String>;
+
+    // Comandos de um elemento
+    fn element(&self, out: &mut Vec<u8>, element: &TemplateElement<u32>) -> Result<(), String>;
+
+    // Comando de impressão do formulário, repetido `copies` vezes pela própria impressora
+    fn end(&self, out: &mut Vec<u8>, template: &LabelTemplate<u32>, copies: u32) -> Result<(), String>;
+
+    // Medidas das fontes residentes, usadas para alinhar e ajustar os textos
+    fn fonts(&self) -> &'static FontMetrics {
+        &PPLA_FONTS
+    }
+}
+
+// Fileira de etiquetas (uma posição por coluna do modelo) impressa `copies` vezes
+#[derive(Debug, Clone)]
+pub struct LabelRow<'a> {
+    pub products: Vec<Option<&'a Product>>,
+    pub copies: u32,
+}
+
+// Fileiras de uma lista de posições: uma fileira por grupo de colunas, cada uma impressa uma vez.
+// Posições vazias (None) ficam em branco e fileiras sem nenhum produto são puladas.
+pub fn rows_from_positions(columns: usize, products: &[Option<Product>]) -> Vec<LabelRow<'_>> {
+    products
+        .chunks(columns.max(1))
+        .filter(|row| row.iter().any(Option::is_some))
+        .map(|row| LabelRow {
+            products: row.iter().map(Option::as_ref).collect(),
+            copies: 1,
+        })
+        .collect()
+}
+
+// Fileiras para imprimir cada produto na quantidade pedida.
+// As fileiras completas de um mesmo produto viram um único formulário com cópias; as sobras de
+// todos os produtos são agrupadas no fim, em fileiras mistas, para não desperdiçar etiquetas.
+pub fn rows_from_quantities(columns: usize, items: &[(Product, u32)]) -> Vec<LabelRow<'_>> {
+    let columns = columns.max(1);
+    let mut rows = Vec::new();
+    let mut remainder = Vec::new();
+
+    for (product, quantity) in items {
+        let full_rows = *quantity / columns as u32;
+        if full_rows > 0 {
+            rows.push(LabelRow {
+                products: vec![Some(product); columns],
+                copies: full_rows,
+            });
+        }
+        for _ in 0..*quantity % columns as u32 {
+            remainder.push(Some(product));
+        }
+    }
+
+    for row in remainder.chunks(columns) {
+        rows.push(LabelRow {
+            products: row.to_vec(),
+            copies: 1,
+        });
+    }
+
+    rows
+}
+
+// Confere resolução, densidade, velocidade e tamanho da etiqueta contra o que a impressora aceita
+pub fn validate_printer_config(language: LabelLanguage, config: &PrinterConfig) -> Result<(), String> {
+    let resolution = Resolution::of_printer(config)?;
+    validate_darkness_and_speed(config)?;
+    validate_label_size(language, resolution, config.width, config.height)
+}
+
+fn validate_darkness_and_speed(config: &PrinterConfig) -> Result<(), String> {
+    if !DARKNESS_RANGE.contains(&config.darkness) {
+        return Err(format!(
+            "Densidade {} fora da faixa aceita pela impressora ({} a {})",
+            config.darkness,
+            DARKNESS_RANGE.start(),
+            DARKNESS_RANGE.end()
+        ));
+    }
+    if !SPEED_RANGE.contains(&config.speed) {
+        return Err(format!(
+            "Velocidade {} fora da faixa aceita pela impressora ({} a {})",
+            config.speed,
+            SPEED_RANGE.start(),
+            SPEED_RANGE.end()
+        ));
+    }
+    Ok(())
+}
+
+// Largura da fita e altura da etiqueta, da configuração ou do perfil de mídia
+fn validate_label_size(language: LabelLanguage, resolution: Resolution, width: u32, height: u32) -> Result<(), String> {
+    if width == 0 || height == 0 {
+        return Err("A largura e a altura da etiqueta devem ser maiores que zero".to_string());
+    }
+
+    let printhead_width = resolution.dots(PRINTHEAD_WIDTH_MM);
+    if width > printhead_width {
+        return Err(format!(
+            "Largura de {} dots maior que a cabeça de impressão das impressoras {} ({} dots a {} dpi, {} mm)",
+            width,
+            language.name(),
+            printhead_width,
+            resolution.dpi(),
+            PRINTHEAD_WIDTH_MM
+        ));
+    }
+    Ok(())
+}
+
+// Comandos de um lote e os avisos do ajuste dos textos (nomes reduzidos, quebrados ou cortados)
+#[derive(Debug, Clone)]
+pub struct LabelBatch {
+    pub data: Vec<u8>,
+    pub warnings: Vec<String>,
+}
+
+// Gera o conteúdo de um lote de etiquetas a partir do modelo (sem acessar impressora nem banco).
+// A lista é dividida em fileiras com uma posição por coluna do modelo (ver rows_from_positions).
+// O modelo e a mídia vêm de media_profile::resolve (colunas e tamanho do perfil de mídia, se houver);
+// o modelo é convertido aqui de mm para os dots da mídia.
+pub fn build_label_batch(
+    language: LabelLanguage,
+    template: &LabelTemplate,
+    media: &Media,
+    config: &PrinterConfig,
+    products: &[Option<Product>],
+) -> Result<LabelBatch, String> {
+    build_label_rows(language, template, media, config, &rows_from_positions(template.column_x.len(), products))
+}
+
+// Gera o conteúdo das fileiras; cada fileira é um formulário impresso separadamente
+pub fn build_label_rows(
+    language: LabelLanguage,
+    template: &LabelTemplate,
+    media: &Media,
+    config: &PrinterConfig,
+    rows: &[LabelRow],
+) -> Result<LabelBatch, String> {
+    validate_darkness_and_speed(config)?;
+    validate_label_size(language, media.resolution, media.width, media.height)?;
+
+    let writer = language.writer();
+    let page = CodePage::parse(&config.code_page)?;
+    let fallback = Fallback::parse(&config.code_page_fallback)?;
+    let mut label_content = Vec::new();
+    let mut warnings = Vec::new();
+
+    // Medidas do modelo (mm) -> dots da impressora
+    let template = &template.in_dots(media.resolution, &mut warnings);
+
+    // O tamanho da etiqueta vem da configuração ou do perfil de mídia; um modelo maior seria cortado
+    if template.width > media.width || template.height > media.height {
+        warnings.push(format!(
+            "O modelo \"{}\" ({} x {} dots) é maior que a etiqueta configurada ({} x {} dots)",
+            template.name, template.width, template.height, media.width, media.height
+        ));
+    }
+
+    for row in rows {
+        if row.copies == 0 || row.copies > MAX_COPIES {
+            return Err(format!("Quantidade de cópias inválida: {} (use de 1 a {})", row.copies, MAX_COPIES));
+        }
+
+        writer.begin(&mut label_content, media, config)?;
+        writer.select_code_page(&mut label_content, page)?;
+
+        for (column_x, product) in template.column_x.iter().zip(&row.products) {
+            let product = match product {
+                Some(product) => product,
+                None => continue,
+            };
+
+            for element in &template.elements {
+                let mut element_warnings = Vec::new();
+                for placed in element.placed(template, writer.fonts(), (*column_x, media.top), product, &mut element_warnings)? {
+                    writer.element(&mut label_content, &placed)?;
+                }
+                warnings.extend(
+                    element_warnings
+                        .into_iter()
+                        .map(|warning| format!("Produto {}: {}", product.product_code, warning)),
+                );
+            }
+        }
+
+        writer.end(&mut label_content, template, row.copies)?;
+    }
+
+    if label_content.is_empty() {
+        return Err("Nenhum produto para imprimir no lote".to_string());
+    }
+
+    // Os comandos são montados em UTF-8; a impressora recebe um byte por caractere
+    let text = String::from_utf8(label_content).map_err(|e| format!("Erro ao converter textos da etiqueta: {}", e))?;
+    let transcoded = code_page::transcode(&text, page, fallback);
+    if !transcoded.replaced.is_empty() {
+        let replaced: Vec<String> = transcoded.replaced.iter().map(|c| format!("'{}'", c)).collect();
+        warnings.push(format!(
+            "Caracteres sem correspondência na página de código {} foram substituídos: {}",
+            page.name(),
+            replaced.join(", ")
+        ));
+    }
+
+    Ok(LabelBatch {
+        data: transcoded.bytes,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::label_template::{HorizontalAlign, VerticalAlign};
+    use crate::media_profile::{self, MediaProfile};
+    use crate::ppla_emulator::{self, Bitmap, EmulatorSetup};
+    use crate::test_support::{build, product};
+
+    const LANGUAGES: [LabelLanguage; 4] = [LabelLanguage::Ppla, LabelLanguage::Pplb, LabelLanguage::Zpl, LabelLanguage::Tspl];
+
+    // Nome com aspas, barra invertida, quebras de linha e comandos das quatro linguagens
+    const HOSTILE_NAME: &str = "Porca 1/4\" \\ M6\r\nP999\r\n^XA^FDx^FS~JR";
+
+    // Modelo padrão com um único texto: o nome, sem ajuste
+    fn name_template() -> LabelTemplate {
+        let mut template = LabelTemplate::default_template();
+        template.elements = vec![TemplateElement::Text {
+            x: 0.0,
+            y: 1.25,
+            rotation: 0,
+            font: "1".to_string(),
+            h_mult: 1,
+            v_mult: 1,
+            reverse: false,
+            align: HorizontalAlign::Left,
+            valign: VerticalAlign::Top,
+            fit: None,
+            content: "{product.name_short}".to_string(),
+        }];
+        template
+    }
+
+    fn lines(batch: &LabelBatch) -> Vec<String> {
+        String::from_utf8(batch.data.clone())
+            .unwrap()
+            .split(['\r', '\n'])
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    // Cada linguagem recebe o nome num único campo escapado e um único comando de impressão
+    #[test]
+    fn nome_hostil_fica_dentro_de_um_unico_campo() {
+        let expected = [
+            (LabelLanguage::Ppla, "A12,10,0,1,1,1,N,\"Porca 1/4\\\" \\\\ M6  P999  ^XA^FDx^FS~JR\"", "P", "P1"),
+            (LabelLanguage::Pplb, "A12,10,0,1,1,1,N,\"Porca 1/4\\\" \\\\ M6  P999  ^XA^FDx^FS~JR\"", "P", "P1"),
+            (LabelLanguage::Zpl, "^FO12,10^A0N,12,8^FH_^FDPorca 1/4\" \\ M6  P999  _5EXA_5EFDx_5EFS_7EJR^FS", "^PQ", "^PQ1"),
+            (LabelLanguage::Tspl, "TEXT 12,10,\"1\",0,1,1,\"Porca 1/4\\[\"] \\ M6  P999  ^XA^FDx^FS~JR\"", "PRINT", "PRINT 1,1"),
+        ];
+
+        for (language, field, print_prefix, print) in expected {
+            let batch = build(language, &name_template(), &[Some(product("PRC-006", HOSTILE_NAME, "7891234567895"))]);
+            let lines = lines(&batch);
+
+            let fields: Vec<&String> = lines.iter().filter(|line| line.contains("Porca")).collect();
+            assert_eq!(fields, [field], "{:?}", language);
+            let prints: Vec<&String> = lines.iter().filter(|line| line.starts_with(print_prefix)).collect();
+            assert_eq!(prints, [print], "{:?}", language);
+            assert!(
+                !lines.iter().any(|line| line.starts_with("P999") || line.starts_with("^FDx") || line.starts_with("~JR")),
+                "{:?}: {:?}",
+                language,
+                lines
+            );
+        }
+    }
+
+    #[test]
+    fn zpl_tem_um_unico_formulario() {
+        let batch = build(LabelLanguage::Zpl, &name_template(), &[Some(product("PRC-006", HOSTILE_NAME, "7891234567895"))]);
+        let text = String::from_utf8(batch.data).unwrap();
+
+        assert_eq!(text.matches("^XA").count(), 1);
+        assert_eq!(text.matches("^XZ").count(), 1);
+        assert!(!text.contains("~JR"));
+    }
+
+    #[test]
+    fn ppla_e_pplb_devolvem_o_nome_intacto_ao_interpretar() {
+        for language in [LabelLanguage::Ppla, LabelLanguage::Pplb] {
+            let batch = build(language, &name_template(), &[Some(product("PRC-006", HOSTILE_NAME, "7891234567895"))]);
+
+            let texts: Vec<String> = crate::ppla_parser::parse(&batch.data)
+                .unwrap()
+                .into_iter()
+                .filter_map(|parsed| match parsed.command {
+                    crate::ppla_parser::PplaCommand::Text { data, .. } => Some(data),
+                    _ => None,
+                })
+                .collect();
+
+            // Só o CR/LF vira espaço
+            assert_eq!(texts, ["Porca 1/4\" \\ M6  P999  ^XA^FDx^FS~JR"], "{:?}", language);
+        }
+    }
+
+    // Produtos (código, quantidade) de cada fileira e as cópias
+    fn summary(rows: &[LabelRow]) -> Vec<(Vec<Option<String>>, u32)> {
+        rows.iter()
+            .map(|row| (row.products.iter().map(|p| p.map(|p| p.product_code.clone())).collect(), row.copies))
+            .collect()
+    }
+
+    fn item(code: &str) -> Product {
+        product(code, code, "7891234567895")
+    }
+
+    fn codes(codes: &[&str]) -> Vec<Option<String>> {
+        codes.iter().map(|code| Some(code.to_string())).collect()
+    }
+
+    #[test]
+    fn quantidade_menor_que_as_colunas_fica_numa_fileira_parcial() {
+        let items = [(item("A"), 2)];
+
+        assert_eq!(summary(&rows_from_quantities(3, &items)), [(codes(&["A", "A"]), 1)]);
+    }
+
+    #[test]
+    fn multiplo_exato_vira_um_formulario_com_copias() {
+        let items = [(item("A"), 9)];
+
+        assert_eq!(summary(&rows_from_quantities(3, &items)), [(codes(&["A", "A", "A"]), 3)]);
+    }
+
+    #[test]
+    fn sobras_de_produtos_diferentes_sao_agrupadas_no_fim() {
+        let items = [(item("A"), 4), (item("B"), 5), (item("C"), 1)];
+
+        assert_eq!(
+            summary(&rows_from_quantities(3, &items)),
+            [
+                (codes(&["A", "A", "A"]), 1),
+                (codes(&["B", "B", "B"]), 1),
+                (codes(&["A", "B", "B"]), 1),
+                (codes(&["C"]), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn uma_coluna_imprime_cada_produto_com_copias() {
+        let items = [(item("A"), 5), (item("B"), 1)];
+
+        assert_eq!(summary(&rows_from_quantities(1, &items)), [(codes(&["A"]), 5), (codes(&["B"]), 1)]);
+    }
+
+    #[test]
+    fn copias_acima_do_limite_sao_recusadas() {
+        let template = LabelTemplate::default_template();
+        let config = PrinterConfig::default();
+        let media = Media::from_config(&template, &config).unwrap();
+        let item = item("A");
+
+        for copies in [0, MAX_COPIES + 1] {
+            let rows = [LabelRow { products: vec![Some(&item)], copies }];
+            assert_eq!(
+                build_label_rows(LabelLanguage::Ppla, &template, &media, &config, &rows).unwrap_err(),
+                format!("Quantidade de cópias inválida: {} (use de 1 a 9999)", copies)
+            );
+        }
+
+        let rows = [LabelRow { products: vec![Some(&item)], copies: MAX_COPIES }];
+        let batch = build_label_rows(LabelLanguage::Ppla, &template, &media, &config, &rows).unwrap();
+        assert!(batch.data.ends_with(b"P9999\r\n"));
+    }
+
+    #[test]
+    fn codigo_de_barras_com_comandos_e_recusado() {
+        let mut template = LabelTemplate::default_template();
+        template.elements.retain(|element| matches!(element, TemplateElement::Barcode { .. }));
+        let mut hostile = product("PRC-006", "Porca", "7891234567895");
+        hostile.barcode = "789123456789\r\nP999".to_string();
+        let config = PrinterConfig::default();
+        let media = Media::from_config(&template, &config).unwrap();
+
+        for language in LANGUAGES {
+            assert!(build_label_batch(language, &template, &media, &config, &[Some(hostile.clone())]).is_err(), "{:?}", language);
+        }
+    }
+
+    // Posições e barras podem diferir em até um dot de 203 dpi. O tamanho dos textos pode diferir em até 15%:
+    // as fontes residentes têm tamanho fixo em dots e os multiplicadores são inteiros (ver label_metrics::scaled_font)
+    const POSITION_TOLERANCE_MM: f64 = 0.125;
+    const TEXT_SIZE_TOLERANCE: f64 = 0.15;
+
+    // Modelo padrão no rolo de 3 colunas, com um produto, desenhado pelo emulador do PPLA
+    fn default_label(dpi: u32) -> Bitmap {
+        let profile = &MediaProfile::default_profiles()[0];
+        let config = PrinterConfig { dpi, ..PrinterConfig::default() };
+        let (template, media) = media_profile::resolve(Some(profile), &LabelTemplate::default_template(), &config).unwrap();
+        let products = [Some(product("ARR-001", "Arruela lisa 1/4", "7891234567895"))];
+        let batch = build_label_batch(LabelLanguage::Ppla, &template, &media, &config, &products).unwrap();
+
+        let setup = EmulatorSetup { width: media.width, height: media.height, dpi };
+        ppla_emulator::emulate_bytes(&batch.data, setup).unwrap().labels.remove(0).bitmap
+    }
+
+    // Caixa [esquerda, topo, direita, base] em mm dos dots pretos entre as alturas top e bottom (mm)
+    fn black_box_mm(bitmap: &Bitmap, top: f64, bottom: f64) -> [f64; 4] {
+        let resolution = Resolution::new(bitmap.dpi).unwrap();
+        let mut bounds = [u32::MAX, u32::MAX, 0, 0];
+
+        for y in resolution.dots(top)..resolution.dots(bottom) {
+            for x in (0..bitmap.width).filter(|x| bitmap.get(*x, y)) {
+                bounds = [bounds[0].min(x), bounds[1].min(y), bounds[2].max(x + 1), bounds[3].max(y + 1)];
+            }
+        }
+
+        bounds.map(|dots| resolution.mm(dots))
+    }
+
+    // Largura em mm da barra mais fina cruzada pela linha na altura y (mm)
+    fn narrowest_bar_mm(bitmap: &Bitmap, y: f64) -> f64 {
+        let resolution = Resolution::new(bitmap.dpi).unwrap();
+        let y = resolution.dots(y);
+        let mut narrowest = u32::MAX;
+        let mut run = 0;
+
+        for x in 0..=bitmap.width {
+            if bitmap.get(x, y) {
+                run += 1;
+            } else if run > 0 {
+                narrowest = narrowest.min(run);
+                run = 0;
+            }
+        }
+
+        resolution.mm(narrowest)
+    }
+
+    fn assert_close(at_203: f64, at_300: f64, tolerance: f64, what: &str) {
+        assert!(
+            (at_203 - at_300).abs() <= tolerance + 1e-9,
+            "{}: {:.3}mm a 203 dpi e {:.3}mm a 300 dpi",
+            what,
+            at_203,
+            at_300
+        );
+    }
+
+    #[test]
+    fn modelo_padrao_sai_com_o_mesmo_tamanho_fisico_a_203_e_300_dpi() {
+        let (low, high) = (default_label(203), default_label(300));
+        let label_mm = |bitmap: &Bitmap| {
+            let resolution = Resolution::new(bitmap.dpi).unwrap();
+            (resolution.mm(bitmap.width), resolution.mm(bitmap.height))
+        };
+        assert_eq!(label_mm(&low), (105.0, 22.0));
+        assert_eq!(label_mm(&high), (105.0, 22.0));
+
+        // Faixas de altura de cada texto do modelo padrão (Y dos elementos em mm)
+        for (top, bottom) in [(1.875, 5.625), (5.625, 8.75), (8.75, 11.875)] {
+            let (a, b) = (black_box_mm(&low, top, bottom), black_box_mm(&high, top, bottom));
+            let what = format!("texto em {}mm", top);
+
+            assert_close(a[1], b[1], POSITION_TOLERANCE_MM, &format!("{}, topo", what));
+            assert_close((a[0] + a[2]) / 2.0, (b[0] + b[2]) / 2.0, POSITION_TOLERANCE_MM, &format!("{}, centro", what));
+            let (width, height) = ((b[2] - b[0]) / (a[2] - a[0]), (b[3] - b[1]) / (a[3] - a[1]));
+            assert!((width - 1.0).abs() <= TEXT_SIZE_TOLERANCE, "{}: largura {:.0}% da de 203 dpi", what, width * 100.0);
+            assert!((height - 1.0).abs() <= TEXT_SIZE_TOLERANCE, "{}: altura {:.0}% da de 203 dpi", what, height * 100.0);
+        }
+
+        // EAN-13 (só as barras, sem os dígitos): mesma área e barra fina de 0,25mm nas duas resoluções
+        let (a, b) = (black_box_mm(&low, 11.875, 17.5), black_box_mm(&high, 11.875, 17.5));
+        for (side, (at_203, at_300)) in ["esquerda", "topo", "direita", "base"].iter().zip(a.into_iter().zip(b)) {
+            assert_close(at_203, at_300, POSITION_TOLERANCE_MM, &format!("barras, {}", side));
+        }
+        assert_close(narrowest_bar_mm(&low, 14.0), narrowest_bar_mm(&high, 14.0), POSITION_TOLERANCE_MM, "barra fina");
+        assert_eq!(narrowest_bar_mm(&low, 14.0), 0.25);
+    }
+}
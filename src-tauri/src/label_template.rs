@@ -0,0 +1,1185 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use crate::barcode::Symbology;
+use crate::label_metrics::{self, FontMetrics};
+use crate::resolution::Resolution;
+use crate::text_fit::{self, FitMode, TextFit};
+use crate::Product;
+
+// Nome do modelo criado automaticamente com o layout original das etiquetas
+pub const DEFAULT_TEMPLATE_NAME: &str = "Padrão 3 colunas (33mm)";
+
+// Alinhamento horizontal dentro da coluna
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HorizontalAlign {
+    #[default]
+    Left, // X é a distância até a borda esquerda da coluna
+    Center, // X desloca o elemento a partir do centro
+    Right, // X é a distância até a borda direita da coluna
+}
+
+impl HorizontalAlign {
+    // Posição da borda esquerda de um elemento de largura `size` numa área de largura `area`
+    fn start(&self, offset: u32, size: u32, area: u32) -> u32 {
+        match self {
+            HorizontalAlign::Left => offset,
+            HorizontalAlign::Center => area.saturating_sub(size) / 2 + offset,
+            HorizontalAlign::Right => area.saturating_sub(size + offset),
+        }
+    }
+}
+
+// Alinhamento vertical dentro da etiqueta
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerticalAlign {
+    #[default]
+    Top, // Y é a distância até o topo da etiqueta
+    Middle, // Y desloca o elemento a partir do meio
+    Bottom, // Y é a distância até a base da etiqueta
+}
+
+impl VerticalAlign {
+    fn start(&self, offset: u32, size: u32, area: u32) -> u32 {
+        match self {
+            VerticalAlign::Top => offset,
+            VerticalAlign::Middle => area.saturating_sub(size) / 2 + offset,
+            VerticalAlign::Bottom => area.saturating_sub(size + offset),
+        }
+    }
+}
+
+// Elemento de um modelo de etiqueta.
+// Posições e tamanhos em mm, relativos ao canto da coluna (TemplateElement<u32>: em dots da impressora,
+// ver in_dots); textos aceitam campos como "{product.name_short}". Fontes e multiplicadores são os de 203 dpi.
+// Textos e códigos de barras são alinhados pela área que ocupam, calculada com as métricas das fontes da linguagem.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateElement<U = f64> {
+    Text {
+        x: U,
+        y: U,
+        #[serde(default)]
+        rotation: u16, // Graus: 0, 90, 180 ou 270
+        font: String,  // Fonte da impressora ("1" a "5")
+        #[serde(default = "default_multiplier")]
+        h_mult: u32, // Multiplicador horizontal
+        #[serde(default = "default_multiplier")]
+        v_mult: u32, // Multiplicador vertical
+        #[serde(default)]
+        reverse: bool, // Texto invertido (branco no preto)
+        #[serde(default)]
+        align: HorizontalAlign,
+        #[serde(default)]
+        valign: VerticalAlign,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fit: Option<TextFit<U>>, // Ajuste quando o texto não cabe (sem valor: imprime como está)
+        content: String,
+    },
+    Barcode {
+        x: U,
+        y: U,
+        #[serde(default)]
+        rotation: u16,
+        symbology: String, // Tipo do código: ean13, ean8, upca, code128, itf14, qr ou datamatrix
+        narrow: U,         // Largura da barra fina (tamanho do módulo nos códigos 2D)
+        wide: U,           // Largura da barra larga
+        height: U,         // Altura das barras
+        #[serde(default = "default_true")]
+        human_readable: bool, // Imprime os dígitos abaixo das barras
+        #[serde(default)]
+        align: HorizontalAlign,
+        #[serde(default)]
+        valign: VerticalAlign,
+        content: String,
+    },
+    Line {
+        x: U,
+        y: U,
+        width: U,
+        height: U, // Espessura, para linhas horizontais
+    },
+    Box {
+        x: U,
+        y: U,
+        width: U,
+        height: U,
+        thickness: U,
+    },
+    Image {
+        x: U,
+        y: U,
+        name: String, // Nome do gráfico gravado na memória da impressora
+    },
+}
+
+fn default_multiplier() -> u32 {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// Modelo de etiqueta: tamanho da fita, colunas e elementos repetidos em cada coluna.
+// Medidas em mm, como nos perfis de mídia; LabelTemplate<u32> é o modelo em dots da impressora (ver in_dots).
+#[derive(Debug, Clone, Serialize)]
+pub struct LabelTemplate<U = f64> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub name: String,
+    pub description: Option<String>,
+    pub width: U,                // Largura da fita
+    pub height: U,               // Altura da etiqueta
+    pub gap: U,                  // Espaço entre etiquetas
+    pub column_x: Vec<U>,        // Posição X de cada coluna
+    pub column_width: Option<U>, // Largura de cada coluna (sem valor: distância entre colunas)
+    pub elements: Vec<TemplateElement<U>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+// Modelo como chega do frontend. Modelos feitos antes das medidas em mm trazem as medidas em dots
+// e a resolução em que foram feitos (dpi); são convertidos para mm na leitura.
+#[derive(Deserialize)]
+struct TemplateData {
+    id: Option<i64>,
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    width: f64,
+    height: f64,
+    gap: f64,
+    column_x: Vec<f64>,
+    #[serde(default)]
+    column_width: Option<f64>,
+    #[serde(default)]
+    dpi: Option<u32>,
+    elements: Vec<TemplateElement>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for LabelTemplate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = TemplateData::deserialize(deserializer)?;
+        let template = LabelTemplate {
+            id: data.id,
+            name: data.name,
+            description: data.description,
+            width: data.width,
+            height: data.height,
+            gap: data.gap,
+            column_x: data.column_x,
+            column_width: data.column_width,
+            elements: data.elements,
+            created_at: data.created_at,
+            updated_at: data.updated_at,
+        };
+
+        match data.dpi {
+            Some(dpi) => Ok(template.converted_from_dots(Resolution::new(dpi).map_err(de::Error::custom)?)),
+            None => Ok(template),
+        }
+    }
+}
+
+impl LabelTemplate {
+    // Layout original: fita de 105mm com 3 etiquetas de 33mm x 22mm
+    pub fn default_template() -> LabelTemplate {
+        // Margem esquerda de 1,5mm e 2mm entre as etiquetas
+        let column_x = vec![1.5, 1.5 + 33.0 + 2.0, 1.5 + 2.0 * (33.0 + 2.0)];
+
+        let text = |y: f64, font: &str, content: &str, fit: Option<TextFit>| TemplateElement::Text {
+            x: 0.0,
+            y,
+            rotation: 0,
+            font: font.to_string(),
+            h_mult: 1,
+            v_mult: 1,
+            reverse: false,
+            align: HorizontalAlign::Center,
+            valign: VerticalAlign::Top,
+            fit,
+            content: content.to_string(),
+        };
+
+        // Nomes longos passam para a fonte 1 e, se ainda assim não couberem, são cortados
+        let shrink = TextFit {
+            mode: FitMode::Shrink,
+            width: None,
+            max_lines: 1,
+            line_spacing: 0.0,
+            marker: "...".to_string(),
+        };
+
+        // As medidas são as do layout em dots de 203 dpi divididas por 8, para a saída a 203 dpi não mudar
+        LabelTemplate {
+            id: None,
+            name: DEFAULT_TEMPLATE_NAME.to_string(),
+            description: Some("Empresa, nome, código e EAN-13 em 3 colunas".to_string()),
+            width: 105.0,
+            height: 22.0,
+            gap: 3.125,
+            column_x,
+            column_width: Some(33.0),
+            elements: vec![
+                text(1.875, "3", "ESTRELA METAIS", None),
+                text(5.625, "2", "{product.name_short}", Some(shrink)),
+                text(8.75, "2", "{product.product_code}", None),
+                TemplateElement::Barcode {
+                    x: 0.0,
+                    y: 11.875,
+                    rotation: 0,
+                    symbology: "ean13".to_string(),
+                    narrow: 0.25,
+                    wide: 0.75,
+                    height: 5.625,
+                    human_readable: true,
+                    align: HorizontalAlign::Center,
+                    valign: VerticalAlign::Top,
+                    content: "{product.barcode}".to_string(),
+                },
+            ],
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("O modelo precisa de um nome".to_string());
+        }
+
+        if self.width <= 0.0 || self.height <= 0.0 {
+            return Err("Largura e altura do modelo devem ser maiores que zero".to_string());
+        }
+
+        if self.column_x.is_empty() {
+            return Err("O modelo precisa de pelo menos uma coluna".to_string());
+        }
+
+        if matches!(self.column_width, Some(width) if width <= 0.0) {
+            return Err("A largura da coluna deve ser maior que zero".to_string());
+        }
+
+        for (index, element) in self.elements.iter().enumerate() {
+            element
+                .validate()
+                .map_err(|e| format!("Elemento {}: {}", index + 1, e))?;
+        }
+
+        Ok(())
+    }
+
+    // Modelo nos dots da impressora. Cada medida é arredondada uma única vez, a partir dos mm,
+    // então o erro fica abaixo de meio dot em qualquer resolução.
+    pub fn in_dots(&self, resolution: Resolution, warnings: &mut Vec<String>) -> LabelTemplate<u32> {
+        let dots = |mm: f64| resolution.dots(mm);
+
+        LabelTemplate {
+            id: self.id,
+            name: self.name.clone(),
+            description: self.description.clone(),
+            width: dots(self.width),
+            height: dots(self.height),
+            gap: dots(self.gap),
+            column_x: self.column_x.iter().map(|x| dots(*x)).collect(),
+            column_width: self.column_width.map(dots),
+            elements: self
+                .elements
+                .iter()
+                .map(|element| element.in_dots(resolution, warnings))
+                .collect(),
+            created_at: self.created_at.clone(),
+            updated_at: self.updated_at.clone(),
+        }
+    }
+
+    // Modelo gravado em dots na resolução dada (antes das medidas em mm), com as medidas passadas para mm
+    fn converted_from_dots(self, resolution: Resolution) -> LabelTemplate {
+        let mm = |dots: f64| dots / resolution.dots_per_mm() as f64;
+
+        LabelTemplate {
+            width: mm(self.width),
+            height: mm(self.height),
+            gap: mm(self.gap),
+            column_x: self.column_x.iter().map(|x| mm(*x)).collect(),
+            column_width: self.column_width.map(mm),
+            elements: self
+                .elements
+                .iter()
+                .map(|element| element.converted_from_dots(resolution))
+                .collect(),
+            ..self
+        }
+    }
+}
+
+impl LabelTemplate<u32> {
+    // Largura da coluna usada no alinhamento
+    pub fn column_width(&self) -> u32 {
+        if let Some(width) = self.column_width {
+            return width;
+        }
+
+        match self.column_x.as_slice() {
+            [first, second, ..] => second.saturating_sub(*first),
+            [first] => self.width.saturating_sub(*first),
+            [] => self.width,
+        }
+    }
+}
+
+impl<U: Copy> TemplateElement<U> {
+    // Mesmo elemento com as medidas convertidas (mm <-> dots); fontes e multiplicadores não mudam
+    fn map_units<V>(&self, convert: impl Fn(U) -> V) -> TemplateElement<V> {
+        match self.clone() {
+            TemplateElement::Text { x, y, rotation, font, h_mult, v_mult, reverse, align, valign, fit, content } => TemplateElement::Text {
+                x: convert(x),
+                y: convert(y),
+                rotation,
+                font,
+                h_mult,
+                v_mult,
+                reverse,
+                align,
+                valign,
+                fit: fit.map(|fit| fit.map_units(&convert)),
+                content,
+            },
+            TemplateElement::Barcode { x, y, rotation, symbology, narrow, wide, height, human_readable, align, valign, content } => {
+                TemplateElement::Barcode {
+                    x: convert(x),
+                    y: convert(y),
+                    rotation,
+                    symbology,
+                    narrow: convert(narrow),
+                    wide: convert(wide),
+                    height: convert(height),
+                    human_readable,
+                    align,
+                    valign,
+                    content,
+                }
+            }
+            TemplateElement::Line { x, y, width, height } => TemplateElement::Line {
+                x: convert(x),
+                y: convert(y),
+                width: convert(width),
+                height: convert(height),
+            },
+            TemplateElement::Box { x, y, width, height, thickness } => TemplateElement::Box {
+                x: convert(x),
+                y: convert(y),
+                width: convert(width),
+                height: convert(height),
+                thickness: convert(thickness),
+            },
+            TemplateElement::Image { x, y, name } => TemplateElement::Image { x: convert(x), y: convert(y), name },
+        }
+    }
+}
+
+impl TemplateElement {
+    // Elemento nos dots da impressora. Barras, espessuras e fontes não podem sumir: ficam com pelo menos 1 dot,
+    // e fora de 203 dpi a fonte é trocada pela de tamanho mais próximo
+    fn in_dots(&self, resolution: Resolution, warnings: &mut Vec<String>) -> TemplateElement<u32> {
+        let mut element = self.map_units(|mm| resolution.dots(mm));
+        let reference = Resolution::default();
+
+        match &mut element {
+            TemplateElement::Text { font, h_mult, v_mult, .. } => {
+                if resolution != reference {
+                    (*font, *h_mult, *v_mult) = scale_font(font, *h_mult, *v_mult, reference, resolution, warnings);
+                }
+            }
+            TemplateElement::Barcode { narrow, wide, .. } => {
+                *narrow = (*narrow).max(1);
+                *wide = (*wide).max(1);
+            }
+            TemplateElement::Line { height, .. } => *height = (*height).max(1),
+            TemplateElement::Box { thickness, .. } => *thickness = (*thickness).max(1),
+            TemplateElement::Image { name, .. } => {
+                if resolution != reference {
+                    push_once(
+                        warnings,
+                        format!("O gráfico \"{}\" gravado na impressora não é redimensionado para {} dpi", name, resolution.dpi()),
+                    );
+                }
+            }
+        }
+
+        element
+    }
+
+    // Elemento gravado em dots na resolução dada, com as medidas em mm e a fonte equivalente a 203 dpi
+    fn converted_from_dots(&self, resolution: Resolution) -> TemplateElement {
+        let mut element = self.map_units(|dots| dots / resolution.dots_per_mm() as f64);
+
+        if let TemplateElement::Text { font, h_mult, v_mult, .. } = &mut element {
+            (*font, *h_mult, *v_mult) = scale_font(font, *h_mult, *v_mult, resolution, Resolution::default(), &mut Vec::new());
+        }
+
+        element
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            TemplateElement::Text { rotation, font, h_mult, v_mult, fit, content, .. } => {
+                validate_rotation(*rotation)?;
+                if !label_metrics::FONTS.contains(&font.as_str()) {
+                    return Err(format!("Fonte inválida: {} (use 1 a 5)", font));
+                }
+                if !(1..=8).contains(h_mult) || !(1..=8).contains(v_mult) {
+                    return Err("Multiplicadores de fonte devem estar entre 1 e 8".to_string());
+                }
+                if let Some(fit) = fit {
+                    fit.validate()?;
+                    if fit.mode == FitMode::Wrap && *rotation != 0 {
+                        return Err("Quebra de linha só é suportada em textos sem rotação".to_string());
+                    }
+                }
+                validate_bindings(content)
+            }
+            TemplateElement::Barcode { rotation, symbology, narrow, wide, height, content, .. } => {
+                validate_rotation(*rotation)?;
+                Symbology::parse(symbology)?;
+                if *narrow <= 0.0 || *wide <= 0.0 || *height <= 0.0 {
+                    return Err("Dimensões do código de barras devem ser maiores que zero".to_string());
+                }
+                validate_bindings(content)
+            }
+            TemplateElement::Line { width, height, .. } => {
+                if *width <= 0.0 || *height <= 0.0 {
+                    return Err("Dimensões da linha devem ser maiores que zero".to_string());
+                }
+                Ok(())
+            }
+            TemplateElement::Box { width, height, thickness, .. } => {
+                if *width <= 0.0 || *height <= 0.0 || *thickness <= 0.0 {
+                    return Err("Dimensões do retângulo devem ser maiores que zero".to_string());
+                }
+                Ok(())
+            }
+            TemplateElement::Image { name, .. } => {
+                if name.trim().is_empty() {
+                    return Err("Informe o nome do gráfico gravado na impressora".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl TemplateElement<u32> {
+    // Elemento posicionado na coluna cuja origem é `column_origin` (início da coluna e margem superior),
+    // com os campos do produto preenchidos. Textos são medidos com as fontes da linguagem (`fonts`).
+    // Um texto quebrado em várias linhas gera um elemento por linha; os ajustes de texto viram avisos.
+    pub fn placed(
+        &self,
+        template: &LabelTemplate<u32>,
+        fonts: &FontMetrics,
+        column_origin: (u32, u32),
+        product: &Product,
+        warnings: &mut Vec<String>,
+    ) -> Result<Vec<TemplateElement<u32>>, String> {
+        let mut element = self.clone();
+
+        if let TemplateElement::Text { content, .. } | TemplateElement::Barcode { content, .. } = &mut element {
+            *content = resolve_bindings(content, product)?;
+        }
+
+        let (lines, line_height) = element.fitted_lines(template, fonts, warnings)?;
+        let block_height = line_height * lines.len() as u32;
+
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut line)| {
+                let origin = line.aligned_origin(fonts, template.column_width(), template.height, block_height)?;
+
+                match &mut line {
+                    TemplateElement::Text { x, y, .. } | TemplateElement::Barcode { x, y, .. } => {
+                        if let Some((origin_x, origin_y)) = origin {
+                            *x = origin_x;
+                            *y = origin_y + index as u32 * line_height;
+                        }
+                        *x += column_origin.0;
+                        *y += column_origin.1;
+                    }
+                    TemplateElement::Line { x, y, .. } | TemplateElement::Box { x, y, .. } | TemplateElement::Image { x, y, .. } => {
+                        *x += column_origin.0;
+                        *y += column_origin.1;
+                    }
+                }
+
+                Ok(line)
+            })
+            .collect()
+    }
+
+    // Aplica o ajuste do texto; devolve as linhas e a altura de cada uma (0 para elementos de uma linha só)
+    fn fitted_lines(&self, template: &LabelTemplate<u32>, fonts: &FontMetrics, warnings: &mut Vec<String>) -> Result<(Vec<TemplateElement<u32>>, u32), String> {
+        let (x, y, rotation, font, h_mult, v_mult, fit, content) = match self {
+            TemplateElement::Text { x, y, rotation, font, h_mult, v_mult, fit: Some(fit), content, .. } => {
+                (*x, *y, *rotation, font, *h_mult, *v_mult, fit, content)
+            }
+            _ => return Ok((vec![self.clone()], 0)),
+        };
+
+        // Espaço até o fim da coluna, ou da etiqueta para textos na vertical
+        let available = match rotation {
+            90 | 270 => template.height.saturating_sub(y),
+            _ => template.column_width().saturating_sub(x),
+        };
+
+        let fitted = text_fit::fit_text(fonts, fit, font, h_mult, content, available);
+        if let Some(warning) = fitted.warning {
+            warnings.push(warning);
+        }
+
+        let (_, text_height) = fonts.text_size(&fitted.font, h_mult, v_mult, "")?;
+        let line_height = if fitted.lines.len() > 1 { text_height + fit.line_spacing } else { 0 };
+
+        let lines = fitted
+            .lines
+            .into_iter()
+            .map(|line| {
+                let mut element = self.clone();
+                if let TemplateElement::Text { font, fit, content, .. } = &mut element {
+                    *font = fitted.font.clone();
+                    *fit = None;
+                    *content = line;
+                }
+                element
+            })
+            .collect();
+
+        Ok((lines, line_height))
+    }
+
+    // Origem do comando (relativa à coluna) para que a área ocupada pelo elemento fique alinhada.
+    // Linhas de um texto quebrado são alinhadas verticalmente como um bloco de altura block_height.
+    fn aligned_origin(&self, fonts: &FontMetrics, column_width: u32, label_height: u32, block_height: u32) -> Result<Option<(u32, u32)>, String> {
+        let (x, y, rotation, align, valign) = match self {
+            TemplateElement::Text { x, y, rotation, align, valign, .. }
+            | TemplateElement::Barcode { x, y, rotation, align, valign, .. } => (*x, *y, *rotation, *align, *valign),
+            _ => return Ok(None),
+        };
+        let (width, height) = match fonts.element_size(self)? {
+            Some(size) => size,
+            None => return Ok(None),
+        };
+
+        let bounds = label_metrics::rotated_bounds(rotation, width, height.max(block_height));
+        let left = align.start(x, bounds.width, column_width) as i64;
+        let top = valign.start(y, bounds.height, label_height) as i64;
+
+        Ok(Some(((left - bounds.left).max(0) as u32, (top - bounds.top).max(0) as u32)))
+    }
+}
+
+// Fonte e multiplicadores na nova resolução (ver label_metrics::scaled_font); avisa quando a altura
+// do texto não consegue acompanhar a mudança de resolução
+fn scale_font(
+    font: &str,
+    h_mult: u32,
+    v_mult: u32,
+    source: Resolution,
+    target: Resolution,
+    warnings: &mut Vec<String>,
+) -> (String, u32, u32) {
+    let factor = target.factor(source);
+    let (scaled, scaled_h, scaled_v) = match label_metrics::scaled_font(font, h_mult, v_mult, factor) {
+        Some(scaled) => scaled,
+        None => return (font.to_string(), h_mult, v_mult), // Fonte inválida: recusada na validação
+    };
+
+    let height = |font: &str, v_mult: u32| label_metrics::font_cell(font).map_or(0, |(_, h)| h * v_mult) as f64;
+    let difference = height(scaled, scaled_v) / (height(font, v_mult) * factor) - 1.0;
+    if difference.abs() > 0.1 {
+        push_once(
+            warnings,
+            format!(
+                "Textos na fonte {} ({}x{}) saem {:.0}% {} a {} dpi (fonte {}, {}x{})",
+                font,
+                h_mult,
+                v_mult,
+                difference.abs() * 100.0,
+                if difference > 0.0 { "maiores" } else { "menores" },
+                target.dpi(),
+                scaled,
+                scaled_h,
+                scaled_v
+            ),
+        );
+    }
+
+    (scaled.to_string(), scaled_h, scaled_v)
+}
+
+fn push_once(warnings: &mut Vec<String>, warning: String) {
+    if !warnings.contains(&warning) {
+        warnings.push(warning);
+    }
+}
+
+fn validate_rotation(rotation: u16) -> Result<(), String> {
+    match rotation {
+        0 | 90 | 180 | 270 => Ok(()),
+        _ => Err(format!("Rotação inválida: {} (use 0, 90, 180 ou 270)", rotation)),
+    }
+}
+
+// Valor de um campo do produto, ex.: "product.name_short"
+fn product_field(product: &Product, field: &str) -> Result<String, String> {
+    match field {
+        "product.id" => Ok(product.id.map(|id| id.to_string()).unwrap_or_default()),
+        "product.product_code" => Ok(product.product_code.clone()),
+        "product.name" => Ok(product.name.clone()),
+        "product.name_short" => Ok(product.name_short.clone()),
+        "product.barcode" => Ok(product.barcode.clone()),
+        "product.description" => Ok(product.description.clone().unwrap_or_default()),
+        _ => Err(format!("Campo desconhecido: {{{}}}", field)),
+    }
+}
+
+// Substitui os campos "{product.xxx}" pelo valor do produto ("{{" e "}}" geram chaves literais)
+pub fn resolve_bindings(content: &str, product: &Product) -> Result<String, String> {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        result.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            result.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+
+        if tail.starts_with('}') {
+            return Err(format!("Chave '}}' sem abertura em \"{}\"", content));
+        }
+
+        let end = tail
+            .find('}')
+            .ok_or_else(|| format!("Campo sem fechamento em \"{}\"", content))?;
+        result.push_str(&product_field(product, tail[1..end].trim())?);
+        rest = &tail[end + 1..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+// Confere os campos usando um produto vazio
+fn validate_bindings(content: &str) -> Result<(), String> {
+    let product = Product {
+        id: None,
+        product_code: String::new(),
+        name: String::new(),
+        name_short: String::new(),
+        barcode: String::new(),
+        description: None,
+        created_at: None,
+        updated_at: None,
+    };
+
+    resolve_bindings(content, &product).map(|_| ())
+}
+
+// Cria a tabela de modelos e o modelo padrão, se ainda não existirem
+pub fn setup_table(conn: &Connection) -> Result<(), String> {
+    create_table(conn)?;
+    migrate_from_dots(conn)?;
+
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM label_templates", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    if count == 0 {
+        println!("Criando modelo de etiqueta padrão");
+        insert_template(conn, &LabelTemplate::default_template())?;
+    }
+
+    Ok(())
+}
+
+fn create_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS label_templates (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            width REAL NOT NULL,
+            height REAL NOT NULL,
+            gap REAL NOT NULL,
+            column_x TEXT NOT NULL,
+            column_width REAL,
+            elements TEXT NOT NULL,
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .map_err(|e| format!("Erro ao criar tabela de modelos: {}", e))?;
+
+    Ok(())
+}
+
+// Tabelas anteriores às medidas em mm guardam dots (colunas INTEGER) na resolução da coluna dpi.
+// A tabela é recriada com as medidas convertidas para mm, mantendo ids e datas.
+fn migrate_from_dots(conn: &Connection) -> Result<(), String> {
+    let width_type: String = conn
+        .query_row("SELECT type FROM pragma_table_info('label_templates') WHERE name = 'width'", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if !width_type.eq_ignore_ascii_case("INTEGER") {
+        return Ok(());
+    }
+
+    println!("Convertendo medidas dos modelos de etiqueta de dots para mm");
+    crate::add_column_if_missing(conn, "label_templates", "column_width", "INTEGER");
+    crate::add_column_if_missing(conn, "label_templates", "dpi", "INTEGER NOT NULL DEFAULT 203");
+
+    let templates = {
+        let mut stmt = conn
+            .prepare(&format!("SELECT {}, dpi FROM label_templates", TEMPLATE_COLUMNS))
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((template_from_row(row)?, row.get::<_, u32>(11)?)))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?
+    };
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("ALTER TABLE label_templates RENAME TO label_templates_dots", [])
+        .map_err(|e| format!("Erro ao converter tabela de modelos: {}", e))?;
+    create_table(&tx)?;
+
+    for (template, dpi) in templates {
+        // Só 203 e 300 dpi eram aceitos na gravação
+        let template = template.converted_from_dots(Resolution::new(dpi).unwrap_or_default());
+        tx.execute(
+            "INSERT INTO label_templates (id, name, description, width, height, gap, column_x, column_width, elements, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                template.id,
+                template.name,
+                template.description,
+                template.width,
+                template.height,
+                template.gap,
+                to_json(&template.column_x)?,
+                template.column_width,
+                to_json(&template.elements)?,
+                template.created_at,
+                template.updated_at,
+            ],
+        )
+        .map_err(|e| format!("Erro ao converter modelo \"{}\": {}", template.name, e))?;
+    }
+
+    tx.execute("DROP TABLE label_templates_dots", [])
+        .map_err(|e| format!("Erro ao converter tabela de modelos: {}", e))?;
+    tx.commit().map_err(|e| format!("Erro ao converter tabela de modelos: {}", e))
+}
+
+const TEMPLATE_COLUMNS: &str =
+    "id, name, description, width, height, gap, column_x, elements, created_at, updated_at, column_width";
+
+fn template_from_row(row: &Row) -> rusqlite::Result<LabelTemplate> {
+    let column_x: String = row.get(6)?;
+    let elements: String = row.get(7)?;
+
+    let from_json = |index: usize, e: serde_json::Error| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    };
+
+    Ok(LabelTemplate {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        description: row.get(2)?,
+        width: row.get(3)?,
+        height: row.get(4)?,
+        gap: row.get(5)?,
+        column_x: serde_json::from_str(&column_x).map_err(|e| from_json(6, e))?,
+        column_width: row.get(10)?,
+        elements: serde_json::from_str(&elements).map_err(|e| from_json(7, e))?,
+        created_at: row.get(8)?,
+        updated_at: row.get(9)?,
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| format!("Erro ao gerar JSON do modelo: {}", e))
+}
+
+pub fn list_templates(conn: &Connection) -> Result<Vec<LabelTemplate>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM label_templates ORDER BY id", TEMPLATE_COLUMNS))
+        .map_err(|e| e.to_string())?;
+
+    let templates = stmt
+        .query_map([], template_from_row)
+        .map_err(|e| e.to_string())?;
+
+    templates
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+pub fn get_template(conn: &Connection, id: i64) -> Result<LabelTemplate, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM label_templates WHERE id = ?", TEMPLATE_COLUMNS),
+        params![id],
+        template_from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Modelo de etiqueta {} não encontrado", id))
+}
+
+pub fn insert_template(conn: &Connection, template: &LabelTemplate) -> Result<LabelTemplate, String> {
+    template.validate()?;
+
+    conn.execute(
+        "INSERT INTO label_templates (name, description, width, height, gap, column_x, column_width, elements)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            template.name.trim(),
+            template.description,
+            template.width,
+            template.height,
+            template.gap,
+            to_json(&template.column_x)?,
+            template.column_width,
+            to_json(&template.elements)?,
+        ],
+    )
+    .map_err(|e| format!("Erro ao salvar modelo: {}", e))?;
+
+    get_template(conn, conn.last_insert_rowid())
+}
+
+pub fn update_template(conn: &Connection, id: i64, template: &LabelTemplate) -> Result<LabelTemplate, String> {
+    template.validate()?;
+
+    let changed = conn
+        .execute(
+            "UPDATE label_templates SET
+                name = ?,
+                description = ?,
+                width = ?,
+                height = ?,
+                gap = ?,
+                column_x = ?,
+                column_width = ?,
+                elements = ?,
+                updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+            params![
+                template.name.trim(),
+                template.description,
+                template.width,
+                template.height,
+                template.gap,
+                to_json(&template.column_x)?,
+                template.column_width,
+                to_json(&template.elements)?,
+                id
+            ],
+        )
+        .map_err(|e| format!("Erro ao atualizar modelo: {}", e))?;
+
+    if changed == 0 {
+        return Err(format!("Modelo de etiqueta {} não encontrado", id));
+    }
+
+    get_template(conn, id)
+}
+
+pub fn delete_template(conn: &Connection, id: i64) -> Result<(), String> {
+    let changed = conn
+        .execute("DELETE FROM label_templates WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+
+    if changed == 0 {
+        return Err(format!("Modelo de etiqueta {} não encontrado", id));
+    }
+
+    Ok(())
+}
+
+// Modelo escolhido ou, sem escolha, o padrão gravado no banco (que o usuário pode ter editado).
+// O padrão embutido só é usado se a linha do padrão não existir mais.
+pub fn load_template(conn: &Connection, id: Option<i64>) -> Result<LabelTemplate, String> {
+    match id {
+        Some(id) => get_template(conn, id),
+        None => conn
+            .query_row(
+                &format!("SELECT {} FROM label_templates WHERE name = ?", TEMPLATE_COLUMNS),
+                params![DEFAULT_TEMPLATE_NAME],
+                template_from_row,
+            )
+            .optional()
+            .map_err(|e| e.to_string())
+            .map(|template| template.unwrap_or_else(LabelTemplate::default_template)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn seeded_connection() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        setup_table(&conn).unwrap();
+        conn
+    }
+
+    fn seeded_default(conn: &Connection) -> LabelTemplate {
+        list_templates(conn)
+            .unwrap()
+            .into_iter()
+            .find(|template| template.name == DEFAULT_TEMPLATE_NAME)
+            .unwrap()
+    }
+
+    #[test]
+    fn sem_escolha_carrega_o_padrao_editado_no_banco() {
+        let conn = seeded_connection();
+        let mut edited = seeded_default(&conn);
+        edited.gap = 3.75;
+        edited.description = Some("Padrão ajustado na loja".to_string());
+        update_template(&conn, edited.id.unwrap(), &edited).unwrap();
+
+        let loaded = load_template(&conn, None).unwrap();
+
+        assert_eq!(loaded.id, edited.id);
+        assert_eq!(loaded.gap, 3.75);
+        assert_eq!(loaded.description.as_deref(), Some("Padrão ajustado na loja"));
+    }
+
+    #[test]
+    fn sem_a_linha_do_padrao_usa_o_modelo_embutido() {
+        let conn = seeded_connection();
+        let seeded = seeded_default(&conn);
+        delete_template(&conn, seeded.id.unwrap()).unwrap();
+
+        let loaded = load_template(&conn, None).unwrap();
+
+        assert_eq!(loaded.id, None);
+        assert_eq!(loaded.gap, LabelTemplate::default_template().gap);
+    }
+
+    // Modelo padrão em dots de 203 dpi
+    fn default_in_dots() -> LabelTemplate<u32> {
+        LabelTemplate::default_template().in_dots(Resolution::default(), &mut Vec::new())
+    }
+
+    fn product() -> Product {
+        test_support::product("ARR-001", "Arruela lisa 1/4", "7891234567895")
+    }
+
+    fn text(content: &str, rotation: u16, align: HorizontalAlign, valign: VerticalAlign) -> TemplateElement<u32> {
+        TemplateElement::Text {
+            x: 4,
+            y: 6,
+            rotation,
+            font: "2".to_string(),
+            h_mult: 1,
+            v_mult: 1,
+            reverse: false,
+            align,
+            valign,
+            fit: None,
+            content: content.to_string(),
+        }
+    }
+
+    // Posição (x, y) do elemento colocado na coluna
+    fn origin(template: &LabelTemplate<u32>, element: &TemplateElement<u32>, column: usize) -> (u32, u32) {
+        let placed = element
+            .placed(template, &label_metrics::PPLA_FONTS, (template.column_x[column], 0), &product(), &mut Vec::new())
+            .unwrap();
+        match &placed[0] {
+            TemplateElement::Text { x, y, .. } | TemplateElement::Barcode { x, y, .. } => (*x, *y),
+            other => panic!("elemento sem alinhamento: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn modelo_padrao_centraliza_cada_elemento_nas_tres_colunas() {
+        let template = default_in_dots();
+        // Larguras: "ESTRELA METAIS" na fonte 3 = 194, nome na fonte 2 = 190, código = 82, EAN-13 = 190
+        let expected_left = [35, 37, 91, 37];
+
+        for column in 0..3 {
+            let column_x = template.column_x[column];
+            for (element, left) in template.elements.iter().zip(expected_left) {
+                assert_eq!(origin(&template, element, column).0, column_x + left, "coluna {}: {:?}", column, element);
+            }
+        }
+    }
+
+    #[test]
+    fn alinhamento_a_direita_conta_o_x_a_partir_da_borda_direita() {
+        let template = default_in_dots();
+        let element = text("{product.product_code}", 0, HorizontalAlign::Right, VerticalAlign::Top);
+
+        // 264 - (82 + 4) = 178
+        assert_eq!(origin(&template, &element, 0), (12 + 178, 6));
+        assert_eq!(origin(&template, &element, 2), (572 + 178, 6));
+    }
+
+    #[test]
+    fn alinhamento_vertical_no_meio_e_na_base() {
+        let template = default_in_dots();
+
+        // Altura da fonte 2 = 16: (176 - 16) / 2 + 6 e 176 - (16 + 6)
+        assert_eq!(origin(&template, &text("X", 0, HorizontalAlign::Left, VerticalAlign::Middle), 0), (16, 86));
+        assert_eq!(origin(&template, &text("X", 0, HorizontalAlign::Left, VerticalAlign::Bottom), 0), (16, 154));
+    }
+
+    #[test]
+    fn texto_girado_e_alinhado_pela_area_que_ocupa() {
+        let template = default_in_dots();
+
+        // Em 90° o código ocupa 16 x 82 dots à esquerda da origem: a origem fica na borda direita
+        let rotated = text("{product.product_code}", 90, HorizontalAlign::Center, VerticalAlign::Top);
+        assert_eq!(origin(&template, &rotated, 0), (12 + (264 - 16) / 2 + 4 + 15, 6));
+
+        // Em 180° a origem é o canto de baixo à direita
+        let upside_down = text("{product.product_code}", 180, HorizontalAlign::Right, VerticalAlign::Bottom);
+        assert_eq!(origin(&template, &upside_down, 0), (12 + 264 - 4 - 1, 176 - 6 - 1));
+    }
+
+    #[test]
+    fn modelo_padrao_em_dots_a_203_e_300_dpi() {
+        let template = LabelTemplate::default_template();
+        let mut warnings = Vec::new();
+
+        let at_203 = template.in_dots(Resolution::default(), &mut warnings);
+        assert_eq!((at_203.width, at_203.height, at_203.gap), (840, 176, 25));
+        assert_eq!(at_203.column_x, [12, 292, 572]);
+        assert_eq!(at_203.column_width, Some(264));
+
+        let at_300 = template.in_dots(Resolution::new(300).unwrap(), &mut warnings);
+        assert_eq!((at_300.width, at_300.height, at_300.gap), (1260, 264, 38));
+        assert_eq!(at_300.column_x, [18, 438, 858]);
+        assert_eq!(at_300.column_width, Some(396));
+        match &at_300.elements[3] {
+            TemplateElement::Barcode { y, narrow, wide, height, .. } => assert_eq!((*y, *narrow, *wide, *height), (143, 3, 9, 68)),
+            other => panic!("esperado o EAN-13: {:?}", other),
+        }
+        assert!(warnings.is_empty(), "{:?}", warnings);
+    }
+
+    // Modelo padrão como era gravado antes das medidas em mm, a 300 dpi
+    const DOTS_TEMPLATE_300: &str = r#"{
+        "name": "Antigo 300 dpi", "width": 1260, "height": 264, "gap": 38,
+        "column_x": [18, 438, 858], "column_width": 396, "dpi": 300,
+        "elements": [
+            {"type": "text", "x": 0, "y": 23, "font": "4", "content": "ESTRELA METAIS", "align": "center"},
+            {"type": "barcode", "x": 0, "y": 143, "symbology": "ean13", "narrow": 3, "wide": 9, "height": 68, "content": "{product.barcode}"}
+        ]
+    }"#;
+
+    #[test]
+    fn modelo_em_dots_com_dpi_e_convertido_para_mm() {
+        let template: LabelTemplate = serde_json::from_str(DOTS_TEMPLATE_300).unwrap();
+
+        assert_eq!((template.width, template.height, template.gap), (105.0, 22.0, 38.0 / 12.0));
+        assert_eq!(template.column_x, [1.5, 36.5, 71.5]);
+        assert_eq!(template.column_width, Some(33.0));
+        match &template.elements[0] {
+            // A fonte 4 a 300 dpi (24 dots = 2mm) equivale à fonte 2 a 203 dpi (16 dots)
+            TemplateElement::Text { y, font, .. } => assert_eq!((*y, font.as_str()), (23.0 / 12.0, "2")),
+            other => panic!("esperado o texto: {:?}", other),
+        }
+        match &template.elements[1] {
+            TemplateElement::Barcode { narrow, wide, .. } => assert_eq!((*narrow, *wide), (0.25, 0.75)),
+            other => panic!("esperado o EAN-13: {:?}", other),
+        }
+
+        // Sem dpi as medidas já estão em mm
+        let json = serde_json::to_string(&template).unwrap();
+        let again: LabelTemplate = serde_json::from_str(&json).unwrap();
+        assert_eq!((again.width, again.column_x.clone()), (105.0, template.column_x.clone()));
+        assert!(!json.contains("\"dpi\""));
+    }
+
+    #[test]
+    fn tabela_em_dots_e_convertida_para_mm() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE label_templates (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT,
+                width INTEGER NOT NULL,
+                height INTEGER NOT NULL,
+                gap INTEGER NOT NULL,
+                column_x TEXT NOT NULL,
+                column_width INTEGER,
+                dpi INTEGER NOT NULL DEFAULT 203,
+                elements TEXT NOT NULL,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );
+            INSERT INTO label_templates (id, name, width, height, gap, column_x, column_width, dpi, elements)
+            VALUES (7, 'Antigo 300 dpi', 1260, 264, 38, '[18,438,858]', 396, 300,
+                '[{\"type\":\"barcode\",\"x\":0,\"y\":143,\"symbology\":\"ean13\",\"narrow\":3,\"wide\":9,\"height\":68,\"content\":\"{product.barcode}\"}]');
+            INSERT INTO label_templates (id, name, width, height, gap, column_x, elements)
+            VALUES (9, 'Antigo 203 dpi', 840, 176, 25, '[12,292,572]', '[]');",
+        )
+        .unwrap();
+
+        setup_table(&conn).unwrap();
+
+        let old_300 = get_template(&conn, 7).unwrap();
+        assert_eq!((old_300.width, old_300.height), (105.0, 22.0));
+        assert_eq!(old_300.column_x, [1.5, 36.5, 71.5]);
+        assert_eq!(
+            old_300.in_dots(Resolution::new(300).unwrap(), &mut Vec::new()).elements,
+            [TemplateElement::Barcode {
+                x: 0,
+                y: 143,
+                rotation: 0,
+                symbology: "ean13".to_string(),
+                narrow: 3,
+                wide: 9,
+                height: 68,
+                human_readable: true,
+                align: HorizontalAlign::Left,
+                valign: VerticalAlign::Top,
+                content: "{product.barcode}".to_string(),
+            }]
+        );
+
+        let old_203 = get_template(&conn, 9).unwrap();
+        assert_eq!((old_203.width, old_203.height, old_203.gap), (105.0, 22.0, 3.125));
+
+        // Já convertida: a segunda execução não mexe nas medidas; o padrão não é recriado
+        setup_table(&conn).unwrap();
+        assert_eq!(get_template(&conn, 9).unwrap().column_x, [1.5, 36.5, 71.5]);
+        assert_eq!(list_templates(&conn).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn modelo_escolhido_inexistente_gera_erro() {
+        let conn = seeded_connection();
+
+        let error = load_template(&conn, Some(999)).unwrap_err();
+
+        assert_eq!(error, "Modelo de etiqueta 999 não encontrado");
+    }
+}
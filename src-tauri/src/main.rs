@@ -7,8 +7,51 @@ use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::{Manager, AppHandle, State, WindowBuilder, WindowUrl};
 
-// Importar o módulo windows_printing
+// Importar o módulo de impressão do sistema (spooler do Windows ou CUPS)
+#[cfg(windows)]
 mod windows_printing;
+#[cfg(unix)]
+mod cups_printing;
+#[cfg(target_os = "linux")]
+mod usb_lp_printing;
+mod barcode;
+mod code_page;
+mod bitmap_font;
+mod file_printer;
+mod label_escape;
+mod label_metrics;
+mod label_preview;
+mod label_render;
+mod label_template;
+mod media_profile;
+mod network_printing;
+mod ppla_emulator;
+mod ppla_label;
+mod ppla_parser;
+mod pplb_label;
+mod print_policy;
+mod resolution;
+mod printer;
+mod printer_monitor;
+mod printer_status;
+mod serial_printing;
+mod text_fit;
+#[cfg(test)]
+mod test_support;
+mod transport;
+mod tspl_label;
+mod zpl_label;
+
+use label_preview::{LabelPreview, PreviewFormat};
+use label_render::{LabelBatch, LabelLanguage};
+use label_template::LabelTemplate;
+use media_profile::{Media, MediaProfile};
+use ppla_emulator::EmulatorSetup;
+use ppla_parser::PplaDiagnostic;
+use print_policy::PrintOutcome;
+use resolution::Resolution;
+use printer_status::{PrinterStatus, StatusProtocol};
+use transport::{PrintJobInfo, PrinterTarget};
 
 // Estruturas de dados
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -35,16 +78,122 @@ struct PrintJob {
   product_code: String,
   created_at: String,
   status: String,
+  printer_name: Option<String>, // Impressora que recebeu o trabalho
+  attempts: Option<u32>,        // Tentativas até o envio (ou até desistir)
+  quantity: u32,                // Etiquetas impressas do produto no trabalho
+}
+
+// Item de um lote por quantidade: produto cadastrado e número de etiquetas
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct PrintQuantity {
+  product_id: i64,
+  quantity: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PrinterConfig {
   darkness: u8,      // Densidade de impressão (1-15)
-  width: u32,        // Largura em dots (8 dots = 1mm)
-  height: u32,       // Altura em dots
+  width: u32,        // Largura da etiqueta em dots da impressora (8 por mm a 203 dpi, 12 a 300 dpi), até a largura da cabeça
+  height: u32,       // Altura da etiqueta em dots
   speed: u8,         // Velocidade (1-4)
-  port: String,      // Porta da impressora
+  port: String,      // Porta da impressora ("Windows" = fila do sistema, ou conexão direta, ex.: "tcp://10.0.0.5:9100", "/dev/usb/lp0", "COM1")
   selected_printer: Option<String>, // Impressora selecionada
+  #[serde(default = "default_connect_timeout_ms")]
+  connect_timeout_ms: u32, // Tempo limite para conectar em impressoras de rede
+  #[serde(default = "default_write_timeout_ms")]
+  write_timeout_ms: u32,   // Tempo limite para enviar dados a impressoras de rede e seriais
+  #[serde(default = "default_baud_rate")]
+  baud_rate: u32,          // Velocidade da porta serial
+  #[serde(default = "default_data_bits")]
+  data_bits: u8,           // Bits de dados da porta serial (5-8)
+  #[serde(default = "default_parity")]
+  parity: String,          // Paridade da porta serial ("none", "even", "odd")
+  #[serde(default = "default_stop_bits")]
+  stop_bits: u8,           // Stop bits da porta serial (1 ou 2)
+  #[serde(default = "default_flow_control")]
+  flow_control: String,    // Controle de fluxo ("none", "software", "hardware")
+  #[serde(default)]
+  capture_dir: Option<String>, // Pasta dos arquivos da impressora virtual
+  #[serde(default = "default_status_poll_interval_secs")]
+  status_poll_interval_secs: u32, // Intervalo do monitor de status em segundos (0 desliga)
+  #[serde(default = "default_failure_policy")]
+  failure_policy: String,  // Em caso de falha: "fail" (desiste), "retry" (repete) ou "fallback" (repete e usa a reserva)
+  #[serde(default = "default_max_retries")]
+  max_retries: u32,        // Novas tentativas depois da primeira falha
+  #[serde(default = "default_retry_backoff_ms")]
+  retry_backoff_ms: u32,   // Espera antes da primeira nova tentativa (dobra a cada falha)
+  #[serde(default)]
+  fallback_printer: Option<String>, // Impressora reserva da política "fallback"
+  #[serde(default = "default_language")]
+  language: String,        // Linguagem de comandos da impressora ("ppla", "pplb", "zpl" ou "tspl")
+  #[serde(default = "default_code_page")]
+  code_page: String,       // Página de código dos textos ("cp850", "cp1252" ou "iso-8859-1")
+  #[serde(default = "default_code_page_fallback")]
+  code_page_fallback: String, // Caracteres sem correspondência: "strip" (tira o acento) ou "question" ("?")
+  #[serde(default)]
+  media_profile_id: Option<i64>, // Perfil de mídia (sem perfil: largura e altura acima e colunas do modelo)
+  #[serde(default = "default_dpi")]
+  dpi: u32,                // Resolução da cabeça de impressão (203 ou 300 dpi)
+}
+
+fn default_connect_timeout_ms() -> u32 {
+  3000
+}
+
+fn default_write_timeout_ms() -> u32 {
+  10000
+}
+
+fn default_baud_rate() -> u32 {
+  9600
+}
+
+fn default_data_bits() -> u8 {
+  8
+}
+
+fn default_parity() -> String {
+  "none".to_string()
+}
+
+fn default_stop_bits() -> u8 {
+  1
+}
+
+fn default_flow_control() -> String {
+  "none".to_string()
+}
+
+fn default_status_poll_interval_secs() -> u32 {
+  15
+}
+
+fn default_failure_policy() -> String {
+  "fail".to_string()
+}
+
+fn default_max_retries() -> u32 {
+  2
+}
+
+fn default_retry_backoff_ms() -> u32 {
+  1000
+}
+
+fn default_language() -> String {
+  "ppla".to_string()
+}
+
+fn default_code_page() -> String {
+  "cp850".to_string()
+}
+
+fn default_code_page_fallback() -> String {
+  "strip".to_string()
+}
+
+fn default_dpi() -> u32 {
+  Resolution::DEFAULT_DPI
 }
 
 impl Default for PrinterConfig {
@@ -56,6 +205,24 @@ impl Default for PrinterConfig {
           speed: 2,       // Velocidade média
           port: "Windows".to_string(), // Agora o padrão é Windows
           selected_printer: None,      // Inicialmente nenhuma impressora selecionada
+          connect_timeout_ms: default_connect_timeout_ms(),
+          write_timeout_ms: default_write_timeout_ms(),
+          baud_rate: default_baud_rate(),
+          data_bits: default_data_bits(),
+          parity: default_parity(),
+          stop_bits: default_stop_bits(),
+          flow_control: default_flow_control(),
+          capture_dir: None,
+          status_poll_interval_secs: default_status_poll_interval_secs(),
+          failure_policy: default_failure_policy(),
+          max_retries: default_max_retries(),
+          retry_backoff_ms: default_retry_backoff_ms(),
+          fallback_printer: None,
+          language: default_language(),
+          code_page: default_code_page(),
+          code_page_fallback: default_code_page_fallback(),
+          media_profile_id: None,
+          dpi: default_dpi(),
       }
   }
 }
@@ -136,26 +303,55 @@ fn setup_database() -> DbConnection {
   )
   .expect("failed to create printer_settings table");
 
-  // Verifica se a coluna "port" existe na tabela
-  let has_port_column = conn
+  // Colunas adicionadas em versões posteriores
+  add_column_if_missing(&conn, "printer_settings", "port", "TEXT NOT NULL DEFAULT 'Windows'");
+  add_column_if_missing(&conn, "printer_settings", "selected_printer", "TEXT");
+  add_column_if_missing(&conn, "printer_settings", "connect_timeout_ms", "INTEGER NOT NULL DEFAULT 3000");
+  add_column_if_missing(&conn, "printer_settings", "write_timeout_ms", "INTEGER NOT NULL DEFAULT 10000");
+  add_column_if_missing(&conn, "printer_settings", "baud_rate", "INTEGER NOT NULL DEFAULT 9600");
+  add_column_if_missing(&conn, "printer_settings", "data_bits", "INTEGER NOT NULL DEFAULT 8");
+  add_column_if_missing(&conn, "printer_settings", "parity", "TEXT NOT NULL DEFAULT 'none'");
+  add_column_if_missing(&conn, "printer_settings", "stop_bits", "INTEGER NOT NULL DEFAULT 1");
+  add_column_if_missing(&conn, "printer_settings", "flow_control", "TEXT NOT NULL DEFAULT 'none'");
+  add_column_if_missing(&conn, "printer_settings", "capture_dir", "TEXT");
+  add_column_if_missing(&conn, "printer_settings", "status_poll_interval_secs", "INTEGER NOT NULL DEFAULT 15");
+  add_column_if_missing(&conn, "printer_settings", "failure_policy", "TEXT NOT NULL DEFAULT 'fail'");
+  add_column_if_missing(&conn, "printer_settings", "max_retries", "INTEGER NOT NULL DEFAULT 2");
+  add_column_if_missing(&conn, "printer_settings", "retry_backoff_ms", "INTEGER NOT NULL DEFAULT 1000");
+  add_column_if_missing(&conn, "printer_settings", "fallback_printer", "TEXT");
+  add_column_if_missing(&conn, "printer_settings", "language", "TEXT NOT NULL DEFAULT 'ppla'");
+  add_column_if_missing(&conn, "printer_settings", "code_page", "TEXT NOT NULL DEFAULT 'cp850'");
+  add_column_if_missing(&conn, "printer_settings", "code_page_fallback", "TEXT NOT NULL DEFAULT 'strip'");
+  add_column_if_missing(&conn, "printer_settings", "media_profile_id", "INTEGER");
+  add_column_if_missing(&conn, "printer_settings", "dpi", "INTEGER NOT NULL DEFAULT 203");
+  add_column_if_missing(&conn, "print_jobs", "printer_name", "TEXT");
+  add_column_if_missing(&conn, "print_jobs", "attempts", "INTEGER");
+  add_column_if_missing(&conn, "print_jobs", "quantity", "INTEGER NOT NULL DEFAULT 1");
+
+  label_template::setup_table(&conn).expect("failed to create label_templates table");
+  media_profile::setup_table(&conn).expect("failed to create media_profiles table");
+
+  DbConnection(Arc::new(Mutex::new(conn)))
+}
+
+// Adiciona a coluna à tabela caso ela ainda não exista (bancos criados por versões anteriores)
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) {
+  let has_column = conn
       .query_row(
-          "SELECT COUNT(*) FROM pragma_table_info('printer_settings') WHERE name = 'port'",
-          [],
+          "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?",
+          params![table, column],
           |row| row.get::<_, i32>(0),
       )
       .unwrap_or(0) > 0;
 
-  // Se a coluna não existir, adiciona-a
-  if !has_port_column {
-      println!("Adicionando coluna 'port' à tabela printer_settings");
+  if !has_column {
+      println!("Adicionando coluna '{}' à tabela {}", column, table);
       conn.execute(
-          "ALTER TABLE printer_settings ADD COLUMN port TEXT NOT NULL DEFAULT 'Windows'",
+          &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
           [],
       )
-      .expect("failed to add port column to printer_settings table");
+      .unwrap_or_else(|e| panic!("failed to add {} column to {} table: {}", column, table, e));
   }
-
-  DbConnection(Arc::new(Mutex::new(conn)))
 }
 
 fn calculate_ean13_check_digit(code: &str) -> Result<char, String> {
@@ -460,127 +656,308 @@ fn delete_product(id: i64, db: State<DbConnection>) -> Result<(), String> {
   }
 }
 
-// Função para impressão de lote usando formato PPLA para Argox
+// Resolve o nome recebido do frontend para um destino de impressão.
+// Nomes que não são de fila (ex.: "usb", "file://...") são usados diretamente;
+// depois vale a porta configurada (ex.: "tcp://10.0.0.5:9100") e, por fim, a fila do sistema.
+fn resolve_printer_target(printer_name: Option<String>, config: &PrinterConfig) -> Result<PrinterTarget, String> {
+  // Sem impressora informada, vale a selecionada nas configurações
+  let printer_name = printer_name.or_else(|| config.selected_printer.clone());
+
+  if let Some(name) = &printer_name {
+    let target = PrinterTarget::parse(name);
+    if !matches!(target, PrinterTarget::Spooler(_)) {
+      return Ok(target);
+    }
+  }
+
+  if !config.port.eq_ignore_ascii_case("Windows") {
+    let target = PrinterTarget::parse(&config.port);
+    if !matches!(target, PrinterTarget::Spooler(_)) {
+      return Ok(target);
+    }
+  }
+
+  let printer_to_use = match printer_name {
+    Some(name) => name,
+    None => return Err("Nenhuma impressora selecionada. Escolha uma impressora nas configurações.".to_string()),
+  };
+
+  // Nunca trocar por outra impressora em silêncio (a troca só acontece pela política "fallback")
+  let printers = transport::list_system_printers(false)?;
+  if !printers.contains(&printer_to_use) {
+    return Err(format!("Impressora '{}' não encontrada no sistema", printer_to_use));
+  }
+
+  Ok(PrinterTarget::Spooler(printer_to_use))
+}
+
+// Função para impressão de lote na linguagem configurada (PPLA por padrão, para Argox)
 #[tauri::command]
-async fn print_label_batch(products: Vec<Option<Product>>, app_handle: AppHandle, printer_name: Option<String>) -> Result<(), String> {
+async fn print_label_batch(
+  products: Vec<Option<Product>>,
+  app_handle: AppHandle,
+  printer_name: Option<String>,
+  template_id: Option<i64>,
+) -> Result<PrintOutcome, String> {
   println!("Iniciando impressão de lote com {} produtos...", products.len());
   
-  // Obter impressoras do Windows
-  let printers = windows_printing::list_windows_printers()?;
-  if printers.is_empty() {
-    return Err("Nenhuma impressora Windows encontrada. Instale uma impressora no sistema.".to_string());
+  let config = load_printer_config(&app_handle.state::<DbConnection>())?;
+  let (template, media) = load_label_layout(&app_handle.state::<DbConnection>(), template_id, &config)?;
+  let language = LabelLanguage::parse(&config.language)?;
+  println!("Usando modelo de etiqueta: {} ({:?})", template.name, language);
+  
+  // Criar o conteúdo da etiqueta na linguagem da impressora
+  let batch = label_render::build_label_batch(language, &template, &media, &config, &products)?;
+  send_label_batch(&app_handle, printer_name, &config, language, batch, &product_quantities(&products)).await
+}
+
+// Impressão por quantidade: cada produto sai em quantas etiquetas forem pedidas.
+// Fileiras repetidas de um mesmo produto usam o comando de cópias da impressora.
+#[tauri::command]
+async fn print_label_quantities(
+  items: Vec<PrintQuantity>,
+  app_handle: AppHandle,
+  printer_name: Option<String>,
+  template_id: Option<i64>,
+) -> Result<PrintOutcome, String> {
+  println!("Iniciando impressão por quantidade com {} produtos...", items.len());
+
+  let quantities = load_print_quantities(&app_handle.state::<DbConnection>(), &items)?;
+  let config = load_printer_config(&app_handle.state::<DbConnection>())?;
+  let (template, media) = load_label_layout(&app_handle.state::<DbConnection>(), template_id, &config)?;
+  let language = LabelLanguage::parse(&config.language)?;
+  println!("Usando modelo de etiqueta: {} ({:?})", template.name, language);
+
+  let rows = label_render::rows_from_quantities(template.column_x.len(), &quantities);
+  let batch = label_render::build_label_rows(language, &template, &media, &config, &rows)?;
+  send_label_batch(&app_handle, printer_name, &config, language, batch, &quantities).await
+}
+
+// Envia o lote pela política de falha e registra o histórico
+async fn send_label_batch(
+  app_handle: &AppHandle,
+  printer_name: Option<String>,
+  config: &PrinterConfig,
+  language: LabelLanguage,
+  batch: LabelBatch,
+  quantities: &[(Product, u32)],
+) -> Result<PrintOutcome, String> {
+  for warning in &batch.warnings {
+    println!("AVISO: {}", warning);
   }
+  let label_content = batch.data;
   
-  // Usar a impressora especificada ou a primeira da lista
-  let printer_to_use = match printer_name {
-    Some(name) if printers.contains(&name) => name,
-    Some(name) => {
-      println!("AVISO: Impressora solicitada '{}' não encontrada. Usando a primeira disponível.", name);
-      printers[0].clone()
-    },
-    None => {
-      println!("Impressora não especificada, usando a primeira da lista");
-      printers[0].clone()
-    }
-  };
+  // O emulador só conhece PPLA; o que ele apontar vai para o log sem impedir a impressão
+  if language == LabelLanguage::Ppla {
+    log_ppla_diagnostics(&label_content, config);
+  }
   
-  println!("Usando impressora Windows: {}", printer_to_use);
+  println!("Enviando trabalho de impressão com {} bytes", label_content.len());
   
-  // Obter o estado do banco de dados
-  let db_state = app_handle.state::<DbConnection>();
-  let conn = match db_state.0.lock() {
+  let job = PrintJobInfo::new("Etiquetas")
+    .with_product_ids(quantities.iter().filter_map(|(p, _)| p.id).collect());
+  
+  match print_policy::print_with_policy(printer_name, config, &job, &label_content).await {
+    Ok(outcome) => {
+      println!("Impressão enviada com sucesso para '{}' ({} tentativa(s))", outcome.printer, outcome.attempts);
+      record_print_jobs(&app_handle.state::<DbConnection>(), quantities, "completed", &outcome);
+      Ok(outcome)
+    },
+    Err(failure) => {
+      println!("ERRO ao enviar para impressora: {}", failure.error);
+      record_print_jobs(&app_handle.state::<DbConnection>(), quantities, "failed", &failure.outcome);
+      Err(format!(
+        "{} (impressora: {}, tentativas: {})",
+        failure.error, failure.outcome.printer, failure.outcome.attempts
+      ))
+    }
+  }
+}
+
+// Quantidade de etiquetas de cada produto de um lote por posições, na ordem em que aparecem
+fn product_quantities(products: &[Option<Product>]) -> Vec<(Product, u32)> {
+  let mut quantities: Vec<(Product, u32)> = Vec::new();
+
+  for product in products.iter().flatten() {
+    match quantities
+      .iter_mut()
+      .find(|(p, _)| p.id == product.id && p.product_code == product.product_code)
+    {
+      Some((_, quantity)) => *quantity += 1,
+      None => quantities.push((product.clone(), 1)),
+    }
+  }
+
+  quantities
+}
+
+// Produtos do lote por quantidade, lidos do cadastro (itens repetidos são somados)
+fn load_print_quantities(db: &DbConnection, items: &[PrintQuantity]) -> Result<Vec<(Product, u32)>, String> {
+  let conn = db.0.lock().map_err(|e| format!("Erro ao acessar banco de dados: {}", e))?;
+  let mut quantities: Vec<(Product, u32)> = Vec::new();
+
+  for item in items {
+    if item.quantity == 0 || item.quantity > label_render::MAX_COPIES {
+      return Err(format!(
+        "Quantidade inválida para o produto {}: deve ser de 1 a {}",
+        item.product_id, label_render::MAX_COPIES
+      ));
+    }
+
+    if let Some((_, quantity)) = quantities.iter_mut().find(|(p, _)| p.id == Some(item.product_id)) {
+      *quantity = quantity
+        .checked_add(item.quantity)
+        .filter(|total| *total <= label_render::MAX_COPIES)
+        .ok_or_else(|| format!(
+          "Quantidade total do produto {} passa do limite de {} etiquetas",
+          item.product_id, label_render::MAX_COPIES
+        ))?;
+      continue;
+    }
+
+    let product = conn
+      .query_row(
+        "SELECT id, product_code, name, name_short, barcode, description, created_at, updated_at
+         FROM products WHERE id = ?",
+        params![item.product_id],
+        |row| {
+          Ok(Product {
+            id: Some(row.get(0)?),
+            product_code: row.get(1)?,
+            name: row.get(2)?,
+            name_short: row.get(3)?,
+            barcode: row.get(4)?,
+            description: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+          })
+        },
+      )
+      .optional()
+      .map_err(|e| e.to_string())?
+      .ok_or_else(|| format!("Produto {} não encontrado", item.product_id))?;
+
+    quantities.push((product, item.quantity));
+  }
+
+  if quantities.is_empty() {
+    return Err("Nenhum produto para imprimir no lote".to_string());
+  }
+
+  Ok(quantities)
+}
+
+// Modelo escolhido (ou o layout padrão) ajustado ao perfil de mídia da impressora, se houver
+fn load_label_layout(db: &DbConnection, template_id: Option<i64>, config: &PrinterConfig) -> Result<(LabelTemplate, Media), String> {
+  let conn = db.0.lock().map_err(|e| format!("Erro ao acessar banco de dados: {}", e))?;
+  let template = label_template::load_template(&conn, template_id)?;
+  let profile = media_profile::load_profile(&conn, config.media_profile_id)?;
+  if let Some(profile) = &profile {
+    println!("Usando perfil de mídia: {}", profile.name);
+  }
+  media_profile::resolve(profile.as_ref(), &template, config)
+}
+
+#[tauri::command]
+fn list_label_templates(db: State<DbConnection>) -> Result<Vec<LabelTemplate>, String> {
+  let conn = db.0.lock().unwrap();
+  label_template::list_templates(&conn)
+}
+
+#[tauri::command]
+fn get_label_template(id: i64, db: State<DbConnection>) -> Result<LabelTemplate, String> {
+  let conn = db.0.lock().unwrap();
+  label_template::get_template(&conn, id)
+}
+
+#[tauri::command]
+fn create_label_template(template: LabelTemplate, db: State<DbConnection>) -> Result<LabelTemplate, String> {
+  let conn = db.0.lock().unwrap();
+  label_template::insert_template(&conn, &template)
+}
+
+#[tauri::command]
+fn update_label_template(id: i64, template: LabelTemplate, db: State<DbConnection>) -> Result<LabelTemplate, String> {
+  let conn = db.0.lock().unwrap();
+  label_template::update_template(&conn, id, &template)
+}
+
+#[tauri::command]
+fn delete_label_template(id: i64, db: State<DbConnection>) -> Result<(), String> {
+  let conn = db.0.lock().unwrap();
+  label_template::delete_template(&conn, id)
+}
+
+#[tauri::command]
+fn list_media_profiles(db: State<DbConnection>) -> Result<Vec<MediaProfile>, String> {
+  let conn = db.0.lock().unwrap();
+  media_profile::list_profiles(&conn)
+}
+
+#[tauri::command]
+fn get_media_profile(id: i64, db: State<DbConnection>) -> Result<MediaProfile, String> {
+  let conn = db.0.lock().unwrap();
+  media_profile::get_profile(&conn, id)
+}
+
+#[tauri::command]
+fn create_media_profile(profile: MediaProfile, db: State<DbConnection>) -> Result<MediaProfile, String> {
+  let conn = db.0.lock().unwrap();
+  media_profile::insert_profile(&conn, &profile)
+}
+
+#[tauri::command]
+fn update_media_profile(id: i64, profile: MediaProfile, db: State<DbConnection>) -> Result<MediaProfile, String> {
+  let conn = db.0.lock().unwrap();
+  media_profile::update_profile(&conn, id, &profile)
+}
+
+// A impressora que usava o perfil excluído volta para a largura e altura da configuração
+#[tauri::command]
+fn delete_media_profile(id: i64, db: State<DbConnection>) -> Result<(), String> {
+  let conn = db.0.lock().unwrap();
+  media_profile::delete_profile(&conn, id)?;
+  conn.execute(
+      "UPDATE printer_settings SET media_profile_id = NULL WHERE media_profile_id = ?",
+      params![id],
+  ).map_err(|e| e.to_string())?;
+  Ok(())
+}
+
+// Registra no histórico cada produto do lote, com a quantidade e o resultado do envio
+fn record_print_jobs(db: &DbConnection, quantities: &[(Product, u32)], status: &str, outcome: &PrintOutcome) {
+  let conn = match db.0.lock() {
     Ok(conn) => conn,
-    Err(e) => return Err(format!("Erro ao acessar banco de dados: {}", e)),
+    Err(e) => {
+      println!("Erro ao acessar banco de dados: {}", e);
+      return;
+    }
   };
   
-  // Criar o conteúdo da etiqueta no formato PPLA
-  let mut label_content = Vec::new();
-  
-  // Sequência de inicialização com parâmetros corretos para a fita de 105mm
-  label_content.extend_from_slice(b"N\r\n");                  // Limpa buffer
-  label_content.extend_from_slice(b"ZN\r\n");                 // Direção normal
-  label_content.extend_from_slice(b"q840\r\n");               // Largura da fita (105mm = 840 dots)
-  label_content.extend_from_slice(b"Q176,25\r\n");            // Altura da etiqueta (22mm = 176 dots) e gap
-  label_content.extend_from_slice(b"O\r\n");                  // Orientação padrão
-  label_content.extend_from_slice(b"JF\r\n");                 // Configuração de impressão
-  label_content.extend_from_slice(b"Z0\r\n");                 // Sem deslocamento vertical
-  
-  // Posições X exatas para cada etiqueta baseadas nas medidas fornecidas
-  // Margem esquerda: 1.5mm = 12 dots
-  // Largura da etiqueta: 33mm = 264 dots
-  // Espaço entre etiquetas: 2mm = 16 dots
-  let x_positions = [
-    12,                     // Primeira etiqueta: margem esquerda
-    12 + 264 + 16,          // Segunda etiqueta: margem + largura + espaço = 292
-    12 + 264 + 16 + 264 + 16 // Terceira etiqueta: 292 + largura + espaço = 572
-  ];
-  
-  // Adicionar cada produto
-  for (index, product) in products.iter().enumerate().filter(|(_, p)| p.is_some()) {
-    let product = product.as_ref().unwrap();
-    let x = x_positions[index % 3];
-    
-    // Calcular corretamente o centro da etiqueta
-    let center_x = x ; // Centro da etiqueta (largura 264 dots / 2 = 132)
-    
-    // Adicionar empresa (centralizado) - Y=15 (margem superior)
-    let company_cmd = format!("A{},15,0,3,1,1,N,\"ESTRELA METAIS\"\r\n", center_x);
-    label_content.extend_from_slice(company_cmd.as_bytes());
-    
-    // Adicionar nome do produto (centralizado) - Y=45
-    let name_cmd = format!("A{},45,0,2,1,1,N,\"{}\"\r\n", center_x, product.name_short);
-    label_content.extend_from_slice(name_cmd.as_bytes());
-    
-    // Adicionar código do produto (centralizado) - Y=70
-    let code_cmd = format!("A{},70,0,2,1,1,N,\"{}\"\r\n", center_x + (264 / 2) - 16, product.product_code);
-    label_content.extend_from_slice(code_cmd.as_bytes());
-    
-    // Adicionar código de barras EAN-13 (centralizado e horizontal) - Y=95
-    // Para códigos de barras em PPLA, o alinhamento é diferente
-    // O comando B já posiciona o código de barras a partir do ponto X
-    // Para centralizar, precisamos calcular o deslocamento
-    
-    let barcode_cmd = format!("B{},95,0,1,2,6,45,B,\"{}\"\r\n", center_x, product.barcode);
-    label_content.extend_from_slice(barcode_cmd.as_bytes());
-    
-    // Registrar impressão no histórico
+  for (product, quantity) in quantities {
     match conn.execute(
-      "INSERT INTO print_jobs (product_id, product_name, product_code, status) VALUES (?, ?, ?, ?)",
+      "INSERT INTO print_jobs (product_id, product_name, product_code, status, printer_name, attempts, quantity) VALUES (?, ?, ?, ?, ?, ?, ?)",
       params![
         product.id,
         &product.name,
         &product.product_code,
-        "completed"
+        status,
+        &outcome.printer,
+        outcome.attempts,
+        quantity
       ],
     ) {
       Ok(_) => {},
       Err(e) => println!("Erro ao registrar impressão no histórico: {}", e),
     }
   }
-  
-  // Comando de impressão
-  label_content.extend_from_slice(b"P1\r\n");
-  
-  println!("Enviando trabalho de impressão para '{}' com {} bytes", printer_to_use, label_content.len());
-  
-  // Envia para a impressora Windows
-  match windows_printing::print_to_windows_printer(&printer_to_use, "Etiquetas", &label_content) {
-    Ok(_) => {
-      println!("Impressão enviada com sucesso para '{}'", printer_to_use);
-      Ok(())
-    },
-    Err(e) => {
-      println!("ERRO ao enviar para impressora: {}", e);
-      Err(e)
-    }
-  }
 }
 
 #[tauri::command]
 fn get_print_history(db: State<DbConnection>) -> Result<Vec<PrintJob>, String> {
   let conn = db.0.lock().unwrap();
   let mut stmt = conn
-      .prepare("SELECT id, product_id, product_name, product_code, created_at, status FROM print_jobs ORDER BY created_at DESC")
+      .prepare("SELECT id, product_id, product_name, product_code, created_at, status, printer_name, attempts, quantity FROM print_jobs ORDER BY created_at DESC")
       .map_err(|e| e.to_string())?;
 
   let jobs = stmt
@@ -592,6 +969,9 @@ fn get_print_history(db: State<DbConnection>) -> Result<Vec<PrintJob>, String> {
               product_code: row.get(3)?,
               created_at: row.get(4)?,
               status: row.get(5)?,
+              printer_name: row.get(6)?,
+              attempts: row.get(7)?,
+              quantity: row.get(8)?,
           })
       })
       .map_err(|e| e.to_string())?;
@@ -603,34 +983,126 @@ fn get_print_history(db: State<DbConnection>) -> Result<Vec<PrintJob>, String> {
   Ok(result)
 }
 
-// Função para teste de impressão usando formato PPLA para Argox
-#[tauri::command]
-async fn print_test(printer_name: Option<String>) -> Result<(), String> {
-  println!("Iniciando teste de impressão...");
-  
-  // Obter impressoras do Windows
-  let printers = windows_printing::list_windows_printers()?;
-  if printers.is_empty() {
-    return Err("Nenhuma impressora Windows encontrada. Instale uma impressora no sistema.".to_string());
+// Tamanho padrão da etiqueta para o emulador (quando o fluxo não traz q/Q)
+fn emulator_setup(config: &PrinterConfig) -> EmulatorSetup {
+  EmulatorSetup {
+    width: config.width,
+    height: config.height,
+    dpi: config.dpi,
   }
-  
-  // Usar a impressora especificada ou a primeira da lista
-  let printer_to_use = match printer_name {
-    Some(name) if printers.contains(&name) => name,
-    Some(name) => {
-      println!("AVISO: Impressora solicitada '{}' não encontrada. Usando a primeira disponível.", name);
-      printers[0].clone()
+}
+
+// Mostra no log os problemas que o emulador encontrou nos comandos
+fn log_ppla_diagnostics(data: &[u8], config: &PrinterConfig) {
+  match ppla_emulator::emulate_bytes(data, emulator_setup(config)) {
+    Ok(emulation) => {
+      for warning in &emulation.warnings {
+        println!("AVISO: {}", warning);
+      }
     },
-    None => {
-      println!("Impressora não especificada, usando a primeira da lista");
-      printers[0].clone()
+    Err(diagnostics) => {
+      for diagnostic in &diagnostics {
+        println!("AVISO: comando PPLA inválido, {}", diagnostic);
+      }
     }
+  }
+}
+
+// Resultado da conferência de um fluxo PPLA no emulador
+#[derive(Debug, Serialize)]
+struct PplaVerification {
+  valid: bool,
+  commands: usize,                 // Comandos reconhecidos
+  labels: u32,                     // Etiquetas que seriam impressas
+  errors: Vec<PplaDiagnostic>,     // Comandos desconhecidos ou malformados
+  warnings: Vec<PplaDiagnostic>,   // Elementos cortados, gráficos que não podem ser conferidos etc.
+}
+
+// Confere no emulador o que seria enviado por print_label_batch ("label_batch"),
+// print_test ("print_test") ou print_argox_ppla_exact ("argox_exact")
+#[tauri::command]
+fn verify_ppla_output(
+  source: String,
+  products: Option<Vec<Option<Product>>>,
+  template_id: Option<i64>,
+  db: State<DbConnection>,
+) -> Result<PplaVerification, String> {
+  let config = load_printer_config(&db)?;
+
+  let data = match source.as_str() {
+    "label_batch" => {
+      let language = LabelLanguage::parse(&config.language)?;
+      if !matches!(language, LabelLanguage::Ppla | LabelLanguage::Pplb) {
+        return Err(format!("O emulador confere somente PPLA e PPLB (configurado: {})", config.language));
+      }
+      let (template, media) = load_label_layout(&db, template_id, &config)?;
+      label_render::build_label_batch(language, &template, &media, &config, &products.unwrap_or_default())?.data
+    },
+    "print_test" => PRINT_TEST_CONTENT.to_vec(),
+    "argox_exact" => ARGOX_PPLA_EXACT.to_vec(),
+    _ => return Err(format!("Origem desconhecida: {}", source)),
   };
-  
-  println!("Usando impressora Windows para teste: {}", printer_to_use);
-  
-  // Comando de teste para Argox no formato PPLA (baseado no dump)
-  let test_content = b"N\r\n\
+
+  let verification = match ppla_emulator::emulate_bytes(&data, emulator_setup(&config)) {
+    Ok(emulation) => PplaVerification {
+      valid: true,
+      commands: emulation.commands,
+      labels: emulation.labels.iter().map(|label| label.copies).sum(),
+      errors: Vec::new(),
+      warnings: emulation.warnings,
+    },
+    Err(errors) => PplaVerification {
+      valid: false,
+      commands: 0,
+      labels: 0,
+      errors,
+      warnings: Vec::new(),
+    },
+  };
+
+  Ok(verification)
+}
+
+// Pré-visualização das etiquetas, desenhada a partir dos mesmos comandos que seriam impressos.
+// `config` permite pré-visualizar um perfil ainda não salvo.
+#[tauri::command]
+fn preview_label_batch(
+  products: Vec<Option<Product>>,
+  template_id: Option<i64>,
+  format: Option<String>,
+  config: Option<PrinterConfig>,
+  db: State<DbConnection>,
+) -> Result<LabelPreview, String> {
+  let config = match config {
+    Some(config) => config,
+    None => load_printer_config(&db)?,
+  };
+  let format = PreviewFormat::parse(format.as_deref().unwrap_or("png"))?;
+
+  let language = LabelLanguage::parse(&config.language)?;
+  let (template, media) = load_label_layout(&db, template_id, &config)?;
+  let batch = label_render::build_label_batch(language, &template, &media, &config, &products)?;
+
+  // O emulador só desenha PPLA/PPLB: para ZPL e TSPL (já conferidos acima) a imagem sai do mesmo
+  // modelo e mídia gerados em PPLA, com aviso de que é uma aproximação
+  let data = match language {
+    LabelLanguage::Ppla | LabelLanguage::Pplb => batch.data,
+    LabelLanguage::Zpl | LabelLanguage::Tspl => label_render::build_label_batch(LabelLanguage::Ppla, &template, &media, &config, &products)?.data,
+  };
+
+  let mut preview = label_preview::render_preview(&data, emulator_setup(&config), format)?;
+  preview.layout_warnings = batch.warnings;
+  if !matches!(language, LabelLanguage::Ppla | LabelLanguage::Pplb) {
+    preview.layout_warnings.insert(0, format!(
+      "Pré-visualização aproximada: a impressora usa {}, e a imagem foi desenhada a partir do mesmo modelo em PPLA",
+      language.name()
+    ));
+  }
+  Ok(preview)
+}
+
+// Comando de teste para Argox no formato PPLA (baseado no dump)
+const PRINT_TEST_CONTENT: &[u8] = b"N\r\n\
     GW620,215,13,32\r\n\
     ZN\r\n\
     q822\r\n\
@@ -644,11 +1116,21 @@ async fn print_test(printer_name: Option<String>) -> Result<(), String> {
     A50,100,0,3,1,1,N,\"ARGOX OS-2140\"\r\n\
     B50,150,1,1,3,7,100,B,\"123456789012\"\r\n\
     P1\r\n";
+
+// Função para teste de impressão usando formato PPLA para Argox
+#[tauri::command]
+async fn print_test(printer_name: Option<String>, db: State<'_, DbConnection>) -> Result<(), String> {
+  println!("Iniciando teste de impressão...");
   
-  // Envia para a impressora Windows
-  match windows_printing::print_to_windows_printer(&printer_to_use, "Teste", test_content) {
+  let config = load_printer_config(&db)?;
+  let target = resolve_printer_target(printer_name, &config)?;
+  
+  println!("Usando destino para teste: {:?}", target);
+  log_ppla_diagnostics(PRINT_TEST_CONTENT, &config);
+  
+  match transport::print_to_target(&target, &config, &PrintJobInfo::new("Teste"), PRINT_TEST_CONTENT) {
     Ok(_) => {
-      println!("Teste de impressão enviado com sucesso para '{}'", printer_to_use);
+      println!("Teste de impressão enviado com sucesso para {:?}", target);
       Ok(())
     },
     Err(e) => {
@@ -662,37 +1144,25 @@ async fn print_test(printer_name: Option<String>) -> Result<(), String> {
 async fn save_printer_settings(config: PrinterConfig, db: State<'_, DbConnection>) -> Result<(), String> {
   println!("Salvando configurações de impressora");
 
+  label_render::validate_printer_config(LabelLanguage::parse(&config.language)?, &config)?;
+
   let conn = db.0.lock().unwrap();
 
   conn.execute("DELETE FROM printer_settings", [])
       .map_err(|e| e.to_string())?;
 
-  // Verifique se a coluna selected_printer existe
-  let has_selected_printer_column = conn
-      .query_row(
-          "SELECT COUNT(*) FROM pragma_table_info('printer_settings') WHERE name = 'selected_printer'",
-          [],
-          |row| row.get::<_, i32>(0),
-      )
-      .unwrap_or(0) > 0;
-
-  // Adicionar coluna se não existir
-  if !has_selected_printer_column {
-      println!("Adicionando coluna 'selected_printer' à tabela printer_settings");
-      conn.execute(
-          "ALTER TABLE printer_settings ADD COLUMN selected_printer TEXT",
-          [],
-      )
-      .map_err(|e| e.to_string())?;
-  }
-
   // Preparar o valor da impressora selecionada (NULL se None)
   let selected_printer = config.selected_printer.as_ref().map(|s| s.as_str());
 
   conn.execute(
       "INSERT INTO printer_settings (
-          darkness, width, height, speed, port, selected_printer
-      ) VALUES (?, ?, ?, ?, ?, ?)",
+          darkness, width, height, speed, port, selected_printer,
+          connect_timeout_ms, write_timeout_ms,
+          baud_rate, data_bits, parity, stop_bits, flow_control,
+          capture_dir, status_poll_interval_secs,
+          failure_policy, max_retries, retry_backoff_ms, fallback_printer,
+          language, code_page, code_page_fallback, media_profile_id, dpi
+      ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
       params![
           config.darkness,
           config.width,
@@ -700,6 +1170,24 @@ async fn save_printer_settings(config: PrinterConfig, db: State<'_, DbConnection
           config.speed,
           config.port,
           selected_printer,
+          config.connect_timeout_ms,
+          config.write_timeout_ms,
+          config.baud_rate,
+          config.data_bits,
+          config.parity,
+          config.stop_bits,
+          config.flow_control,
+          config.capture_dir,
+          config.status_poll_interval_secs,
+          config.failure_policy,
+          config.max_retries,
+          config.retry_backoff_ms,
+          config.fallback_printer,
+          config.language,
+          config.code_page,
+          config.code_page_fallback,
+          config.media_profile_id,
+          config.dpi,
       ],
   ).map_err(|e| e.to_string())?;
 
@@ -710,80 +1198,57 @@ async fn save_printer_settings(config: PrinterConfig, db: State<'_, DbConnection
 #[tauri::command]
 async fn get_printer_settings(db: State<'_, DbConnection>) -> Result<Option<PrinterConfig>, String> {
   let conn = db.0.lock().unwrap();
+  read_printer_settings(&conn)
+}
 
-  // Primeiro, verifica se a coluna "port" existe
-  let has_port_column = conn
-      .query_row(
-          "SELECT COUNT(*) FROM pragma_table_info('printer_settings') WHERE name = 'port'",
-          [],
-          |row| row.get::<_, i32>(0),
-      )
-      .unwrap_or(0) > 0;
-
-  // Verifica se a coluna "selected_printer" existe
-  let has_selected_printer_column = conn
-      .query_row(
-          "SELECT COUNT(*) FROM pragma_table_info('printer_settings') WHERE name = 'selected_printer'",
-          [],
-          |row| row.get::<_, i32>(0),
-      )
-      .unwrap_or(0) > 0;
-
-  let result = if has_port_column && has_selected_printer_column {
-      // Se ambas as colunas existirem, usa a consulta completa
-      conn.query_row(
-          "SELECT darkness, width, height, speed, port, selected_printer FROM printer_settings LIMIT 1",
-          [],
-          |row| {
-              Ok(PrinterConfig {
-                  darkness: row.get(0)?,
-                  width: row.get(1)?,
-                  height: row.get(2)?,
-                  speed: row.get(3)?,
-                  port: row.get(4)?,
-                  selected_printer: row.get(5)?,
-              })
-          },
-      )
-  } else if has_port_column {
-      // Se apenas a coluna "port" existir
-      conn.query_row(
-          "SELECT darkness, width, height, speed, port FROM printer_settings LIMIT 1",
-          [],
-          |row| {
-              Ok(PrinterConfig {
-                  darkness: row.get(0)?,
-                  width: row.get(1)?,
-                  height: row.get(2)?,
-                  speed: row.get(3)?,
-                  port: row.get(4)?,
-                  selected_printer: None,
-              })
-          },
-      )
-  } else {
-      // Se nenhuma das novas colunas existir
-      conn.query_row(
-          "SELECT darkness, width, height, speed FROM printer_settings LIMIT 1",
-          [],
-          |row| {
-              Ok(PrinterConfig {
-                  darkness: row.get(0)?,
-                  width: row.get(1)?,
-                  height: row.get(2)?,
-                  speed: row.get(3)?,
-                  port: "Windows".to_string(),
-                  selected_printer: None,
-              })
-          },
-      )
-  };
+// Lê as configurações salvas (as colunas são garantidas por setup_database)
+fn read_printer_settings(conn: &Connection) -> Result<Option<PrinterConfig>, String> {
+  conn.query_row(
+      "SELECT darkness, width, height, speed, port, selected_printer,
+              connect_timeout_ms, write_timeout_ms,
+              baud_rate, data_bits, parity, stop_bits, flow_control,
+              capture_dir, status_poll_interval_secs,
+              failure_policy, max_retries, retry_backoff_ms, fallback_printer,
+              language, code_page, code_page_fallback, media_profile_id, dpi
+       FROM printer_settings LIMIT 1",
+      [],
+      |row| {
+          Ok(PrinterConfig {
+              darkness: row.get(0)?,
+              width: row.get(1)?,
+              height: row.get(2)?,
+              speed: row.get(3)?,
+              port: row.get(4)?,
+              selected_printer: row.get(5)?,
+              connect_timeout_ms: row.get(6)?,
+              write_timeout_ms: row.get(7)?,
+              baud_rate: row.get(8)?,
+              data_bits: row.get(9)?,
+              parity: row.get(10)?,
+              stop_bits: row.get(11)?,
+              flow_control: row.get(12)?,
+              capture_dir: row.get(13)?,
+              status_poll_interval_secs: row.get(14)?,
+              failure_policy: row.get(15)?,
+              max_retries: row.get(16)?,
+              retry_backoff_ms: row.get(17)?,
+              fallback_printer: row.get(18)?,
+              language: row.get(19)?,
+              code_page: row.get(20)?,
+              code_page_fallback: row.get(21)?,
+              media_profile_id: row.get(22)?,
+              dpi: row.get(23)?,
+          })
+      },
+  )
+  .optional()
+  .map_err(|e| e.to_string())
+}
 
-  match result {
-      Ok(settings) => Ok(Some(settings)),
-      Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-      Err(e) => Err(e.to_string()),
-  }
+// Configurações salvas ou, se ainda não houver nenhuma, as padrão
+fn load_printer_config(db: &DbConnection) -> Result<PrinterConfig, String> {
+  let conn = db.0.lock().map_err(|e| format!("Erro ao acessar banco de dados: {}", e))?;
+  Ok(read_printer_settings(&conn)?.unwrap_or_default())
 }
 
 // Conexão e configuração de impressora
@@ -791,10 +1256,10 @@ async fn get_printer_settings(db: State<'_, DbConnection>) -> Result<Option<Prin
 async fn connect_printer(config: PrinterConfig, printer_name: Option<String>) -> Result<(), String> {
   println!("Verificando configurações de impressora: {:?}", config);
   
-  // Obter impressoras do Windows
-  let printers = windows_printing::list_windows_printers()?;
+  // Obter impressoras do sistema
+  let printers = transport::list_system_printers(false)?;
   if printers.is_empty() {
-    return Err("Nenhuma impressora Windows encontrada. Instale uma impressora no Windows para continuar.".to_string());
+    return Err("Nenhuma impressora encontrada. Instale uma impressora no sistema para continuar.".to_string());
   }
   
   // Verificar se a impressora selecionada existe
@@ -804,26 +1269,58 @@ async fn connect_printer(config: PrinterConfig, printer_name: Option<String>) ->
     None => printers[0].clone(),
   };
   
-  println!("Impressora Windows selecionada: {}. Configurações salvas.", printer_to_use);
+  println!("Impressora selecionada: {}. Configurações salvas.", printer_to_use);
   Ok(())
 }
 
-// Lista somente impressoras do Windows
+// Lista as impressoras do sistema (spooler do Windows ou filas do CUPS)
 #[tauri::command]
 async fn list_printers(silent: Option<bool>) -> Result<Vec<String>, String> {
   // Usar a versão silenciosa se solicitado, caso contrário usar a versão padrão
-  let printers = if silent.unwrap_or(false) {
-    println!("Listando impressoras Windows em modo silencioso...");
-    windows_printing::list_windows_printers_silent()?
+  let silent = silent.unwrap_or(false);
+  if silent {
+    println!("Listando impressoras em modo silencioso...");
   } else {
-    println!("Listando impressoras Windows com interface padrão...");
-    windows_printing::list_windows_printers()?
-  };
+    println!("Listando impressoras com interface padrão...");
+  }
+  // Sem spooler (ex.: Linux sem CUPS) ainda há as impressoras diretas e a virtual
+  let mut printers = transport::list_system_printers(silent).unwrap_or_else(|e| {
+    println!("AVISO: não foi possível listar as filas de impressão do sistema: {}", e);
+    Vec::new()
+  });
+  
+  // Impressoras ligadas diretamente (ex.: "Argox OS-2140 (/dev/usb/lp0)")
+  printers.extend(transport::list_direct_printers());
   
-  println!("Impressoras Windows detectadas: {:?}", printers);
+  // Impressora virtual, para testes sem impressora conectada
+  printers.push(file_printer::VIRTUAL_PRINTER_NAME.to_string());
+  
+  println!("Impressoras detectadas: {:?}", printers);
   Ok(printers)
 }
 
+// Reenvia um arquivo .prn gravado pela impressora virtual para uma impressora
+#[tauri::command]
+async fn replay_print_file(file_path: String, printer_name: Option<String>, db: State<'_, DbConnection>) -> Result<(), String> {
+  println!("Reenviando arquivo {} ", file_path);
+  
+  let data = std::fs::read(&file_path)
+      .map_err(|e| format!("Erro ao ler arquivo '{}': {}", file_path, e))?;
+  
+  let config = load_printer_config(&db)?;
+  let target = resolve_printer_target(printer_name, &config)?;
+  
+  transport::print_to_target(&target, &config, &PrintJobInfo::new("Reimpressão"), &data)
+}
+
+// Lista as portas seriais disponíveis (COM1, /dev/ttyUSB0, ...)
+#[tauri::command]
+async fn list_serial_ports() -> Result<Vec<String>, String> {
+  let ports = serial_printing::list_serial_ports()?;
+  println!("Portas seriais detectadas: {:?}", ports);
+  Ok(ports)
+}
+
 // Funções de atualização automática
 // Função modificada para verificar atualizações a partir do backend
 #[tauri::command]
@@ -967,50 +1464,67 @@ async fn get_update_settings(_app_handle: AppHandle) -> Result<UpdateSettings, S
 // Verificar se existe impressora conectada ao sistema
 #[tauri::command]
 async fn is_printer_connected() -> bool {
-  // Verificar se existem impressoras no Windows
-  match windows_printing::list_windows_printers() {
+  // Verificar se existem impressoras no sistema
+  match transport::list_system_printers(true) {
     Ok(printers) if !printers.is_empty() => true,
     _ => false
   }
 }
 
-// Teste de conexão com impressora Windows
+// Consulta o status real da impressora (pronta, sem papel, sem ribbon, pausa...).
+// Só funciona em conexões bidirecionais (USB, rede, serial, /dev/usb/lpN).
 #[tauri::command]
-async fn test_printer_connection(_config: PrinterConfig) -> Result<(), String> {
-  // Obter impressoras do Windows
-  let printers = windows_printing::list_windows_printers()?;
-  if printers.is_empty() {
-    return Err("Nenhuma impressora Windows encontrada. Instale uma impressora no sistema.".to_string());
-  }
+async fn get_printer_status(printer_name: Option<String>, protocol: Option<String>, db: State<'_, DbConnection>) -> Result<PrinterStatus, String> {
+  let config = load_printer_config(&db)?;
+  let target = resolve_printer_target(printer_name, &config)?;
   
-  println!("Testando impressora Windows: {}", printers[0]);
+  // Sem protocolo informado, tenta PPLA e depois PPLB
+  let protocols = match protocol {
+    Some(protocol) => vec![StatusProtocol::parse(&protocol)?],
+    None => vec![StatusProtocol::Ppla, StatusProtocol::Pplb],
+  };
+  
+  let _printer = transport::lock_printer();
+  let status = printer_status::read_printer_status(&target, &config, &protocols)?;
+  println!("Status da impressora: {:?}", status);
+  Ok(status)
+}
+
+// Teste de conexão com a impressora configurada
+#[tauri::command]
+async fn test_printer_connection(config: PrinterConfig) -> Result<(), String> {
+  let target = resolve_printer_target(config.selected_printer.clone(), &config)?;
+  
+  println!("Testando impressora: {:?}", target);
   
   // Envia um comando simples para testar
   let test_content = b"N\r\nGW620,215,13,32\r\nP1\r\n";
-  match windows_printing::print_to_windows_printer(&printers[0], "Teste de Conexão ", test_content) {
+  match transport::print_to_target(&target, &config, &PrintJobInfo::new("Teste de Conexão "), test_content) {
     Ok(_) => Ok(()),
     Err(e) => Err(format!("Erro ao testar impressora: {}", e))
   }
 }
 
+// Comando PPLA exato capturado do dump
+// Primeiros 32 bytes do dump - o GW anuncia 13 x 32 bytes de imagem, mas só há 13 (verify_ppla_output aponta)
+const ARGOX_PPLA_EXACT: [u8; 32] = [
+  0x4E, 0x0D, 0x0A, 0x47, 0x57, 0x36, 0x32, 0x30, 0x2C, 0x32, 0x31, 0x35, 0x2C, 0x31, 0x33, 0x2C,
+  0x33, 0x32, 0x2C, 0xF8, 0x1F, 0x98, 0x7F, 0xFE, 0x07, 0xF3, 0xE7, 0xCF, 0xC0, 0xFC, 0x7F, 0xCF
+];
+
 // Função para teste com o formato exato capturado
 #[tauri::command]
-fn print_argox_ppla_exact(printer_name: String) -> Result<(), String> {
+fn print_argox_ppla_exact(printer_name: String, db: State<DbConnection>) -> Result<(), String> {
   println!("Enviando comando PPLA exato para: {}", printer_name);
   
-  // Comando PPLA exato capturado do dump
-  let ppla_command = [
-    0x4E, 0x0D, 0x0A, 0x47, 0x57, 0x36, 0x32, 0x30, 0x2C, 0x32, 0x31, 0x35, 0x2C, 0x31, 0x33, 0x2C,
-    0x33, 0x32, 0x2C, 0xF8, 0x1F, 0x98, 0x7F, 0xFE, 0x07, 0xF3, 0xE7, 0xCF, 0xC0, 0xFC, 0x7F, 0xCF
-    // Primeiros 32 bytes do dump - você pode adicionar mais se necessário
-  ];
-  
-  windows_printing::print_to_windows_printer(&printer_name, "Teste PPLA Exato ", &ppla_command)
+  let config = load_printer_config(&db)?;
+  log_ppla_diagnostics(&ARGOX_PPLA_EXACT, &config);
+  transport::print_to_target(&PrinterTarget::parse(&printer_name), &config, &PrintJobInfo::new("Teste PPLA Exato "), &ARGOX_PPLA_EXACT)
 }
 
 // Função para teste de formatos de impressora
 #[tauri::command]
-fn test_printer_format(printer_name: String, format_type: String) -> Result<(), String> {
+fn test_printer_format(printer_name: String, format_type: String, db: State<DbConnection>) -> Result<(), String> {
   println!("Testando formato {} na impressora {}", format_type, printer_name);
   
   let test_data: Vec<u8> = match format_type.as_str() {
@@ -1036,7 +1550,9 @@ fn test_printer_format(printer_name: String, format_type: String) -> Result<(),
   };
   
   println!("Enviando {} bytes para a impressora", test_data.len());
-  windows_printing::print_to_windows_printer(&printer_name, &format!("Teste {}", format_type), &test_data)
+  let config = load_printer_config(&db)?;
+  let job = PrintJobInfo::new(&format!("Teste {}", format_type));
+  transport::print_to_target(&PrinterTarget::parse(&printer_name), &config, &job, &test_data)
 }
 
 #[tauri::command]
@@ -1079,17 +1595,33 @@ fn main() {
           delete_product,
           get_current_sequence,
           print_label_batch,
+          print_label_quantities,
           get_print_history,
+          verify_ppla_output,
+          preview_label_batch,
+          list_label_templates,
+          get_label_template,
+          create_label_template,
+          update_label_template,
+          delete_label_template,
+          list_media_profiles,
+          get_media_profile,
+          create_media_profile,
+          update_media_profile,
+          delete_media_profile,
           save_printer_settings,
           get_printer_settings,
           connect_printer,
           print_test,
           list_printers,
+          list_serial_ports,
+          replay_print_file,
           check_update_from_backend,
           install_update_from_backend,
           save_update_settings,
           get_update_settings,
           is_printer_connected,
+          get_printer_status,
           test_printer_connection,
           test_printer_format,
           print_argox_ppla_exact,
@@ -1110,6 +1642,12 @@ fn main() {
               check_update_on_startup(app_handle, state).await;
           });
 
+          // Monitor de status da impressora em segundo plano
+          let monitor_handle = app.handle().clone();
+          tauri::async_runtime::spawn(async move {
+              printer_monitor::run(monitor_handle).await;
+          });
+
           Ok(())
       })
       .run(tauri::generate_context!())
@@ -0,0 +1,506 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::label_template::LabelTemplate;
+use crate::resolution::Resolution;
+use crate::PrinterConfig;
+
+// Como a impressora encontra o início de cada etiqueta
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorType {
+    #[default]
+    Gap,        // Espaço entre etiquetas (etiquetas destacáveis)
+    BlackMark,  // Marca preta no verso do liner
+    Continuous, // Papel contínuo, sem marcação
+}
+
+impl SensorType {
+    pub fn parse(value: &str) -> Result<SensorType, String> {
+        match value {
+            "gap" => Ok(SensorType::Gap),
+            "black_mark" => Ok(SensorType::BlackMark),
+            "continuous" => Ok(SensorType::Continuous),
+            _ => Err(format!(
+                "Sensor desconhecido: {} (use gap, black_mark ou continuous)",
+                value
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SensorType::Gap => "gap",
+            SensorType::BlackMark => "black_mark",
+            SensorType::Continuous => "continuous",
+        }
+    }
+}
+
+// Perfil da mídia (rolo de etiquetas). Medidas em milímetros.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub liner_width: f64,    // Largura do liner (fita)
+    pub label_width: f64,    // Largura de cada etiqueta
+    pub label_height: f64,   // Altura de cada etiqueta
+    pub columns: u32,        // Etiquetas lado a lado
+    pub horizontal_gap: f64, // Espaço entre colunas
+    pub vertical_gap: f64,   // Espaço entre fileiras (ou altura da marca preta)
+    #[serde(default)]
+    pub left_margin: f64,    // Do início do liner até a primeira coluna
+    #[serde(default)]
+    pub top_margin: f64,     // Deslocamento do conteúdo a partir do topo da etiqueta
+    #[serde(default)]
+    pub sensor: SensorType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+// Mídia de um trabalho, em dots da impressora: o que vai no início de cada formulário
+#[derive(Debug, Clone, PartialEq)]
+pub struct Media {
+    pub width: u32,  // Largura da fita (q)
+    pub height: u32, // Altura da etiqueta (Q)
+    pub gap: u32,    // Espaço entre etiquetas ou altura da marca preta
+    pub sensor: SensorType,
+    pub top: u32, // Margem superior, somada ao Y de cada elemento
+    pub resolution: Resolution,
+}
+
+impl Media {
+    // Sem perfil de mídia: tamanho da configuração da impressora e gap do modelo
+    pub fn from_config(template: &LabelTemplate, config: &PrinterConfig) -> Result<Media, String> {
+        let resolution = Resolution::of_printer(config)?;
+
+        Ok(Media {
+            width: config.width,
+            height: config.height,
+            gap: resolution.dots(template.gap),
+            sensor: SensorType::Gap,
+            top: 0,
+            resolution,
+        })
+    }
+}
+
+// Espaço entre fileiras dos perfis padrão
+const DEFAULT_VERTICAL_GAP: f64 = 3.125;
+
+impl MediaProfile {
+    // Perfis criados na primeira execução: o rolo original (liner de 105mm com 3 etiquetas
+    // de 33mm x 22mm, 1,5mm de margem e 2mm entre colunas) e o de 2 colunas de 50mm x 30mm.
+    // O gap de 3,125mm dá os 25 dots do layout original (Q176,25) a 203 dpi
+    pub fn default_profiles() -> Vec<MediaProfile> {
+        let profile = |name: &str, description: &str, liner_width, label_width, label_height, columns, left_margin| MediaProfile {
+            id: None,
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            liner_width,
+            label_width,
+            label_height,
+            columns,
+            horizontal_gap: 2.0,
+            vertical_gap: DEFAULT_VERTICAL_GAP,
+            left_margin,
+            top_margin: 0.0,
+            sensor: SensorType::Gap,
+            created_at: None,
+            updated_at: None,
+        };
+
+        vec![
+            profile("3 colunas 33x22mm", "Liner de 105mm, 3 etiquetas de 33mm x 22mm", 105.0, 33.0, 22.0, 3, 1.5),
+            profile("2 colunas 50x30mm", "Liner de 104mm, 2 etiquetas de 50mm x 30mm", 104.0, 50.0, 30.0, 2, 1.0),
+        ]
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("O perfil de mídia precisa de um nome".to_string());
+        }
+
+        if self.liner_width <= 0.0 || self.label_width <= 0.0 || self.label_height <= 0.0 {
+            return Err("Largura do liner e tamanho da etiqueta devem ser maiores que zero".to_string());
+        }
+
+        if self.columns == 0 {
+            return Err("O perfil de mídia precisa de pelo menos uma coluna".to_string());
+        }
+
+        if [self.horizontal_gap, self.vertical_gap, self.left_margin, self.top_margin]
+            .iter()
+            .any(|value| *value < 0.0)
+        {
+            return Err("Espaços e margens não podem ser negativos".to_string());
+        }
+
+        if self.top_margin >= self.label_height {
+            return Err("A margem superior deve ser menor que a altura da etiqueta".to_string());
+        }
+
+        let used = self.left_margin
+            + self.columns as f64 * self.label_width
+            + (self.columns - 1) as f64 * self.horizontal_gap;
+        if used > self.liner_width + 0.01 {
+            return Err(format!(
+                "{} colunas de {}mm com {}mm de espaço e {}mm de margem ocupam {:.1}mm, mais que o liner de {}mm",
+                self.columns, self.label_width, self.horizontal_gap, self.left_margin, used, self.liner_width
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Posição X (em mm) do início de cada coluna
+    pub fn column_x(&self) -> Vec<f64> {
+        (0..self.columns)
+            .map(|column| self.left_margin + column as f64 * (self.label_width + self.horizontal_gap))
+            .collect()
+    }
+
+    pub fn media(&self, resolution: Resolution) -> Media {
+        Media {
+            width: resolution.dots(self.liner_width),
+            height: resolution.dots(self.label_height),
+            gap: resolution.dots(self.vertical_gap),
+            sensor: self.sensor,
+            top: resolution.dots(self.top_margin),
+            resolution,
+        }
+    }
+
+    // Modelo com o tamanho e as colunas deste perfil (os elementos não mudam)
+    pub fn apply(&self, template: &LabelTemplate) -> LabelTemplate {
+        LabelTemplate {
+            width: self.liner_width,
+            height: self.label_height,
+            gap: self.vertical_gap,
+            column_x: self.column_x(),
+            column_width: Some(self.label_width),
+            ..template.clone()
+        }
+    }
+}
+
+// Modelo e mídia de um trabalho: com perfil, o tamanho e as colunas vêm dele;
+// sem perfil, valem o modelo e a largura e altura da configuração da impressora.
+// A mídia sai em dots da impressora; o modelo continua em mm.
+pub fn resolve(
+    profile: Option<&MediaProfile>,
+    template: &LabelTemplate,
+    config: &PrinterConfig,
+) -> Result<(LabelTemplate, Media), String> {
+    match profile {
+        Some(profile) => Ok((profile.apply(template), profile.media(Resolution::of_printer(config)?))),
+        None => Ok((template.clone(), Media::from_config(template, config)?)),
+    }
+}
+
+// Cria a tabela de perfis de mídia e os perfis padrão, se ainda não existirem
+pub fn setup_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS media_profiles (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            liner_width REAL NOT NULL,
+            label_width REAL NOT NULL,
+            label_height REAL NOT NULL,
+            columns INTEGER NOT NULL,
+            horizontal_gap REAL NOT NULL,
+            vertical_gap REAL NOT NULL,
+            left_margin REAL NOT NULL DEFAULT 0,
+            top_margin REAL NOT NULL DEFAULT 0,
+            sensor TEXT NOT NULL DEFAULT 'gap',
+            created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )
+    .map_err(|e| format!("Erro ao criar tabela de perfis de mídia: {}", e))?;
+
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM media_profiles", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    if count == 0 {
+        println!("Criando perfis de mídia padrão");
+        for profile in MediaProfile::default_profiles() {
+            insert_profile(conn, &profile)?;
+        }
+    }
+
+    // Perfis padrão criados com gap de 3mm (24 dots): passam para 3,125mm, como no layout original
+    for profile in MediaProfile::default_profiles() {
+        conn.execute(
+            "UPDATE media_profiles SET vertical_gap = ? WHERE name = ? AND vertical_gap = 3.0",
+            params![DEFAULT_VERTICAL_GAP, profile.name],
+        )
+        .map_err(|e| format!("Erro ao atualizar perfis de mídia padrão: {}", e))?;
+    }
+
+    Ok(())
+}
+
+const PROFILE_COLUMNS: &str = "id, name, description, liner_width, label_width, label_height, columns, \
+     horizontal_gap, vertical_gap, left_margin, top_margin, sensor, created_at, updated_at";
+
+fn profile_from_row(row: &Row) -> rusqlite::Result<MediaProfile> {
+    let sensor: String = row.get(11)?;
+
+    Ok(MediaProfile {
+        id: Some(row.get(0)?),
+        name: row.get(1)?,
+        description: row.get(2)?,
+        liner_width: row.get(3)?,
+        label_width: row.get(4)?,
+        label_height: row.get(5)?,
+        columns: row.get(6)?,
+        horizontal_gap: row.get(7)?,
+        vertical_gap: row.get(8)?,
+        left_margin: row.get(9)?,
+        top_margin: row.get(10)?,
+        sensor: SensorType::parse(&sensor).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(11, rusqlite::types::Type::Text, e.into())
+        })?,
+        created_at: row.get(12)?,
+        updated_at: row.get(13)?,
+    })
+}
+
+pub fn list_profiles(conn: &Connection) -> Result<Vec<MediaProfile>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT {} FROM media_profiles ORDER BY id", PROFILE_COLUMNS))
+        .map_err(|e| e.to_string())?;
+
+    let profiles = stmt
+        .query_map([], profile_from_row)
+        .map_err(|e| e.to_string())?;
+
+    profiles
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+pub fn get_profile(conn: &Connection, id: i64) -> Result<MediaProfile, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM media_profiles WHERE id = ?", PROFILE_COLUMNS),
+        params![id],
+        profile_from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Perfil de mídia {} não encontrado", id))
+}
+
+pub fn insert_profile(conn: &Connection, profile: &MediaProfile) -> Result<MediaProfile, String> {
+    profile.validate()?;
+
+    conn.execute(
+        "INSERT INTO media_profiles (name, description, liner_width, label_width, label_height, columns,
+                                     horizontal_gap, vertical_gap, left_margin, top_margin, sensor)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            profile.name.trim(),
+            profile.description,
+            profile.liner_width,
+            profile.label_width,
+            profile.label_height,
+            profile.columns,
+            profile.horizontal_gap,
+            profile.vertical_gap,
+            profile.left_margin,
+            profile.top_margin,
+            profile.sensor.as_str(),
+        ],
+    )
+    .map_err(|e| format!("Erro ao salvar perfil de mídia: {}", e))?;
+
+    get_profile(conn, conn.last_insert_rowid())
+}
+
+pub fn update_profile(conn: &Connection, id: i64, profile: &MediaProfile) -> Result<MediaProfile, String> {
+    profile.validate()?;
+
+    let changed = conn
+        .execute(
+            "UPDATE media_profiles SET
+                name = ?,
+                description = ?,
+                liner_width = ?,
+                label_width = ?,
+                label_height = ?,
+                columns = ?,
+                horizontal_gap = ?,
+                vertical_gap = ?,
+                left_margin = ?,
+                top_margin = ?,
+                sensor = ?,
+                updated_at = CURRENT_TIMESTAMP
+             WHERE id = ?",
+            params![
+                profile.name.trim(),
+                profile.description,
+                profile.liner_width,
+                profile.label_width,
+                profile.label_height,
+                profile.columns,
+                profile.horizontal_gap,
+                profile.vertical_gap,
+                profile.left_margin,
+                profile.top_margin,
+                profile.sensor.as_str(),
+                id
+            ],
+        )
+        .map_err(|e| format!("Erro ao atualizar perfil de mídia: {}", e))?;
+
+    if changed == 0 {
+        return Err(format!("Perfil de mídia {} não encontrado", id));
+    }
+
+    get_profile(conn, id)
+}
+
+pub fn delete_profile(conn: &Connection, id: i64) -> Result<(), String> {
+    let changed = conn
+        .execute("DELETE FROM media_profiles WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+
+    if changed == 0 {
+        return Err(format!("Perfil de mídia {} não encontrado", id));
+    }
+
+    Ok(())
+}
+
+// Perfil escolhido na configuração da impressora, se houver
+pub fn load_profile(conn: &Connection, id: Option<i64>) -> Result<Option<MediaProfile>, String> {
+    id.map(|id| get_profile(conn, id)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dpi: u32) -> PrinterConfig {
+        PrinterConfig { dpi, ..PrinterConfig::default() }
+    }
+
+    fn profiles() -> (MediaProfile, MediaProfile) {
+        let mut profiles = MediaProfile::default_profiles().into_iter();
+        (profiles.next().unwrap(), profiles.next().unwrap())
+    }
+
+    fn at(dpi: u32) -> Resolution {
+        Resolution::new(dpi).unwrap()
+    }
+
+    // Colunas do perfil aplicado ao modelo padrão, em dots da impressora
+    fn column_x(profile: &MediaProfile, dpi: u32) -> Vec<u32> {
+        profile.apply(&LabelTemplate::default_template()).in_dots(at(dpi), &mut Vec::new()).column_x
+    }
+
+    #[test]
+    fn perfis_padrao_a_203_e_300_dpi() {
+        let (three, two) = profiles();
+
+        assert_eq!(
+            three.media(at(203)),
+            Media { width: 840, height: 176, gap: 25, sensor: SensorType::Gap, top: 0, resolution: at(203) }
+        );
+        assert_eq!(column_x(&three, 203), [12, 292, 572]);
+        assert_eq!((three.media(at(300)).width, three.media(at(300)).height, three.media(at(300)).gap), (1260, 264, 38));
+        assert_eq!(column_x(&three, 300), [18, 438, 858]);
+
+        assert_eq!((two.media(at(203)).width, two.media(at(203)).height, two.media(at(203)).gap), (832, 240, 25));
+        assert_eq!(column_x(&two, 203), [8, 424]);
+        assert_eq!((two.media(at(300)).width, two.media(at(300)).height, two.media(at(300)).gap), (1248, 360, 38));
+        assert_eq!(column_x(&two, 300), [12, 636]);
+    }
+
+    #[test]
+    fn perfil_padrao_gera_o_cabecalho_original() {
+        let (three, _) = profiles();
+
+        assert_eq!(crate::ppla_label::label_height_command(&three.media(at(203))), "Q176,25\r\n");
+    }
+
+    #[test]
+    fn perfis_padrao_sao_validos() {
+        let (three, two) = profiles();
+
+        assert_eq!(three.validate(), Ok(()));
+        assert_eq!(two.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validacao_recusa_medidas_impossiveis() {
+        let (three, _) = profiles();
+        let invalid = |change: fn(&mut MediaProfile)| {
+            let mut profile = three.clone();
+            change(&mut profile);
+            profile.validate().unwrap_err()
+        };
+
+        assert_eq!(invalid(|p| p.name = "  ".to_string()), "O perfil de mídia precisa de um nome");
+        assert_eq!(invalid(|p| p.label_height = 0.0), "Largura do liner e tamanho da etiqueta devem ser maiores que zero");
+        assert_eq!(invalid(|p| p.columns = 0), "O perfil de mídia precisa de pelo menos uma coluna");
+        assert_eq!(invalid(|p| p.left_margin = -1.0), "Espaços e margens não podem ser negativos");
+        assert_eq!(invalid(|p| p.top_margin = 22.0), "A margem superior deve ser menor que a altura da etiqueta");
+        assert_eq!(
+            invalid(|p| p.columns = 4),
+            "4 colunas de 33mm com 2mm de espaço e 1.5mm de margem ocupam 139.5mm, mais que o liner de 105mm"
+        );
+    }
+
+    #[test]
+    fn aplica_o_perfil_em_mm() {
+        let (_, two) = profiles();
+        let template = LabelTemplate::default_template();
+
+        let applied = two.apply(&template);
+
+        assert_eq!((applied.width, applied.height, applied.gap), (104.0, 30.0, 3.125));
+        assert_eq!(applied.column_x, [1.0, 53.0]);
+        assert_eq!(applied.column_width, Some(50.0));
+        assert_eq!(applied.elements, template.elements);
+
+        let at_203 = applied.in_dots(at(203), &mut Vec::new());
+        assert_eq!((at_203.width, at_203.height, at_203.gap, at_203.column_width), (832, 240, 25, Some(400)));
+    }
+
+    #[test]
+    fn resolve_com_e_sem_perfil() {
+        let (_, two) = profiles();
+        let template = LabelTemplate::default_template();
+
+        let (applied, media) = resolve(Some(&two), &template, &config(300)).unwrap();
+        assert_eq!(applied.column_x, [1.0, 53.0]);
+        assert_eq!((media.width, media.height, media.gap, media.resolution), (1248, 360, 38, at(300)));
+
+        let (same, media) = resolve(None, &template, &config(300)).unwrap();
+        assert_eq!(same.column_x, template.column_x);
+        assert_eq!((media.width, media.height, media.gap), (840, 176, 38));
+        assert_eq!(media.resolution, at(300));
+    }
+
+    #[test]
+    fn perfis_padrao_antigos_passam_para_o_gap_original() {
+        let conn = Connection::open_in_memory().unwrap();
+        setup_table(&conn).unwrap();
+        conn.execute("UPDATE media_profiles SET vertical_gap = 3.0", []).unwrap();
+
+        setup_table(&conn).unwrap();
+
+        let gaps: Vec<f64> = list_profiles(&conn).unwrap().iter().map(|p| p.vertical_gap).collect();
+        assert_eq!(gaps, [3.125, 3.125]);
+    }
+}
@@ -0,0 +1,196 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use crate::transport::PrinterTransport;
+
+// Porta padrão de impressão RAW (JetDirect / print servers)
+pub const DEFAULT_RAW_PORT: u16 = 9100;
+
+// Completa o endereço com a porta padrão quando ela não foi informada
+pub fn normalize_address(address: &str) -> String {
+    let address = address.trim().trim_end_matches('/');
+
+    let has_port = if address.starts_with('[') {
+        address.contains("]:")
+    } else {
+        address.contains(':')
+    };
+
+    if has_port {
+        address.to_string()
+    } else {
+        format!("{}:{}", address, DEFAULT_RAW_PORT)
+    }
+}
+
+// Transporte por socket TCP cru (porta 9100): os bytes vão direto para o print server
+pub struct TcpTransport {
+    address: String,
+    stream: TcpStream,
+}
+
+impl TcpTransport {
+    pub fn connect(address: &str, connect_timeout: Duration, write_timeout: Duration) -> Result<Self, String> {
+        let address = normalize_address(address);
+
+        let socket_addrs = address
+            .to_socket_addrs()
+            .map_err(|e| format!("Endereço de impressora inválido '{}': {}", address, e))?;
+
+        // Tenta cada endereço resolvido até conseguir conectar
+        let mut last_error = None;
+        for socket_addr in socket_addrs {
+            match TcpStream::connect_timeout(&socket_addr, connect_timeout) {
+                Ok(stream) => {
+                    stream
+                        .set_write_timeout(Some(write_timeout))
+                        .map_err(|e| format!("Erro ao configurar tempo limite de escrita: {}", e))?;
+                    stream
+                        .set_nodelay(true)
+                        .map_err(|e| format!("Erro ao configurar conexão: {}", e))?;
+
+                    println!("Conectado à impressora de rede {}", address);
+                    return Ok(Self { address, stream });
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(match last_error {
+            Some(e) => format!("Falha ao conectar à impressora de rede {}: {}", address, e),
+            None => format!("Endereço de impressora não encontrado: {}", address),
+        })
+    }
+}
+
+impl PrinterTransport for TcpTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        self.stream
+            .write_all(data)
+            .and_then(|_| self.stream.flush())
+            .map_err(|e| format!("Erro ao enviar dados para {}: {}", self.address, e))
+    }
+
+    fn read(&mut self, buffer: &mut [u8], timeout: Duration) -> Result<usize, String> {
+        self.stream
+            .set_read_timeout(Some(timeout))
+            .map_err(|e| format!("Erro ao configurar tempo limite de leitura: {}", e))?;
+
+        match self.stream.read(buffer) {
+            Ok(count) => Ok(count),
+            // Sem resposta dentro do tempo limite
+            Err(e) if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => Ok(0),
+            Err(e) => Err(format!("Erro ao ler resposta de {}: {}", self.address, e)),
+        }
+    }
+
+    fn supports_read(&self) -> bool {
+        true
+    }
+
+    fn describe(&self) -> String {
+        format!("impressora de rede {}", self.address)
+    }
+
+    fn close(&mut self) -> Result<(), String> {
+        // Encerra o envio para o print server saber que o trabalho terminou
+        match self.stream.shutdown(Shutdown::Both) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotConnected => Ok(()),
+            Err(e) => Err(format!("Erro ao encerrar conexão com {}: {}", self.address, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::send_job;
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Instant;
+
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn normalize_address_usa_a_porta_9100() {
+        assert_eq!(normalize_address("10.0.0.5"), "10.0.0.5:9100");
+        assert_eq!(normalize_address(" 10.0.0.5/ "), "10.0.0.5:9100");
+        assert_eq!(normalize_address("10.0.0.5:6101"), "10.0.0.5:6101");
+        assert_eq!(normalize_address("impressora.local"), "impressora.local:9100");
+        assert_eq!(normalize_address("[::1]"), "[::1]:9100");
+        assert_eq!(normalize_address("[::1]:9101"), "[::1]:9101");
+    }
+
+    #[test]
+    fn envia_o_trabalho_para_o_servidor_local() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut received = Vec::new();
+            stream.read_to_end(&mut received).unwrap();
+            received
+        });
+
+        let mut transport = TcpTransport::connect(&address, TIMEOUT, TIMEOUT).unwrap();
+        send_job(&mut transport, b"N\r\nA10,10,0,3,1,1,N,\"TESTE\"\r\nP1\r\n").unwrap();
+
+        assert_eq!(server.join().unwrap(), b"N\r\nA10,10,0,3,1,1,N,\"TESTE\"\r\nP1\r\n");
+    }
+
+    #[test]
+    fn le_a_resposta_da_impressora() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 2];
+            stream.read_exact(&mut request).unwrap();
+            stream.write_all(b"@0\r\n").unwrap();
+            request
+        });
+
+        let mut transport = TcpTransport::connect(&address, TIMEOUT, TIMEOUT).unwrap();
+        transport.send(b"\x01A").unwrap();
+        let mut buffer = [0u8; 16];
+        let count = transport.read(&mut buffer, TIMEOUT).unwrap();
+
+        assert_eq!(&buffer[..count], b"@0\r\n");
+        assert_eq!(&server.join().unwrap(), b"\x01A");
+    }
+
+    #[test]
+    fn porta_fechada_gera_erro_de_conexao() {
+        // Porta que acabou de ser liberada: nada escutando nela
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+
+        let error = TcpTransport::connect(&address, TIMEOUT, TIMEOUT).err().unwrap();
+
+        assert!(error.starts_with("Falha ao conectar à impressora de rede"), "{}", error);
+    }
+
+    #[test]
+    fn endereco_sem_resposta_respeita_o_tempo_limite() {
+        // Endereço de documentação (TEST-NET-1): a conexão não é recusada, só não responde.
+        // Em redes com proxy transparente a conexão pode até ser aceita; o que importa é não travar.
+        let started = Instant::now();
+
+        let result = TcpTransport::connect("192.0.2.1:9100", Duration::from_millis(300), TIMEOUT);
+
+        if let Err(error) = result {
+            assert!(error.starts_with("Falha ao conectar à impressora de rede"), "{}", error);
+        }
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn endereco_invalido_gera_erro() {
+        let error = TcpTransport::connect("não é um host:abc", TIMEOUT, TIMEOUT).err().unwrap();
+
+        assert!(error.starts_with("Endereço de impressora inválido"), "{}", error);
+    }
+}
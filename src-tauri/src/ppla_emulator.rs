@@ -0,0 +1,646 @@
+use crate::barcode::{self, Symbology};
+use crate::bitmap_font::{glyph_pixel, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::label_metrics::{font_cell, CHAR_SPACING, HUMAN_READABLE_FONT, HUMAN_READABLE_GAP};
+use crate::label_render::PRINTHEAD_WIDTH_MM;
+use crate::ppla_parser::{self, LineMode, ParsedCommand, PplaCommand, PplaDiagnostic};
+use crate::resolution::Resolution;
+
+// Maior comprimento de etiqueta desenhado pelo emulador (1 m); q/Q acima da cabeça de impressão
+// ou desse comprimento viram diagnóstico em vez de alocar a imagem
+const MAX_LABEL_LENGTH_MM: f64 = 1000.0;
+
+// Tipo do comando B (ou b) -> simbologia (mesma convenção dos geradores)
+fn barcode_symbology(kind: &str) -> Option<Symbology> {
+    match kind {
+        "E30" => Some(Symbology::Ean13),
+        "E80" => Some(Symbology::Ean8),
+        "UA0" => Some(Symbology::UpcA),
+        "1" | "1B" => Some(Symbology::Code128),
+        "2" => Some(Symbology::Itf14),
+        "Q" => Some(Symbology::Qr),
+        "D" => Some(Symbology::DataMatrix),
+        _ => None,
+    }
+}
+
+// Imagem monocromática da etiqueta, em dots
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    pub dpi: u32,
+    pixels: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DrawMode {
+    Black,
+    White,
+    Xor,
+}
+
+impl From<LineMode> for DrawMode {
+    fn from(mode: LineMode) -> Self {
+        match mode {
+            LineMode::Black => DrawMode::Black,
+            LineMode::Xor => DrawMode::Xor,
+            LineMode::White => DrawMode::White,
+        }
+    }
+}
+
+impl Bitmap {
+    pub fn new(width: u32, height: u32, dpi: u32) -> Self {
+        Self {
+            width,
+            height,
+            dpi,
+            pixels: vec![false; width as usize * height as usize],
+        }
+    }
+
+    // Indica se o dot é preto
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        x < self.width && y < self.height && self.pixels[(y * self.width + x) as usize]
+    }
+
+    pub fn black_pixels(&self) -> usize {
+        self.pixels.iter().filter(|p| **p).count()
+    }
+
+    // Pinta um dot; devolve false quando ele cai fora da etiqueta (e é cortado)
+    fn plot(&mut self, x: i64, y: i64, mode: DrawMode) -> bool {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return false;
+        }
+
+        let pixel = &mut self.pixels[(y as u32 * self.width + x as u32) as usize];
+        *pixel = match mode {
+            DrawMode::Black => true,
+            DrawMode::White => false,
+            DrawMode::Xor => !*pixel,
+        };
+        true
+    }
+
+    // Gira a imagem 180° (impressão de baixo para cima, ZB)
+    fn rotated_180(&self) -> Bitmap {
+        let mut rotated = self.clone();
+        rotated.pixels.reverse();
+        rotated
+    }
+}
+
+// Etiqueta resultante de um comando P
+#[derive(Debug, Clone)]
+pub struct EmulatedLabel {
+    pub bitmap: Bitmap,
+    pub copies: u32, // Quantidade de etiquetas iguais impressas
+}
+
+// Resultado da emulação
+#[derive(Debug, Clone)]
+pub struct Emulation {
+    pub commands: usize,
+    pub labels: Vec<EmulatedLabel>,
+    pub warnings: Vec<PplaDiagnostic>, // Problemas que não impedem a impressão (ex.: elementos cortados)
+}
+
+// Tamanho da etiqueta usado enquanto o fluxo não define q/Q
+#[derive(Debug, Clone, Copy)]
+pub struct EmulatorSetup {
+    pub width: u32,
+    pub height: u32,
+    pub dpi: u32,
+}
+
+// Fonte e ampliação de um texto
+struct TextStyle {
+    cell: (u32, u32),
+    h_mult: u32,
+    v_mult: u32,
+    reverse: bool,
+}
+
+// Desenha relativo a uma origem, com a rotação do PPLA (0 a 3, sentido horário)
+struct Canvas<'a> {
+    bitmap: &'a mut Bitmap,
+    x: i64,
+    y: i64,
+    rotation: u8,
+    clipped: bool,
+}
+
+impl Canvas<'_> {
+    fn plot(&mut self, dx: i64, dy: i64, mode: DrawMode) {
+        let (x, y) = match self.rotation {
+            1 => (self.x - dy, self.y + dx),
+            2 => (self.x - dx, self.y - dy),
+            3 => (self.x + dy, self.y - dx),
+            _ => (self.x + dx, self.y + dy),
+        };
+
+        if !self.bitmap.plot(x, y, mode) {
+            self.clipped = true;
+        }
+    }
+
+    fn fill(&mut self, dx: i64, dy: i64, width: i64, height: i64, mode: DrawMode) {
+        for row in dy..dy + height {
+            for column in dx..dx + width {
+                self.plot(column, row, mode);
+            }
+        }
+    }
+
+    // Texto na fonte da impressora; devolve a largura ocupada
+    fn text(&mut self, dx: i64, dy: i64, style: &TextStyle, data: &str) -> i64 {
+        let (cell_width, cell_height) = style.cell;
+        let glyph_width = cell_width - CHAR_SPACING;
+        let glyph_height = cell_height - cell_height / 4; // Espaço para as descendentes
+        let (h_mult, v_mult) = (style.h_mult.max(1) as i64, style.v_mult.max(1) as i64);
+        let advance = (cell_width as i64) * h_mult;
+        let (glyph_mode, background) = if style.reverse {
+            (DrawMode::White, true)
+        } else {
+            (DrawMode::Black, false)
+        };
+
+        for (index, c) in data.chars().enumerate() {
+            let left = dx + index as i64 * advance;
+
+            if background {
+                self.fill(left, dy, advance, cell_height as i64 * v_mult, DrawMode::Black);
+            }
+
+            for row in 0..glyph_height {
+                for column in 0..glyph_width {
+                    let source_column = column * GLYPH_WIDTH / glyph_width;
+                    let source_row = row * GLYPH_HEIGHT / glyph_height;
+                    if glyph_pixel(c, source_column, source_row) {
+                        self.fill(
+                            left + column as i64 * h_mult,
+                            dy + row as i64 * v_mult,
+                            h_mult,
+                            v_mult,
+                            glyph_mode,
+                        );
+                    }
+                }
+            }
+        }
+
+        data.chars().count() as i64 * advance
+    }
+}
+
+struct Emulator {
+    setup: EmulatorSetup,
+    width: u32,
+    height: u32,
+    direction: char,
+    image: Option<Bitmap>,
+    labels: Vec<EmulatedLabel>,
+    warnings: Vec<PplaDiagnostic>,
+    errors: Vec<PplaDiagnostic>,
+}
+
+impl Emulator {
+    fn image(&mut self) -> &mut Bitmap {
+        let (width, height, dpi) = (self.width, self.height, self.setup.dpi);
+        self.image.get_or_insert_with(|| Bitmap::new(width, height, dpi))
+    }
+
+    // Confere o tamanho da etiqueta antes de aceitá-lo
+    fn check_size(&self, width: u32, height: u32) -> Result<(), String> {
+        let resolution = Resolution::new(self.setup.dpi).unwrap_or_default();
+        let max_width = resolution.dots(PRINTHEAD_WIDTH_MM);
+        let max_height = resolution.dots(MAX_LABEL_LENGTH_MM);
+
+        if width > max_width {
+            return Err(format!(
+                "Largura de {} dots maior que a cabeça de impressão ({} dots a {} dpi)",
+                width,
+                max_width,
+                resolution.dpi()
+            ));
+        }
+        if height > max_height {
+            return Err(format!(
+                "Altura de {} dots maior que o emulador aceita ({} dots, {} mm a {} dpi)",
+                height,
+                max_height,
+                MAX_LABEL_LENGTH_MM,
+                resolution.dpi()
+            ));
+        }
+        Ok(())
+    }
+
+    fn canvas(&mut self, x: i32, y: i32, rotation: u8) -> Canvas<'_> {
+        Canvas {
+            bitmap: self.image(),
+            x: x as i64,
+            y: y as i64,
+            rotation,
+            clipped: false,
+        }
+    }
+
+    fn diagnostic(parsed: &ParsedCommand, message: String) -> PplaDiagnostic {
+        PplaDiagnostic {
+            line: parsed.line,
+            offset: parsed.offset,
+            message,
+        }
+    }
+
+    fn warn(&mut self, parsed: &ParsedCommand, message: String) {
+        self.warnings.push(Self::diagnostic(parsed, message));
+    }
+
+    fn error(&mut self, parsed: &ParsedCommand, message: String) {
+        self.errors.push(Self::diagnostic(parsed, message));
+    }
+
+    fn barcode(&mut self, parsed: &ParsedCommand) -> Result<bool, String> {
+        let (x, y, rotation, kind, narrow, wide, height, human_readable, data) = match &parsed.command {
+            PplaCommand::Barcode { x, y, rotation, kind, narrow, wide, height, human_readable, data } => {
+                (*x, *y, *rotation, kind, *narrow, *wide, *height, *human_readable, data)
+            }
+            _ => return Ok(false),
+        };
+
+        let symbology = match barcode_symbology(kind) {
+            Some(symbology) if !symbology.is_2d() => symbology,
+            _ => {
+                self.warn(parsed, format!("Código de barras tipo '{}' não é desenhado pelo emulador", kind));
+                return Ok(false);
+            }
+        };
+        let modules = barcode::modules(symbology, data, barcode::wide_ratio(narrow, wide))?.unwrap_or_default();
+        let digits = barcode::validate(symbology, data)?;
+
+        let narrow = narrow.max(1) as i64;
+        let height = height as i64;
+        let mut canvas = self.canvas(x, y, rotation);
+
+        for (index, bar) in modules.iter().enumerate() {
+            if *bar {
+                canvas.fill(index as i64 * narrow, 0, narrow, height, DrawMode::Black);
+            }
+        }
+
+        if human_readable {
+            // Dígitos centralizados abaixo das barras
+            let style = TextStyle {
+                cell: font_cell(HUMAN_READABLE_FONT).unwrap_or((10, 12)),
+                h_mult: 1,
+                v_mult: 1,
+                reverse: false,
+            };
+            let text_width = digits.len() as i64 * style.cell.0 as i64;
+            let bars_width = modules.len() as i64 * narrow;
+            canvas.text((bars_width - text_width) / 2, height + HUMAN_READABLE_GAP as i64, &style, &digits);
+        }
+
+        Ok(canvas.clipped)
+    }
+
+    // Os módulos de QR e DataMatrix não são gerados: a área do símbolo aparece como um quadrado vazado
+    fn barcode_2d(&mut self, parsed: &ParsedCommand) -> Result<bool, String> {
+        let (x, y, kind, module, data) = match &parsed.command {
+            PplaCommand::Barcode2D { x, y, kind, module, data } => (*x, *y, kind, *module, data),
+            _ => return Ok(false),
+        };
+
+        let symbology = match barcode_symbology(kind) {
+            Some(symbology) if symbology.is_2d() => symbology,
+            _ => return Err(format!("Tipo de código 2D desconhecido: '{}'", kind)),
+        };
+        barcode::validate(symbology, data)?;
+        let side = barcode::side_2d(symbology, data).unwrap_or(0) as i64 * module.max(1) as i64;
+
+        self.warn(
+            parsed,
+            format!("Código {} desenhado apenas como área reservada ({} dots)", symbology.name(), side),
+        );
+
+        let mut canvas = self.canvas(x, y, 0);
+        let border = module.max(1) as i64;
+        canvas.fill(0, 0, side, border, DrawMode::Black);
+        canvas.fill(0, side - border, side, border, DrawMode::Black);
+        canvas.fill(0, 0, border, side, DrawMode::Black);
+        canvas.fill(side - border, 0, border, side, DrawMode::Black);
+        Ok(canvas.clipped)
+    }
+
+    fn graphic(&mut self, x: i32, y: i32, bytes_per_row: u32, data: &[u8]) -> bool {
+        let mut canvas = self.canvas(x, y, 0);
+
+        for (index, byte) in data.iter().enumerate() {
+            let row = (index / bytes_per_row as usize) as i64;
+            let column = (index % bytes_per_row as usize) as i64 * 8;
+            for bit in 0..8 {
+                // No GW, bit 0 imprime (preto) e bit 1 fica em branco
+                if byte & (0x80 >> bit) == 0 {
+                    canvas.plot(column + bit as i64, row, DrawMode::Black);
+                }
+            }
+        }
+
+        canvas.clipped
+    }
+
+    fn print(&mut self, labels: u32, copies: u32) {
+        let bitmap = match self.image.clone() {
+            Some(bitmap) => bitmap,
+            None => Bitmap::new(self.width, self.height, self.setup.dpi),
+        };
+        let bitmap = if self.direction == 'B' { bitmap.rotated_180() } else { bitmap };
+
+        self.labels.push(EmulatedLabel {
+            bitmap,
+            copies: labels * copies.max(1),
+        });
+    }
+
+    fn run(&mut self, parsed: &ParsedCommand) {
+        let clipped = match &parsed.command {
+            PplaCommand::ClearImage => {
+                self.image = None;
+                false
+            }
+            PplaCommand::LabelWidth(width) => {
+                match self.check_size(*width, self.height) {
+                    Ok(()) => {
+                        self.width = *width;
+                        self.image = None;
+                    }
+                    Err(e) => self.error(parsed, e),
+                }
+                false
+            }
+            PplaCommand::LabelHeight { height, .. } => {
+                match self.check_size(self.width, *height) {
+                    Ok(()) => {
+                        self.height = *height;
+                        self.image = None;
+                    }
+                    Err(e) => self.error(parsed, e),
+                }
+                false
+            }
+            PplaCommand::PrintDirection(direction) => {
+                self.direction = *direction;
+                false
+            }
+            PplaCommand::Text { x, y, rotation, font, h_mult, v_mult, reverse, data } => match font_cell(font) {
+                Some(cell) => {
+                    let style = TextStyle {
+                        cell,
+                        h_mult: *h_mult,
+                        v_mult: *v_mult,
+                        reverse: *reverse,
+                    };
+                    let mut canvas = self.canvas(*x, *y, *rotation);
+                    canvas.text(0, 0, &style, data);
+                    canvas.clipped
+                }
+                None => {
+                    self.error(parsed, format!("Fonte desconhecida: '{}'", font));
+                    false
+                }
+            },
+            PplaCommand::Barcode { .. } => match self.barcode(parsed) {
+                Ok(clipped) => clipped,
+                Err(e) => {
+                    self.error(parsed, e);
+                    false
+                }
+            },
+            PplaCommand::Barcode2D { .. } => match self.barcode_2d(parsed) {
+                Ok(clipped) => clipped,
+                Err(e) => {
+                    self.error(parsed, e);
+                    false
+                }
+            },
+            PplaCommand::Line { x, y, width, height, mode } => {
+                let mut canvas = self.canvas(*x, *y, 0);
+                canvas.fill(0, 0, *width as i64, *height as i64, (*mode).into());
+                canvas.clipped
+            }
+            PplaCommand::Box { x, y, thickness, x_end, y_end } => {
+                let width = (*x_end - *x) as i64;
+                let height = (*y_end - *y) as i64;
+                let thickness = *thickness as i64;
+                let mut canvas = self.canvas(*x, *y, 0);
+                canvas.fill(0, 0, width, thickness, DrawMode::Black);
+                canvas.fill(0, height - thickness, width, thickness, DrawMode::Black);
+                canvas.fill(0, 0, thickness, height, DrawMode::Black);
+                canvas.fill(width - thickness, 0, thickness, height, DrawMode::Black);
+                canvas.clipped
+            }
+            PplaCommand::Graphic { data, .. } if data.is_empty() => {
+                self.warn(parsed, "GW sem dados de imagem: nada é desenhado".to_string());
+                false
+            }
+            PplaCommand::Graphic { x, y, bytes_per_row, data, .. } => self.graphic(*x, *y, *bytes_per_row, data),
+            PplaCommand::StoredGraphic { name, .. } => {
+                self.warn(parsed, format!("Gráfico '{}' fica na memória da impressora e não é desenhado", name));
+                false
+            }
+            PplaCommand::Print { labels, copies } => {
+                self.print(*labels, *copies);
+                false
+            }
+            PplaCommand::VerticalOffset(_)
+            | PplaCommand::Options(_)
+            | PplaCommand::TopOfForm(_)
+            | PplaCommand::Density(_)
+            | PplaCommand::Speed(_)
+            | PplaCommand::CodePage(_)
+            | PplaCommand::Escape(_) => false,
+        };
+
+        if clipped {
+            self.warn(parsed, "Elemento ultrapassa a área da etiqueta e será cortado".to_string());
+        }
+    }
+}
+
+// Desenha os comandos em bitmaps, um por comando P
+pub fn emulate(commands: &[ParsedCommand], setup: EmulatorSetup) -> Result<Emulation, Vec<PplaDiagnostic>> {
+    let mut emulator = Emulator {
+        setup,
+        width: setup.width,
+        height: setup.height,
+        direction: 'T',
+        image: None,
+        labels: Vec::new(),
+        warnings: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    if let Err(e) = emulator.check_size(setup.width, setup.height) {
+        return Err(vec![PplaDiagnostic {
+            line: 1,
+            offset: 0,
+            message: format!("Tamanho configurado: {}", e),
+        }]);
+    }
+
+    for command in commands {
+        emulator.run(command);
+    }
+
+    if emulator.labels.iter().any(|label| label.bitmap.black_pixels() == 0) {
+        let (line, offset) = commands.last().map(|c| (c.line, c.offset)).unwrap_or((1, 0));
+        emulator.warnings.push(PplaDiagnostic {
+            line,
+            offset,
+            message: "Etiqueta em branco: nenhum elemento desenhado".to_string(),
+        });
+    }
+
+    if emulator.labels.is_empty() {
+        let (line, offset) = commands.last().map(|c| (c.line, c.offset)).unwrap_or((1, 0));
+        emulator.warnings.push(PplaDiagnostic {
+            line,
+            offset,
+            message: "Nenhum comando P: nada seria impresso".to_string(),
+        });
+    }
+
+    if !emulator.errors.is_empty() {
+        return Err(emulator.errors);
+    }
+
+    Ok(Emulation {
+        commands: commands.len(),
+        labels: emulator.labels,
+        warnings: emulator.warnings,
+    })
+}
+
+// Interpreta e desenha um fluxo de bytes
+pub fn emulate_bytes(data: &[u8], setup: EmulatorSetup) -> Result<Emulation, Vec<PplaDiagnostic>> {
+    emulate(&ppla_parser::parse(data)?, setup)
+}
+
+// Junta os diagnósticos em uma mensagem de erro
+pub fn describe_diagnostics(diagnostics: &[PplaDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETUP: EmulatorSetup = EmulatorSetup { width: 840, height: 176, dpi: 203 };
+
+    fn messages(diagnostics: &[PplaDiagnostic]) -> Vec<&str> {
+        diagnostics.iter().map(|d| d.message.as_str()).collect()
+    }
+
+    #[test]
+    fn desenha_uma_etiqueta_por_comando_p() {
+        let emulation = emulate_bytes(b"N\r\nq400\r\nQ100,24\r\nLO10,10,20,5\r\nP2,3\r\nN\r\nX10,10,2,50,50\r\nP1\r\n", SETUP).unwrap();
+
+        assert_eq!(emulation.labels.len(), 2);
+        assert_eq!(emulation.labels[0].copies, 6);
+        assert_eq!((emulation.labels[0].bitmap.width, emulation.labels[0].bitmap.height), (400, 100));
+        assert_eq!(emulation.labels[0].bitmap.black_pixels(), 100);
+        assert!(emulation.labels[0].bitmap.get(10, 10) && !emulation.labels[0].bitmap.get(30, 10));
+        assert!(emulation.labels[1].bitmap.get(49, 49) && !emulation.labels[1].bitmap.get(30, 30));
+        assert!(emulation.warnings.is_empty(), "{:?}", emulation.warnings);
+    }
+
+    #[test]
+    fn largura_maior_que_a_cabeca_gera_diagnostico_sem_alocar() {
+        let errors = emulate_bytes(b"N\r\nq4000000000\r\nP1\r\n", SETUP).unwrap_err();
+
+        assert_eq!(messages(&errors), ["Largura de 4000000000 dots maior que a cabeça de impressão (864 dots a 203 dpi)"]);
+        assert_eq!(errors[0].line, 2);
+    }
+
+    #[test]
+    fn limite_da_largura_acompanha_a_resolucao() {
+        let setup = EmulatorSetup { dpi: 300, ..SETUP };
+
+        assert!(emulate_bytes(b"q1296\r\nLO0,0,1,1\r\nP1\r\n", setup).is_ok());
+        assert_eq!(
+            messages(&emulate_bytes(b"q1297\r\nP1\r\n", setup).unwrap_err()),
+            ["Largura de 1297 dots maior que a cabeça de impressão (1296 dots a 300 dpi)"]
+        );
+    }
+
+    #[test]
+    fn altura_absurda_gera_diagnostico_sem_alocar() {
+        let errors = emulate_bytes(b"N\r\nQ4000000000,24\r\nP1\r\n", SETUP).unwrap_err();
+
+        assert_eq!(messages(&errors), ["Altura de 4000000000 dots maior que o emulador aceita (8000 dots, 1000 mm a 203 dpi)"]);
+    }
+
+    #[test]
+    fn tamanho_configurado_fora_do_limite_gera_diagnostico() {
+        let setup = EmulatorSetup { width: 5000, ..SETUP };
+
+        let errors = emulate_bytes(b"P1\r\n", setup).unwrap_err();
+
+        assert_eq!(messages(&errors), ["Tamanho configurado: Largura de 5000 dots maior que a cabeça de impressão (864 dots a 203 dpi)"]);
+    }
+
+    #[test]
+    fn avisa_elemento_cortado_e_etiqueta_em_branco() {
+        let clipped = emulate_bytes(b"N\r\nq100\r\nQ50,24\r\nLO90,10,20,5\r\nP1\r\n", SETUP).unwrap();
+        assert_eq!(messages(&clipped.warnings), ["Elemento ultrapassa a área da etiqueta e será cortado"]);
+
+        let blank = emulate_bytes(b"N\r\nP1\r\n", SETUP).unwrap();
+        assert_eq!(messages(&blank.warnings), ["Etiqueta em branco: nenhum elemento desenhado"]);
+
+        let nothing = emulate_bytes(b"N\r\nA10,10,0,2,1,1,N,\"X\"\r\n", SETUP).unwrap();
+        assert_eq!(messages(&nothing.warnings), ["Nenhum comando P: nada seria impresso"]);
+    }
+
+    #[test]
+    fn fonte_desconhecida_gera_erro() {
+        let errors = emulate_bytes(b"N\r\nA10,10,0,9,1,1,N,\"X\"\r\nP1\r\n", SETUP).unwrap_err();
+
+        assert_eq!(messages(&errors), ["Fonte desconhecida: '9'"]);
+    }
+
+    #[test]
+    fn largura_do_ean13_desenhado_bate_com_o_layout() {
+        let emulation = emulate_bytes(b"N\r\nB10,10,0,E30,2,6,45,N,\"7891234567895\"\r\nP1\r\n", SETUP).unwrap();
+        let bitmap = &emulation.labels[0].bitmap;
+        let last_bar = (0..bitmap.width).rev().find(|x| bitmap.get(*x, 20)).unwrap();
+        let (width, _) = crate::label_metrics::PPLA_FONTS.barcode_size("ean13", 2, 6, 45, false, "7891234567895").unwrap();
+
+        assert_eq!(last_bar + 1 - 10, width);
+        assert_eq!(width, 95 * 2);
+    }
+
+    #[test]
+    fn teste_de_impressao_passa_pelo_emulador() {
+        let emulation = emulate_bytes(crate::PRINT_TEST_CONTENT, SETUP).unwrap();
+
+        assert_eq!(emulation.labels.len(), 1);
+        assert_eq!((emulation.labels[0].bitmap.width, emulation.labels[0].bitmap.height), (822, 1218));
+        assert!(emulation.labels[0].bitmap.black_pixels() > 0);
+        assert_eq!(messages(&emulation.warnings)[0], "GW sem dados de imagem: nada é desenhado");
+        assert_eq!(emulation.warnings[0].line, 2);
+    }
+
+    #[test]
+    fn comando_exato_do_dump_aponta_o_gw_incompleto() {
+        let errors = emulate_bytes(&crate::ARGOX_PPLA_EXACT, SETUP).unwrap_err();
+
+        assert_eq!(messages(&errors), ["GW com dados incompletos: esperados 416 bytes de imagem, recebidos 13"]);
+    }
+}
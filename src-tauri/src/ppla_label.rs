@@ -0,0 +1,135 @@
+use crate::barcode::Symbology;
+use crate::code_page::CodePage;
+use crate::label_escape;
+use crate::label_render::LabelWriter;
+use crate::label_template::{LabelTemplate, TemplateElement};
+use crate::media_profile::{Media, SensorType};
+use crate::PrinterConfig;
+
+// Rotação em graus -> parâmetro de rotação (0 a 3)
+pub fn rotation_code(rotation: u16) -> u16 {
+    rotation / 90
+}
+
+// Comando Q: altura da etiqueta e gap, altura da marca preta ("B") ou 0 para papel contínuo
+pub fn label_height_command(media: &Media) -> String {
+    match media.sensor {
+        SensorType::Gap => format!("Q{},{}\r\n", media.height, media.gap),
+        SensorType::BlackMark => format!("Q{},B{}\r\n", media.height, media.gap),
+        SensorType::Continuous => format!("Q{},0\r\n", media.height),
+    }
+}
+
+// Tipo do código de barras usado no comando B; QR e DataMatrix usam o comando b
+fn barcode_type(symbology: Symbology) -> &'static str {
+    match symbology {
+        Symbology::Ean13 => "E30",
+        Symbology::Ean8 => "E80",
+        Symbology::UpcA => "UA0",
+        Symbology::Code128 => "1B",
+        Symbology::Itf14 => "2",
+        Symbology::Qr => "Q",
+        Symbology::DataMatrix => "D",
+    }
+}
+
+// Gerador PPLA (Argox), no formato que o sistema sempre enviou
+pub struct PplaWriter;
+
+impl LabelWriter for PplaWriter {
+    fn begin(&self, out: &mut Vec<u8>, media: &Media, config: &PrinterConfig) -> Result<(), String> {
+        out.extend_from_slice(b"N\r\n"); // Limpa buffer
+        out.extend_from_slice(b"ZN\r\n"); // Direção normal
+        out.extend_from_slice(format!("q{}\r\n", media.width).as_bytes()); // Largura da fita
+        out.extend_from_slice(label_height_command(media).as_bytes()); // Altura da etiqueta e gap
+        out.extend_from_slice(format!("D{}\r\n", config.darkness).as_bytes()); // Densidade (1-15)
+        out.extend_from_slice(format!("S{}\r\n", config.speed).as_bytes()); // Velocidade (1-4)
+        out.extend_from_slice(b"O\r\n"); // Orientação padrão
+        out.extend_from_slice(b"JF\r\n"); // Configuração de impressão
+        out.extend_from_slice(b"Z0\r\n"); // Sem deslocamento vertical
+        Ok(())
+    }
+
+    fn select_code_page(&self, out: &mut Vec<u8>, page: CodePage) -> Result<(), String> {
+        out.extend_from_slice(format!("I8,{},001\r\n", page.epl_table()).as_bytes()); // 8 bits, tabela de caracteres, país (001 = EUA)
+        Ok(())
+    }
+
+    fn element(&self, out: &mut Vec<u8>, element: &TemplateElement<u32>) -> Result<(), String> {
+        epl_element(out, element, barcode_type)
+    }
+
+    fn end(&self, out: &mut Vec<u8>, _template: &LabelTemplate<u32>, copies: u32) -> Result<(), String> {
+        out.extend_from_slice(format!("P{}\r\n", copies).as_bytes());
+        Ok(())
+    }
+}
+
+// Elemento no formato comum ao PPLA e ao PPLB/EPL2, que só diferem no tipo do código de barras
+pub fn epl_element(
+    out: &mut Vec<u8>,
+    element: &TemplateElement<u32>,
+    barcode_type: fn(Symbology) -> &'static str,
+) -> Result<(), String> {
+    let command = match element {
+        TemplateElement::Text { x, y, rotation, font, h_mult, v_mult, reverse, content, .. } => format!(
+            "A{},{},{},{},{},{},{},{}\r\n",
+            x,
+            y,
+            rotation_code(*rotation),
+            font,
+            h_mult,
+            v_mult,
+            if *reverse { "R" } else { "N" },
+            label_escape::epl_quoted(content)
+        ),
+        TemplateElement::Barcode { x, y, rotation, symbology, narrow, wide, height, human_readable, content, .. } => {
+            let symbology = Symbology::parse(symbology)?;
+            let data = label_escape::epl_quoted(&label_escape::barcode_data(symbology, content)?);
+
+            if symbology.is_2d() {
+                // b<x>,<y>,<tipo>,s<tamanho do módulo>,"dados"
+                format!("b{},{},{},s{},{}\r\n", x, y, barcode_type(symbology), narrow, data)
+            } else {
+                format!(
+                    "B{},{},{},{},{},{},{},{},{}\r\n",
+                    x,
+                    y,
+                    rotation_code(*rotation),
+                    barcode_type(symbology),
+                    narrow,
+                    wide,
+                    height,
+                    if *human_readable { "B" } else { "N" },
+                    data
+                )
+            }
+        }
+        TemplateElement::Line { x, y, width, height } => format!("LO{},{},{},{}\r\n", x, y, width, height),
+        TemplateElement::Box { x, y, width, height, thickness } => {
+            format!("X{},{},{},{},{}\r\n", x, y, thickness, x + width, y + height)
+        }
+        TemplateElement::Image { x, y, name } => {
+            format!("GG{},{},\"{}\"\r\n", x, y, label_escape::graphic_name(name)?)
+        }
+    };
+
+    out.extend_from_slice(command.as_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::label_render::LabelLanguage;
+    use crate::test_support;
+
+    // Saída de referência do modelo padrão: qualquer diferença muda o que a impressora recebe
+    const DEFAULT_TEMPLATE_GOLDEN: &[u8] = include_bytes!("fixtures/default_template.ppla");
+
+    #[test]
+    fn modelo_padrao_gera_a_saida_de_referencia() {
+        let batch = test_support::default_template_batch(LabelLanguage::Ppla);
+
+        test_support::assert_golden(&batch.data, DEFAULT_TEMPLATE_GOLDEN);
+    }
+}
@@ -0,0 +1,571 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::code_page::CodePage;
+
+// Comando PPLA interpretado
+#[derive(Debug, Clone, PartialEq)]
+pub enum PplaCommand {
+    ClearImage,                         // N
+    LabelWidth(u32),                    // q<largura>
+    LabelHeight { height: u32, gap: u32, black_mark: bool, offset: Option<i32> }, // Q<altura>,[B]<gap>[,<deslocamento>]
+    PrintDirection(char),               // ZN, ZT ou ZB
+    VerticalOffset(i32),                // Z<n> (usado pelo layout original)
+    Options(Vec<String>),               // O[<opções>]
+    TopOfForm(bool),                    // JF (ligado) ou JB (desligado)
+    Density(u32),                       // D<n>
+    Speed(u32),                         // S<n>
+    CodePage(Vec<String>),              // I<bits>,<página>,<país>
+    Text {
+        x: i32,
+        y: i32,
+        rotation: u8,
+        font: String,
+        h_mult: u32,
+        v_mult: u32,
+        reverse: bool,
+        data: String,
+    },
+    Barcode {
+        x: i32,
+        y: i32,
+        rotation: u8,
+        kind: String,
+        narrow: u32,
+        wide: u32,
+        height: u32,
+        human_readable: bool,
+        data: String,
+    },
+    Barcode2D { x: i32, y: i32, kind: String, module: u32, data: String }, // b<x>,<y>,<Q|D>,s<módulo>
+    Line { x: i32, y: i32, width: u32, height: u32, mode: LineMode }, // LO, LE ou LW
+    Box { x: i32, y: i32, thickness: u32, x_end: i32, y_end: i32 },   // X
+    Graphic { x: i32, y: i32, bytes_per_row: u32, rows: u32, data: Vec<u8> }, // GW
+    StoredGraphic { x: i32, y: i32, name: String }, // GG
+    Print { labels: u32, copies: u32 },             // P<etiquetas>[,<cópias>]
+    Escape(String),                                 // <ESC>... (configuração da Argox)
+}
+
+// Modo de desenho das linhas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineMode {
+    Black, // LO
+    Xor,   // LE
+    White, // LW
+}
+
+// Comando com a posição em que aparece no fluxo
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedCommand {
+    pub line: usize,   // Linha (a partir de 1)
+    pub offset: usize, // Posição do primeiro byte do comando
+    pub command: PplaCommand,
+}
+
+// Problema encontrado no fluxo de comandos
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PplaDiagnostic {
+    pub line: usize,
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PplaDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "linha {}, byte {}: {}", self.line, self.offset, self.message)
+    }
+}
+
+// Texto do comando: UTF-8 quando válido, senão Latin-1 (um caractere por byte)
+fn decode(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|b| *b as char).collect(),
+    }
+}
+
+fn number<T: FromStr>(field: &str, name: &str) -> Result<T, String> {
+    field
+        .trim()
+        .parse()
+        .map_err(|_| format!("Parâmetro {} inválido: '{}'", name, field))
+}
+
+// Separa os parâmetros numéricos, exigindo a quantidade exata
+fn fields<'a>(params: &'a str, names: &[&str]) -> Result<Vec<&'a str>, String> {
+    let fields: Vec<&str> = params.split(',').collect();
+    if fields.len() != names.len() {
+        return Err(format!(
+            "Esperados {} parâmetros ({}), encontrados {}",
+            names.len(),
+            names.join(","),
+            fields.len()
+        ));
+    }
+    Ok(fields)
+}
+
+// Lê um texto entre aspas ("\" e \\ escapam aspas e barra) que deve encerrar o comando
+fn quoted(value: &str) -> Result<String, String> {
+    let mut chars = value.trim_end().chars();
+    if chars.next() != Some('"') {
+        return Err("Texto deve estar entre aspas".to_string());
+    }
+
+    let mut result = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => match chars.next() {
+                Some(c) => result.push(c),
+                None => return Err("Texto sem aspas de fechamento".to_string()),
+            },
+            Some('"') => break,
+            Some(c) => result.push(c),
+            None => return Err("Texto sem aspas de fechamento".to_string()),
+        }
+    }
+
+    if chars.next().is_some() {
+        return Err("Conteúdo depois das aspas de fechamento".to_string());
+    }
+
+    Ok(result)
+}
+
+// Divide "p1,p2,...,\"dados\"" em parâmetros e texto
+fn params_and_data<'a>(params: &'a str, names: &[&str]) -> Result<(Vec<&'a str>, String), String> {
+    let quote = params
+        .find('"')
+        .ok_or_else(|| "Texto deve estar entre aspas".to_string())?;
+    let prefix = params[..quote]
+        .strip_suffix(',')
+        .ok_or_else(|| "Falta a vírgula antes do texto".to_string())?;
+
+    Ok((fields(prefix, names)?, quoted(&params[quote..])?))
+}
+
+fn rotation(field: &str) -> Result<u8, String> {
+    match number::<u8>(field, "rotação")? {
+        rotation @ 0..=3 => Ok(rotation),
+        rotation => Err(format!("Rotação inválida: {} (use 0 a 3)", rotation)),
+    }
+}
+
+fn flag(field: &str, name: &str, on: &str, off: &str) -> Result<bool, String> {
+    match field.trim() {
+        value if value == on => Ok(true),
+        value if value == off => Ok(false),
+        value => Err(format!("Parâmetro {} inválido: '{}' (use {} ou {})", name, value, on, off)),
+    }
+}
+
+// Interpreta uma linha de comando (exceto GW, que carrega dados binários)
+fn parse_line(text: &str) -> Result<PplaCommand, String> {
+    if text == "N" {
+        return Ok(PplaCommand::ClearImage);
+    }
+
+    if let Some(params) = text.strip_prefix("GG") {
+        let (fields, name) = params_and_data(params, &["x", "y"])?;
+        return Ok(PplaCommand::StoredGraphic {
+            x: number(fields[0], "x")?,
+            y: number(fields[1], "y")?,
+            name,
+        });
+    }
+
+    for (prefix, mode) in [("LO", LineMode::Black), ("LE", LineMode::Xor), ("LW", LineMode::White)] {
+        if let Some(params) = text.strip_prefix(prefix) {
+            let fields = fields(params, &["x", "y", "largura", "altura"])?;
+            return Ok(PplaCommand::Line {
+                x: number(fields[0], "x")?,
+                y: number(fields[1], "y")?,
+                width: number(fields[2], "largura")?,
+                height: number(fields[3], "altura")?,
+                mode,
+            });
+        }
+    }
+
+    match text {
+        "JF" => return Ok(PplaCommand::TopOfForm(true)),
+        "JB" => return Ok(PplaCommand::TopOfForm(false)),
+        "ZN" => return Ok(PplaCommand::PrintDirection('N')),
+        "ZT" => return Ok(PplaCommand::PrintDirection('T')),
+        "ZB" => return Ok(PplaCommand::PrintDirection('B')),
+        _ => {}
+    }
+
+    let mut chars = text.chars();
+    let name = chars.next().unwrap_or_default();
+    let params = chars.as_str();
+
+    match name {
+        'Z' => Ok(PplaCommand::VerticalOffset(number(params, "deslocamento")?)),
+        'q' => Ok(PplaCommand::LabelWidth(number(params, "largura")?)),
+        'Q' => {
+            let fields: Vec<&str> = params.split(',').collect();
+            if fields.len() != 2 && fields.len() != 3 {
+                return Err(format!("Esperados 2 ou 3 parâmetros (altura,gap[,deslocamento]), encontrados {}", fields.len()));
+            }
+            // "B" antes do gap: sensor de marca preta, com o gap sendo a altura da marca
+            let gap = fields[1].trim();
+            let black_mark = gap.starts_with('B');
+            Ok(PplaCommand::LabelHeight {
+                height: number(fields[0], "altura")?,
+                gap: number(gap.trim_start_matches('B'), "gap")?,
+                black_mark,
+                offset: fields.get(2).map(|f| number(f, "deslocamento")).transpose()?,
+            })
+        }
+        'O' => Ok(PplaCommand::Options(
+            params.split(',').map(str::trim).filter(|o| !o.is_empty()).map(String::from).collect(),
+        )),
+        'D' => Ok(PplaCommand::Density(number(params, "densidade")?)),
+        'S' => Ok(PplaCommand::Speed(number(params, "velocidade")?)),
+        'I' => Ok(PplaCommand::CodePage(params.split(',').map(|p| p.trim().to_string()).collect())),
+        'A' => {
+            let (fields, data) = params_and_data(params, &["x", "y", "rotação", "fonte", "mult. horizontal", "mult. vertical", "N/R"])?;
+            Ok(PplaCommand::Text {
+                x: number(fields[0], "x")?,
+                y: number(fields[1], "y")?,
+                rotation: rotation(fields[2])?,
+                font: fields[3].trim().to_string(),
+                h_mult: number(fields[4], "mult. horizontal")?,
+                v_mult: number(fields[5], "mult. vertical")?,
+                reverse: flag(fields[6], "N/R", "R", "N")?,
+                data,
+            })
+        }
+        'B' => {
+            let (fields, data) = params_and_data(
+                params,
+                &["x", "y", "rotação", "tipo", "barra fina", "barra larga", "altura", "B/N"],
+            )?;
+            Ok(PplaCommand::Barcode {
+                x: number(fields[0], "x")?,
+                y: number(fields[1], "y")?,
+                rotation: rotation(fields[2])?,
+                kind: fields[3].trim().to_string(),
+                narrow: number(fields[4], "barra fina")?,
+                wide: number(fields[5], "barra larga")?,
+                height: number(fields[6], "altura")?,
+                human_readable: flag(fields[7], "B/N", "B", "N")?,
+                data,
+            })
+        }
+        'b' => {
+            let (fields, data) = params_and_data(params, &["x", "y", "tipo", "módulo"])?;
+            let module = fields[3].trim();
+            Ok(PplaCommand::Barcode2D {
+                x: number(fields[0], "x")?,
+                y: number(fields[1], "y")?,
+                kind: fields[2].trim().to_string(),
+                module: number(module.strip_prefix('s').unwrap_or(module), "módulo")?,
+                data,
+            })
+        }
+        'X' => {
+            let fields = fields(params, &["x", "y", "espessura", "x final", "y final"])?;
+            Ok(PplaCommand::Box {
+                x: number(fields[0], "x")?,
+                y: number(fields[1], "y")?,
+                thickness: number(fields[2], "espessura")?,
+                x_end: number(fields[3], "x final")?,
+                y_end: number(fields[4], "y final")?,
+            })
+        }
+        'P' => {
+            let fields: Vec<&str> = params.split(',').collect();
+            if fields.len() > 2 {
+                return Err(format!("Esperados 1 ou 2 parâmetros (etiquetas[,cópias]), encontrados {}", fields.len()));
+            }
+            Ok(PplaCommand::Print {
+                labels: number(fields[0], "etiquetas")?,
+                copies: fields.get(1).map(|f| number(f, "cópias")).transpose()?.unwrap_or(1),
+            })
+        }
+        _ => Err("Comando desconhecido".to_string()),
+    }
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+    line: usize,
+    commands: Vec<ParsedCommand>,
+    diagnostics: Vec<PplaDiagnostic>,
+    code_page: Option<CodePage>, // Selecionada pelo comando I; os textos seguintes usam essa página
+}
+
+impl<'a> Parser<'a> {
+    fn line_end(&self) -> usize {
+        self.data[self.pos..]
+            .iter()
+            .position(|b| *b == b'\r' || *b == b'\n')
+            .map(|end| self.pos + end)
+            .unwrap_or(self.data.len())
+    }
+
+    fn error(&mut self, line: usize, offset: usize, message: String) {
+        self.diagnostics.push(PplaDiagnostic { line, offset, message });
+    }
+
+    // GW<x>,<y>,<bytes por linha>,<linhas>,<dados binários>. Sem a última vírgula o comando não
+    // traz dados (caso do layout original) e vira um gráfico vazio, que o emulador aponta como aviso
+    fn graphic(&mut self, line: usize, offset: usize) {
+        let mut commas = Vec::new();
+        for (index, byte) in self.data[self.pos..].iter().enumerate() {
+            if *byte == b'\r' || *byte == b'\n' {
+                break;
+            }
+            if *byte == b',' {
+                commas.push(self.pos + index);
+                if commas.len() == 4 {
+                    break;
+                }
+            }
+        }
+
+        let header_end = match commas.len() {
+            4 => commas[3],
+            3 => self.line_end(),
+            _ => {
+                self.pos = self.line_end();
+                self.error(line, offset, "GW incompleto (esperado GWx,y,bytes,linhas,dados)".to_string());
+                return;
+            }
+        };
+
+        let header = decode(&self.data[offset + 2..header_end]);
+        let parsed = fields(&header, &["x", "y", "bytes por linha", "linhas"]).and_then(|fields| {
+            Ok((
+                number::<i32>(fields[0], "x")?,
+                number::<i32>(fields[1], "y")?,
+                number::<u32>(fields[2], "bytes por linha")?,
+                number::<u32>(fields[3], "linhas")?,
+            ))
+        });
+
+        let (x, y, bytes_per_row, rows) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.pos = self.line_end();
+                self.error(line, offset, format!("GW: {}", e));
+                return;
+            }
+        };
+
+        if commas.len() == 3 {
+            self.pos = header_end;
+            self.commands.push(ParsedCommand {
+                line,
+                offset,
+                command: PplaCommand::Graphic { x, y, bytes_per_row, rows, data: Vec::new() },
+            });
+            return;
+        }
+
+        let start = commas[3] + 1;
+        let expected = bytes_per_row as usize * rows as usize;
+        let available = self.data.len() - start;
+
+        if available < expected {
+            self.pos = self.data.len();
+            self.error(
+                line,
+                offset,
+                format!("GW com dados incompletos: esperados {} bytes de imagem, recebidos {}", expected, available),
+            );
+            return;
+        }
+
+        self.pos = start + expected;
+        self.commands.push(ParsedCommand {
+            line,
+            offset,
+            command: PplaCommand::Graphic {
+                x,
+                y,
+                bytes_per_row,
+                rows,
+                data: self.data[start..start + expected].to_vec(),
+            },
+        });
+    }
+
+    fn command(&mut self) {
+        let line = self.line;
+        let offset = self.pos;
+
+        if self.data[self.pos..].starts_with(b"GW") {
+            self.graphic(line, offset);
+            return;
+        }
+
+        let end = self.line_end();
+        let text = match self.code_page {
+            Some(page) => page.decode(&self.data[self.pos..end]),
+            None => decode(&self.data[self.pos..end]),
+        };
+        self.pos = end;
+
+        let parsed = match text.strip_prefix('\x1B') {
+            Some(escape) => Ok(PplaCommand::Escape(escape.to_string())),
+            None => parse_line(text.trim_end()),
+        };
+
+        if let Ok(PplaCommand::CodePage(params)) = &parsed {
+            if let Some(page) = params.get(1).and_then(|table| CodePage::from_epl_table(table)) {
+                self.code_page = Some(page);
+            }
+        }
+
+        match parsed {
+            Ok(command) => self.commands.push(ParsedCommand { line, offset, command }),
+            Err(e) => self.error(line, offset, format!("{} em \"{}\"", e, text.trim_end())),
+        }
+    }
+
+    fn run(mut self) -> Result<Vec<ParsedCommand>, Vec<PplaDiagnostic>> {
+        while self.pos < self.data.len() {
+            match self.data[self.pos] {
+                b'\r' => self.pos += 1,
+                b'\n' => {
+                    self.pos += 1;
+                    self.line += 1;
+                }
+                _ => self.command(),
+            }
+        }
+
+        if self.diagnostics.is_empty() {
+            Ok(self.commands)
+        } else {
+            Err(self.diagnostics)
+        }
+    }
+}
+
+// Converte o fluxo de bytes em comandos, apontando linha e posição de cada erro
+pub fn parse(data: &[u8]) -> Result<Vec<ParsedCommand>, Vec<PplaDiagnostic>> {
+    Parser {
+        data,
+        pos: 0,
+        line: 1,
+        commands: Vec::new(),
+        diagnostics: Vec::new(),
+        code_page: None,
+    }
+    .run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(data: &[u8]) -> Vec<PplaCommand> {
+        parse(data).unwrap().into_iter().map(|parsed| parsed.command).collect()
+    }
+
+    fn messages(data: &[u8]) -> Vec<String> {
+        parse(data).unwrap_err().into_iter().map(|d| d.message).collect()
+    }
+
+    #[test]
+    fn interpreta_o_cabecalho_do_layout_original() {
+        let parsed = commands(b"N\r\nZN\r\nq840\r\nQ176,25\r\nD8\r\nS2\r\nO\r\nJF\r\nZ0\r\nI8,1,001\r\nP1\r\n");
+
+        assert_eq!(
+            parsed,
+            vec![
+                PplaCommand::ClearImage,
+                PplaCommand::PrintDirection('N'),
+                PplaCommand::LabelWidth(840),
+                PplaCommand::LabelHeight { height: 176, gap: 25, black_mark: false, offset: None },
+                PplaCommand::Density(8),
+                PplaCommand::Speed(2),
+                PplaCommand::Options(Vec::new()),
+                PplaCommand::TopOfForm(true),
+                PplaCommand::VerticalOffset(0),
+                PplaCommand::CodePage(vec!["8".to_string(), "1".to_string(), "001".to_string()]),
+                PplaCommand::Print { labels: 1, copies: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn texto_usa_a_pagina_de_codigo_selecionada() {
+        // "Ação" na CP850: ç = 0x87, ã = 0xC6
+        let parsed = commands(b"I8,1,001\r\nA10,20,0,2,1,1,N,\"A\x87\xC6o\"\r\n");
+
+        match &parsed[1] {
+            PplaCommand::Text { x, y, font, data, .. } => {
+                assert_eq!((*x, *y, font.as_str(), data.as_str()), (10, 20, "2", "Ação"));
+            }
+            other => panic!("esperado texto, veio {:?}", other),
+        }
+    }
+
+    #[test]
+    fn gw_traz_os_bytes_binarios_da_imagem() {
+        // Os dados podem conter CR e LF sem encerrar o comando
+        let parsed = parse(b"GW10,20,2,2,\r\n\x00\xFF\r\nP1\r\n").unwrap();
+
+        assert_eq!(
+            parsed[0].command,
+            PplaCommand::Graphic { x: 10, y: 20, bytes_per_row: 2, rows: 2, data: b"\r\n\x00\xFF".to_vec() }
+        );
+        assert_eq!(parsed[1].command, PplaCommand::Print { labels: 1, copies: 1 });
+    }
+
+    #[test]
+    fn gw_sem_dados_vira_grafico_vazio() {
+        assert_eq!(
+            commands(b"N\r\nGW620,215,13,32\r\nP1\r\n"),
+            vec![
+                PplaCommand::ClearImage,
+                PplaCommand::Graphic { x: 620, y: 215, bytes_per_row: 13, rows: 32, data: Vec::new() },
+                PplaCommand::Print { labels: 1, copies: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn gw_incompleto_gera_diagnostico() {
+        assert_eq!(messages(b"N\r\nGW620,215\r\nP1\r\n"), ["GW incompleto (esperado GWx,y,bytes,linhas,dados)"]);
+        assert_eq!(messages(b"GW620,215,X,32\r\n"), ["GW: Parâmetro bytes por linha inválido: 'X'"]);
+    }
+
+    #[test]
+    fn gw_do_dump_tem_dados_incompletos() {
+        let diagnostics = parse(&crate::ARGOX_PPLA_EXACT).unwrap_err();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].offset), (2, 3));
+        assert_eq!(diagnostics[0].message, "GW com dados incompletos: esperados 416 bytes de imagem, recebidos 13");
+    }
+
+    #[test]
+    fn aponta_linha_e_posicao_dos_comandos_invalidos() {
+        let diagnostics = parse(b"N\r\nq840\r\nK9\r\nA10,10,5,2,1,1,N,\"X\"\r\n").unwrap_err();
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!((diagnostics[0].line, diagnostics[0].offset), (3, 9));
+        assert_eq!(diagnostics[0].message, "Comando desconhecido em \"K9\"");
+        assert_eq!(diagnostics[1].line, 4);
+    }
+
+    #[test]
+    fn sensor_de_marca_preta_e_copias() {
+        assert_eq!(
+            commands(b"Q200,B24,-3\r\nP2,3\r\n"),
+            vec![
+                PplaCommand::LabelHeight { height: 200, gap: 24, black_mark: true, offset: Some(-3) },
+                PplaCommand::Print { labels: 2, copies: 3 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use crate::barcode::Symbology;
+use crate::code_page::CodePage;
+use crate::label_render::LabelWriter;
+use crate::label_template::{LabelTemplate, TemplateElement};
+use crate::media_profile::Media;
+use crate::ppla_label::{epl_element, label_height_command};
+use crate::PrinterConfig;
+
+// Tipo do código de barras usado no comando B do PPLB/EPL2; QR e DataMatrix usam o comando b
+fn barcode_type(symbology: Symbology) -> &'static str {
+    match symbology {
+        Symbology::Ean13 => "E30",
+        Symbology::Ean8 => "E80",
+        Symbology::UpcA => "UA0",
+        Symbology::Code128 => "1B",
+        Symbology::Itf14 => "2",
+        Symbology::Qr => "Q",
+        Symbology::DataMatrix => "D",
+    }
+}
+
+// Gerador PPLB (Argox em modo B, Elgin e compatíveis com EPL2)
+pub struct PplbWriter;
+
+impl LabelWriter for PplbWriter {
+    fn begin(&self, out: &mut Vec<u8>, media: &Media, config: &PrinterConfig) -> Result<(), String> {
+        out.extend_from_slice(b"\r\n"); // Encerra qualquer comando incompleto que tenha ficado no buffer
+        out.extend_from_slice(b"N\r\n"); // Limpa a imagem anterior
+        out.extend_from_slice(format!("q{}\r\n", media.width).as_bytes()); // Largura da fita
+        out.extend_from_slice(label_height_command(media).as_bytes()); // Altura da etiqueta e gap
+        out.extend_from_slice(format!("D{}\r\n", config.darkness).as_bytes()); // Densidade (1-15)
+        out.extend_from_slice(format!("S{}\r\n", config.speed).as_bytes()); // Velocidade (1-4)
+        out.extend_from_slice(b"ZT\r\n"); // Imprime a partir do topo
+        Ok(())
+    }
+
+    fn select_code_page(&self, out: &mut Vec<u8>, page: CodePage) -> Result<(), String> {
+        out.extend_from_slice(format!("I8,{},001\r\n", page.epl_table()).as_bytes()); // 8 bits, tabela de caracteres, país (001 = EUA)
+        Ok(())
+    }
+
+    fn element(&self, out: &mut Vec<u8>, element: &TemplateElement<u32>) -> Result<(), String> {
+        epl_element(out, element, barcode_type)
+    }
+
+    fn end(&self, out: &mut Vec<u8>, _template: &LabelTemplate<u32>, copies: u32) -> Result<(), String> {
+        out.extend_from_slice(format!("P{}\r\n", copies).as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::label_render::LabelLanguage;
+    use crate::test_support;
+
+    // Saída de referência do modelo padrão: qualquer diferença muda o que a impressora recebe
+    const DEFAULT_TEMPLATE_GOLDEN: &[u8] = include_bytes!("fixtures/default_template.pplb");
+
+    #[test]
+    fn modelo_padrao_gera_a_saida_de_referencia() {
+        let batch = test_support::default_template_batch(LabelLanguage::Pplb);
+
+        test_support::assert_golden(&batch.data, DEFAULT_TEMPLATE_GOLDEN);
+    }
+}
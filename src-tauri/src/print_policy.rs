@@ -0,0 +1,275 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::transport::{self, PrintJobInfo, PrinterTarget};
+use crate::PrinterConfig;
+
+// Espera máxima entre duas tentativas
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+// O que fazer quando o envio para a impressora falha
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FailurePolicy {
+    Fail,     // Devolve o erro na primeira falha
+    Retry,    // Tenta de novo na mesma impressora, com espera crescente
+    Fallback, // Tenta de novo e, se continuar falhando, envia para a impressora reserva
+}
+
+impl FailurePolicy {
+    pub fn parse(value: &str) -> Result<FailurePolicy, String> {
+        match value.to_lowercase().as_str() {
+            "fail" | "falhar" => Ok(FailurePolicy::Fail),
+            "retry" | "repetir" => Ok(FailurePolicy::Retry),
+            "fallback" | "reserva" => Ok(FailurePolicy::Fallback),
+            _ => Err(format!("Política de falha desconhecida: {} (use fail, retry ou fallback)", value)),
+        }
+    }
+}
+
+// Resultado do envio devolvido para a interface e gravado no histórico
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintOutcome {
+    pub printer: String,     // Impressora que recebeu (ou deveria ter recebido) o trabalho
+    pub attempts: u32,       // Total de tentativas, somando as da impressora reserva
+    pub used_fallback: bool, // O trabalho foi para a impressora reserva
+}
+
+// Falha depois de esgotar a política configurada
+#[derive(Debug, Clone)]
+pub struct PrintFailure {
+    pub outcome: PrintOutcome,
+    pub error: String,
+}
+
+// Espera antes da tentativa seguinte: dobra a cada falha, até o limite
+fn retry_delay(config: &PrinterConfig, failed_attempts: u32) -> Duration {
+    let base = Duration::from_millis(config.retry_backoff_ms as u64);
+    base.saturating_mul(2u32.saturating_pow(failed_attempts.saturating_sub(1)))
+        .min(MAX_RETRY_BACKOFF)
+}
+
+// Envia o trabalho para um destino, repetindo até `retries` vezes depois da primeira falha.
+// Cada tentativa reenvia o lote inteiro, já que não há como saber quantas etiquetas saíram.
+async fn send_with_retries(
+    target: &PrinterTarget,
+    config: &PrinterConfig,
+    job: &PrintJobInfo,
+    data: &[u8],
+    retries: u32,
+) -> (u32, Result<(), String>) {
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+
+        let error = match transport::print_to_target(target, config, job, data) {
+            Ok(()) => return (attempts, Ok(())),
+            Err(e) => e,
+        };
+
+        println!("Tentativa {} para {} falhou: {}", attempts, target.display_name(), error);
+
+        if attempts > retries {
+            return (attempts, Err(error));
+        }
+
+        let delay = retry_delay(config, attempts);
+        println!("Nova tentativa em {} ms", delay.as_millis());
+        tokio::time::sleep(delay).await;
+    }
+}
+
+// Envia o trabalho seguindo a política de falha da impressora.
+// Impressora solicitada inexistente não cai mais em outra qualquer: ou vai para a reserva
+// configurada (política "fallback") ou o envio falha.
+pub async fn print_with_policy(
+    printer_name: Option<String>,
+    config: &PrinterConfig,
+    job: &PrintJobInfo,
+    data: &[u8],
+) -> Result<PrintOutcome, PrintFailure> {
+    let requested = printer_name
+        .clone()
+        .or_else(|| config.selected_printer.clone())
+        .unwrap_or_default();
+
+    let fail = |printer: String, attempts: u32, error: String| PrintFailure {
+        outcome: PrintOutcome {
+            printer,
+            attempts,
+            used_fallback: false,
+        },
+        error,
+    };
+
+    let policy = FailurePolicy::parse(&config.failure_policy).map_err(|e| fail(requested.clone(), 0, e))?;
+
+    let fallback_name = config
+        .fallback_printer
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+
+    if policy == FailurePolicy::Fallback && fallback_name.is_none() {
+        return Err(fail(
+            requested,
+            0,
+            "A política \"fallback\" exige uma impressora reserva configurada".to_string(),
+        ));
+    }
+
+    let retries = match policy {
+        FailurePolicy::Fail => 0,
+        FailurePolicy::Retry | FailurePolicy::Fallback => config.max_retries,
+    };
+
+    // Impressora principal
+    let (printer, attempts, primary_error) = match crate::resolve_printer_target(printer_name, config) {
+        Ok(target) => {
+            println!("Usando destino de impressão: {:?}", target);
+            let (attempts, result) = send_with_retries(&target, config, job, data, retries).await;
+            match result {
+                Ok(()) => {
+                    return Ok(PrintOutcome {
+                        printer: target.display_name(),
+                        attempts,
+                        used_fallback: false,
+                    })
+                }
+                Err(e) => (target.display_name(), attempts, e),
+            }
+        }
+        Err(e) => (requested, 0, e),
+    };
+
+    let fallback_name = match (policy, fallback_name) {
+        (FailurePolicy::Fallback, Some(name)) => name,
+        _ => return Err(fail(printer, attempts, primary_error)),
+    };
+
+    // Impressora reserva
+    println!(
+        "AVISO: falha na impressora '{}' ({}). Enviando para a impressora reserva '{}'.",
+        printer, primary_error, fallback_name
+    );
+
+    let fallback_target = crate::resolve_printer_target(Some(fallback_name.to_string()), config).map_err(|e| {
+        fail(
+            fallback_name.to_string(),
+            attempts,
+            format!("{}; impressora reserva indisponível: {}", primary_error, e),
+        )
+    })?;
+
+    let (fallback_attempts, result) = send_with_retries(&fallback_target, config, job, data, retries).await;
+    let outcome = PrintOutcome {
+        printer: fallback_target.display_name(),
+        attempts: attempts + fallback_attempts,
+        used_fallback: true,
+    };
+
+    match result {
+        Ok(()) => Ok(outcome),
+        Err(e) => Err(PrintFailure {
+            outcome,
+            error: format!("{}; impressora reserva também falhou: {}", primary_error, e),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(policy: &str, retries: u32, fallback: Option<String>) -> PrinterConfig {
+        PrinterConfig {
+            failure_policy: policy.to_string(),
+            max_retries: retries,
+            retry_backoff_ms: 1,
+            fallback_printer: fallback,
+            ..PrinterConfig::default()
+        }
+    }
+
+    // Destino que sempre falha: arquivo numa pasta que não existe
+    fn failing_target(dir: &std::path::Path) -> String {
+        format!("file://{}", dir.join("pasta-inexistente").join("saida.prn").display())
+    }
+
+    #[test]
+    fn espera_dobra_a_cada_falha_ate_o_limite() {
+        let config = PrinterConfig { retry_backoff_ms: 500, ..PrinterConfig::default() };
+
+        assert_eq!(retry_delay(&config, 1), Duration::from_millis(500));
+        assert_eq!(retry_delay(&config, 2), Duration::from_millis(1000));
+        assert_eq!(retry_delay(&config, 4), Duration::from_millis(4000));
+        assert_eq!(retry_delay(&config, 7), MAX_RETRY_BACKOFF);
+        assert_eq!(retry_delay(&config, 40), MAX_RETRY_BACKOFF);
+    }
+
+    #[test]
+    fn politica_desconhecida_e_recusada() {
+        assert_eq!(FailurePolicy::parse("Reserva"), Ok(FailurePolicy::Fallback));
+        assert_eq!(
+            FailurePolicy::parse("ignorar"),
+            Err("Política de falha desconhecida: ignorar (use fail, retry ou fallback)".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn repete_ate_esgotar_as_tentativas() {
+        let dir = tempfile::tempdir().unwrap();
+        let job = PrintJobInfo::new("Etiquetas");
+
+        let failure = print_with_policy(Some(failing_target(dir.path())), &config("retry", 2, None), &job, b"N\r\n")
+            .await
+            .unwrap_err();
+
+        // A primeira tentativa e mais `max_retries`
+        assert_eq!(failure.outcome.attempts, 3);
+        assert!(!failure.outcome.used_fallback);
+        assert!(failure.error.starts_with("Erro ao abrir arquivo"), "{}", failure.error);
+
+        let failure = print_with_policy(Some(failing_target(dir.path())), &config("fail", 2, None), &job, b"N\r\n")
+            .await
+            .unwrap_err();
+        assert_eq!(failure.outcome.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn envia_para_a_reserva_quando_a_principal_falha() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("reserva.prn");
+        let config = config("fallback", 1, Some(format!("file://{}", output.display())));
+
+        let job = PrintJobInfo::new("Etiquetas");
+
+        let outcome = print_with_policy(Some(failing_target(dir.path())), &config, &job, b"N\r\nP1\r\n").await.unwrap();
+
+        assert!(outcome.used_fallback);
+        assert_eq!(outcome.printer, format!("file://{}", output.display()));
+        // Duas tentativas na principal e uma na reserva
+        assert_eq!(outcome.attempts, 3);
+        assert_eq!(std::fs::read(&output).unwrap(), b"N\r\nP1\r\n");
+    }
+
+    #[tokio::test]
+    async fn politica_fallback_sem_reserva_e_erro() {
+        let dir = tempfile::tempdir().unwrap();
+
+        for fallback in [None, Some("  ".to_string())] {
+            let failure = print_with_policy(
+                Some(failing_target(dir.path())),
+                &config("fallback", 1, fallback),
+                &PrintJobInfo::new("Etiquetas"),
+                b"N\r\n",
+            )
+            .await
+            .unwrap_err();
+
+            assert_eq!(failure.error, "A política \"fallback\" exige uma impressora reserva configurada");
+            assert_eq!(failure.outcome.attempts, 0);
+        }
+    }
+}
@@ -1,47 +1,27 @@
-use rusb::{Context, Device, DeviceHandle, UsbContext};
-use serde::{Deserialize, Serialize};
+use rusb::{Context, DeviceHandle, UsbContext};
 use std::time::Duration;
-use std::sync::Mutex;
-use once_cell::sync::Lazy;
+
+use crate::transport::PrinterTransport;
 
 // Constantes da Argox OS-2140
 const ARGOX_VID: u16 = 0x1CBE;
 const ARGOX_PID: u16 = 0x0002;
 const TIMEOUT: Duration = Duration::from_secs(1);
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PrinterConfig {
-    pub darkness: u8,      // Densidade de impressão (1-15)
-    pub width: u32,        // Largura em dots (8 dots = 1mm)
-    pub height: u32,       // Altura em dots
-    pub speed: u8,         // Velocidade (1-4)
-}
-
-impl Default for PrinterConfig {
-    fn default() -> Self {
-        Self {
-            darkness: 8,    // Densidade média
-            width: 400,     // 50mm * 8 dots
-            height: 240,    // 30mm * 8 dots
-            speed: 2,       // Velocidade média
-        }
-    }
-}
-
 pub struct UsbPrinter {
     handle: DeviceHandle<Context>,
     endpoint_out: u8,
     endpoint_in: u8,
-    config: PrinterConfig,
+    interface_number: u8,
 }
 
 impl UsbPrinter {
-    pub fn new(config: PrinterConfig) -> Result<Self, String> {
+    pub fn new() -> Result<Self, String> {
         let context = Context::new()
             .map_err(|e| format!("Erro ao criar contexto USB: {}", e))?;
 
         // Procura a impressora Argox
-        let (device, device_desc) = context
+        let device = context
             .devices()
             .map_err(|e| format!("Erro ao listar dispositivos: {}", e))?
             .iter()
@@ -51,16 +31,10 @@ impl UsbPrinter {
                     .map(|desc| desc.vendor_id() == ARGOX_VID && desc.product_id() == ARGOX_PID)
                     .unwrap_or(false)
             })
-            .and_then(|device| {
-                device
-                    .device_descriptor()
-                    .map(|desc| (device, desc))
-                    .ok()
-            })
             .ok_or("Impressora Argox OS-2140 não encontrada")?;
 
         // Configura a impressora
-        let mut handle = device
+        let handle = device
             .open()
             .map_err(|e| format!("Erro ao abrir dispositivo: {}", e))?;
 
@@ -85,8 +59,9 @@ impl UsbPrinter {
             .ok_or("Descritor de interface não encontrado")?;
 
         // Configura a interface
+        let interface_number = interface_desc.interface_number();
         handle
-            .claim_interface(interface_desc.interface_number())
+            .claim_interface(interface_number)
             .map_err(|e| format!("Erro ao configurar interface: {}", e))?;
 
         // Encontra endpoints
@@ -107,7 +82,7 @@ impl UsbPrinter {
             handle,
             endpoint_out,
             endpoint_in,
-            config,
+            interface_number,
         })
     }
 
@@ -117,97 +92,65 @@ impl UsbPrinter {
             .map_err(|e| format!("Erro ao enviar dados: {}", e))
     }
 
-    pub fn read(&self, buffer: &mut [u8]) -> Result<usize, String> {
-        self.handle
-            .read_bulk(self.endpoint_in, buffer, TIMEOUT)
-            .map_err(|e| format!("Erro ao ler dados: {}", e))
+    pub fn read(&self, buffer: &mut [u8], timeout: Duration) -> Result<usize, String> {
+        read_result(self.handle.read_bulk(self.endpoint_in, buffer, timeout))
     }
+}
 
-    pub fn print_label(&self, text: &str) -> Result<(), String> {
-        let commands = vec![
-            format!("Q{},24\r\n", self.config.height),  // Altura
-            format!("q{}\r\n", self.config.width),      // Largura
-            format!("S{}\r\n", self.config.speed),      // Velocidade
-            format!("D{}\r\n", self.config.darkness),   // Densidade
-            "ZT\r\n".to_string(),                      // Limpa buffer
-            format!("A50,50,0,3,1,1,N,\"{}\"\r\n", text), // Texto
-            "P1\r\n".to_string(),                      // Imprime
-        ];
-
-        for cmd in commands {
-            self.write(cmd.as_bytes())?;
-        }
+// Sem dados dentro do prazo não é erro: devolve 0, como os demais transportes
+fn read_result(result: rusb::Result<usize>) -> Result<usize, String> {
+    match result {
+        Ok(read) => Ok(read),
+        Err(rusb::Error::Timeout) => Ok(0),
+        Err(e) => Err(format!("Erro ao ler dados: {}", e)),
+    }
+}
 
+impl PrinterTransport for UsbPrinter {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        // A escrita bulk pode aceitar só parte dos dados, então envia até o fim
+        let mut offset = 0;
+        while offset < data.len() {
+            let written = self.write(&data[offset..])?;
+            if written == 0 {
+                return Err("Impressora USB não aceitou os dados".to_string());
+            }
+            offset += written;
+        }
         Ok(())
     }
 
-    pub fn test_connection(&self) -> Result<(), String> {
-        // Envia comando de status
-        self.write(b"~H\r\n")?;
-        
-        // Lê resposta
-        let mut buffer = [0u8; 32];
-        let read = self.read(&mut buffer)?;
-        
-        if read > 0 {
-            Ok(())
-        } else {
-            Err("Impressora não respondeu".to_string())
-        }
+    fn read(&mut self, buffer: &mut [u8], timeout: Duration) -> Result<usize, String> {
+        UsbPrinter::read(self, buffer, timeout)
     }
-}
 
-// Estado global da impressora
-pub static PRINTER: Lazy<Mutex<Option<UsbPrinter>>> = Lazy::new(|| Mutex::new(None));
-
-#[tauri::command]
-pub async fn connect_printer(config: PrinterConfig) -> Result<(), String> {
-    let printer = UsbPrinter::new(config)?;
-    
-    // Testa a conexão
-    printer.test_connection()?;
-    
-    // Se chegou aqui, salva a impressora no estado global
-    let mut printer_guard = PRINTER.lock().unwrap();
-    *printer_guard = Some(printer);
-    
-    Ok(())
-}
+    fn supports_read(&self) -> bool {
+        true
+    }
 
-#[tauri::command]
-pub async fn print_test() -> Result<(), String> {
-    let printer_guard = PRINTER.lock().unwrap();
-    
-    if let Some(printer) = &*printer_guard {
-        printer.print_label("Teste de Impressão")?;
-        Ok(())
-    } else {
-        Err("Impressora não conectada".to_string())
+    fn describe(&self) -> String {
+        "Argox OS-2140 (USB)".to_string()
+    }
+
+    fn close(&mut self) -> Result<(), String> {
+        self.handle
+            .release_interface(self.interface_number)
+            .map_err(|e| format!("Erro ao liberar interface USB: {}", e))
     }
 }
 
-#[tauri::command]
-pub async fn list_printers() -> Result<Vec<String>, String> {
-    let context = Context::new()
-        .map_err(|e| format!("Erro ao criar contexto USB: {}", e))?;
-
-    let devices = context
-        .devices()
-        .map_err(|e| format!("Erro ao listar dispositivos: {}", e))?;
-
-    let mut printers = Vec::new();
-
-    for device in devices.iter() {
-        if let Ok(desc) = device.device_descriptor() {
-            if desc.vendor_id() == ARGOX_VID && desc.product_id() == ARGOX_PID {
-                if let Ok(handle) = device.open() {
-                    if let Ok(product) = handle.read_product_string_ascii(&desc) {
-                        printers.push(product);
-                    }
-                }
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leitura_sem_resposta_no_prazo_devolve_zero() {
+        assert_eq!(read_result(Err(rusb::Error::Timeout)), Ok(0));
+        assert_eq!(read_result(Ok(4)), Ok(4));
     }
 
-    Ok(printers)
-}
\ No newline at end of file
+    #[test]
+    fn outros_erros_de_leitura_continuam_erros() {
+        assert_eq!(read_result(Err(rusb::Error::NoDevice)).unwrap_err(), format!("Erro ao ler dados: {}", rusb::Error::NoDevice));
+    }
+}
@@ -0,0 +1,328 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::printer_status::{self, PrinterStatus, StatusProtocol};
+use crate::transport::{self, PrintJobInfo, PrinterTarget, PrinterTransport};
+use crate::{DbConnection, PrinterConfig};
+
+// Nome do evento emitido para o frontend
+pub const STATUS_EVENT: &str = "printer-status-changed";
+
+// Espera máxima entre consultas quando a impressora não responde
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// Espera quando o monitor está desligado (intervalo 0), para reler a configuração
+const DISABLED_CHECK: Duration = Duration::from_secs(30);
+
+// Protocolos tentados em cada consulta
+const PROTOCOLS: [StatusProtocol; 2] = [StatusProtocol::Ppla, StatusProtocol::Pplb];
+
+// Conteúdo do evento "printer-status-changed"
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrinterStatusEvent {
+    pub printer: String,               // Destino monitorado
+    pub supported: bool,               // O destino permite pedir o status (filas do sistema e arquivos não)
+    pub reachable: bool,               // A impressora respondeu ao pedido de status
+    pub status: Option<PrinterStatus>, // Estado interpretado, quando houve resposta
+    pub error: Option<String>,         // Motivo da falha, quando não houve resposta
+}
+
+impl PrinterStatusEvent {
+    fn from_result(printer: String, result: Result<PrinterStatus, String>) -> Self {
+        match result {
+            Ok(status) => PrinterStatusEvent {
+                printer,
+                supported: true,
+                reachable: true,
+                status: Some(status),
+                error: None,
+            },
+            Err(e) => PrinterStatusEvent {
+                printer,
+                supported: true,
+                reachable: false,
+                status: None,
+                error: Some(e),
+            },
+        }
+    }
+
+    // Destino sem leitura de resposta: não há status a mostrar, mas também não é uma falha
+    fn unsupported(printer: String) -> Self {
+        PrinterStatusEvent {
+            printer,
+            supported: false,
+            reachable: false,
+            status: None,
+            error: None,
+        }
+    }
+
+    // Só o estado importa para decidir se houve mudança (mensagens de erro variam)
+    fn same_state(&self, other: &PrinterStatusEvent) -> bool {
+        self.printer == other.printer
+            && self.supported == other.supported
+            && self.reachable == other.reachable
+            && self.status == other.status
+    }
+}
+
+// Pede o status numa conexão aberta
+fn status_event(printer: String, transport: &mut dyn PrinterTransport, timeout: Duration) -> PrinterStatusEvent {
+    if !transport.supports_read() {
+        return PrinterStatusEvent::unsupported(printer);
+    }
+    PrinterStatusEvent::from_result(printer, printer_status::query_protocols(transport, &PROTOCOLS, timeout))
+}
+
+// Destino da impressora selecionada. Resolver uma fila do sistema consulta a lista de impressoras,
+// então o destino só é resolvido de novo quando a impressora selecionada ou a porta mudam.
+#[derive(Default)]
+struct TargetCache {
+    resolved: Option<((Option<String>, String), PrinterTarget)>,
+}
+
+impl TargetCache {
+    fn target(
+        &mut self,
+        config: &PrinterConfig,
+        resolve: impl FnOnce(&PrinterConfig) -> Result<PrinterTarget, String>,
+    ) -> Result<PrinterTarget, String> {
+        let key = (config.selected_printer.clone(), config.port.clone());
+
+        match &self.resolved {
+            Some((resolved_key, target)) if *resolved_key == key => Ok(target.clone()),
+            _ => {
+                // Falhas não ficam guardadas: a impressora pode aparecer na próxima consulta
+                self.resolved = None;
+                let target = resolve(config)?;
+                self.resolved = Some((key, target.clone()));
+                Ok(target)
+            }
+        }
+    }
+}
+
+// Estado guardado entre as consultas
+#[derive(Default)]
+struct Monitor {
+    last_event: Option<PrinterStatusEvent>,
+    backoff: Option<Duration>,
+}
+
+impl Monitor {
+    // Espera antes da próxima consulta
+    fn next_wait(&self, interval: Duration) -> Duration {
+        self.backoff.unwrap_or(interval)
+    }
+
+    // Registra o resultado de uma consulta; devolve o evento a emitir quando o estado mudou
+    fn update(&mut self, event: PrinterStatusEvent, interval: Duration) -> Option<PrinterStatusEvent> {
+        // Impressora inacessível: espera cada vez mais entre as tentativas
+        self.backoff = if event.reachable || !event.supported {
+            None
+        } else {
+            Some((self.backoff.unwrap_or(interval) * 2).min(MAX_BACKOFF).max(interval))
+        };
+
+        let changed = self
+            .last_event
+            .as_ref()
+            .map(|last| !last.same_state(&event))
+            .unwrap_or(true);
+
+        if !changed {
+            return None;
+        }
+        self.last_event = Some(event.clone());
+        Some(event)
+    }
+}
+
+// Resultado de uma rodada de consulta
+enum Poll {
+    Busy,                       // Um trabalho está sendo enviado; tenta de novo depois
+    Done(PrinterStatusEvent),
+}
+
+// Consulta a impressora selecionada, sem nunca disputar a conexão com um trabalho em andamento
+fn poll_once(app_handle: &AppHandle, targets: &mut TargetCache) -> Result<Poll, String> {
+    let config = crate::load_printer_config(&app_handle.state::<DbConnection>())?;
+    let target = targets.target(&config, |config| {
+        crate::resolve_printer_target(config.selected_printer.clone(), config)
+    })?;
+    let printer = format!("{:?}", target);
+
+    let _printer = match transport::try_lock_printer() {
+        Some(guard) => guard,
+        None => return Ok(Poll::Busy),
+    };
+
+    let mut transport = match transport::open_transport(&target, &config, &PrintJobInfo::new("Status")) {
+        Ok(transport) => transport,
+        Err(e) => return Ok(Poll::Done(PrinterStatusEvent::from_result(printer, Err(e)))),
+    };
+    let event = status_event(printer, transport.as_mut(), printer_status::STATUS_TIMEOUT);
+    let _ = transport.close();
+
+    Ok(Poll::Done(event))
+}
+
+// Intervalo configurado entre consultas (0 desliga o monitor)
+fn poll_interval(app_handle: &AppHandle) -> Duration {
+    crate::load_printer_config(&app_handle.state::<DbConnection>())
+        .map(|config| Duration::from_secs(config.status_poll_interval_secs as u64))
+        .unwrap_or(Duration::ZERO)
+}
+
+// Monitora o status da impressora em segundo plano, emitindo eventos só quando o estado muda
+pub async fn run(app_handle: AppHandle) {
+    println!("Monitor de status da impressora iniciado");
+
+    let mut monitor = Monitor::default();
+    let mut targets = TargetCache::default();
+
+    loop {
+        let interval = poll_interval(&app_handle);
+        if interval.is_zero() {
+            tokio::time::sleep(DISABLED_CHECK).await;
+            continue;
+        }
+
+        tokio::time::sleep(monitor.next_wait(interval)).await;
+
+        // A consulta faz E/S bloqueante, então roda fora do executor assíncrono
+        let handle = app_handle.clone();
+        let task = tauri::async_runtime::spawn_blocking(move || {
+            let poll = poll_once(&handle, &mut targets);
+            (targets, poll)
+        });
+        let poll = match task.await {
+            Ok((cache, poll)) => {
+                targets = cache;
+                poll
+            }
+            Err(e) => {
+                targets = TargetCache::default();
+                Err(format!("Falha na tarefa de monitoramento: {}", e))
+            }
+        };
+
+        let event = match poll {
+            Ok(Poll::Busy) => continue,
+            Ok(Poll::Done(event)) => event,
+            Err(e) => PrinterStatusEvent::from_result(String::new(), Err(e)),
+        };
+
+        if let Some(event) = monitor.update(event, interval) {
+            println!("Status da impressora alterado: {:?}", event);
+            let _ = app_handle.emit_all(STATUS_EVENT, event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{FileTransport, MemoryTransport};
+
+    const INTERVAL: Duration = Duration::from_secs(10);
+    const TIMEOUT: Duration = Duration::from_millis(10);
+
+    fn event(response: &[u8]) -> PrinterStatusEvent {
+        let mut transport = MemoryTransport::new().with_response(response);
+        status_event("Usb".to_string(), &mut transport, TIMEOUT)
+    }
+
+    #[test]
+    fn resposta_da_impressora_vira_status() {
+        let ready = event(b"\x02NNNNNNNN\r");
+        assert!(ready.supported && ready.reachable);
+        assert!(ready.status.unwrap().ready);
+
+        let silent = event(b"");
+        assert!(silent.supported && !silent.reachable);
+        assert!(silent.error.is_some());
+    }
+
+    #[test]
+    fn emite_somente_quando_o_estado_muda() {
+        let mut monitor = Monitor::default();
+
+        assert!(monitor.update(event(b"\x02NNNNNNNN\r"), INTERVAL).is_some());
+        assert!(monitor.update(event(b"\x02NNNNNNNN\r"), INTERVAL).is_none());
+
+        let paper_out = monitor.update(event(b"\x02NYNNNNNN\r"), INTERVAL).unwrap();
+        assert!(paper_out.status.unwrap().paper_out);
+
+        // Sem resposta duas vezes: a mensagem de erro pode variar, o estado não
+        assert!(monitor.update(event(b""), INTERVAL).is_some());
+        let mut other_error = event(b"");
+        other_error.error = Some("Outro erro".to_string());
+        assert!(monitor.update(other_error, INTERVAL).is_none());
+    }
+
+    #[test]
+    fn espera_dobra_enquanto_a_impressora_nao_responde() {
+        let mut monitor = Monitor::default();
+        let mut waits = Vec::new();
+
+        for _ in 0..7 {
+            monitor.update(event(b""), INTERVAL);
+            waits.push(monitor.next_wait(INTERVAL).as_secs());
+        }
+        assert_eq!(waits, [20, 40, 80, 160, 300, 300, 300]);
+
+        // A primeira resposta volta ao intervalo configurado
+        monitor.update(event(b"\x02NNNNNNNN\r"), INTERVAL);
+        assert_eq!(monitor.next_wait(INTERVAL), INTERVAL);
+    }
+
+    #[test]
+    fn destino_sem_leitura_nao_e_falha() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut transport = FileTransport::new(dir.path().join("saida.prn"));
+        let mut monitor = Monitor::default();
+
+        let unsupported = status_event("Spooler(\"Argox\")".to_string(), &mut transport, TIMEOUT);
+        assert_eq!(unsupported, PrinterStatusEvent::unsupported("Spooler(\"Argox\")".to_string()));
+        assert!(monitor.update(unsupported.clone(), INTERVAL).is_some());
+        assert!(monitor.update(unsupported, INTERVAL).is_none());
+        assert_eq!(monitor.next_wait(INTERVAL), INTERVAL);
+        // Nada foi enviado ao destino
+        assert!(!dir.path().join("saida.prn").exists());
+    }
+
+    #[test]
+    fn destino_so_e_resolvido_de_novo_quando_a_configuracao_muda() {
+        let mut targets = TargetCache::default();
+        let mut config = PrinterConfig { selected_printer: Some("Argox".to_string()), ..PrinterConfig::default() };
+        let calls = std::cell::Cell::new(0);
+        let mut resolve = |config: &PrinterConfig| {
+            targets.target(config, |config| {
+                calls.set(calls.get() + 1);
+                Ok(PrinterTarget::Spooler(config.selected_printer.clone().unwrap_or_default()))
+            })
+        };
+
+        assert_eq!(resolve(&config), Ok(PrinterTarget::Spooler("Argox".to_string())));
+        assert_eq!(resolve(&config), Ok(PrinterTarget::Spooler("Argox".to_string())));
+        config.darkness = 12;
+        assert_eq!(resolve(&config), Ok(PrinterTarget::Spooler("Argox".to_string())));
+        config.selected_printer = Some("Zebra".to_string());
+        assert_eq!(resolve(&config), Ok(PrinterTarget::Spooler("Zebra".to_string())));
+
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn falha_ao_resolver_nao_fica_guardada() {
+        let mut targets = TargetCache::default();
+        let config = PrinterConfig::default();
+
+        assert!(targets.target(&config, |_| Err("Impressora 'Argox' não encontrada no sistema".to_string())).is_err());
+        assert_eq!(targets.target(&config, |_| Ok(PrinterTarget::Usb)), Ok(PrinterTarget::Usb));
+    }
+}
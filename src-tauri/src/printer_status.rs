@@ -0,0 +1,329 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::transport::{self, PrintJobInfo, PrinterTarget, PrinterTransport};
+use crate::PrinterConfig;
+
+// Tempo máximo esperando a resposta de status
+pub const STATUS_TIMEOUT: Duration = Duration::from_millis(1500);
+
+// Protocolo usado para pedir o status
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusProtocol {
+    Ppla, // <SOH>A -> "abcdefgh<CR>" com Y/N em cada posição
+    Pplb, // ~S,CHECK -> código hexadecimal de dois dígitos (bits de erro)
+}
+
+impl StatusProtocol {
+    pub fn parse(value: &str) -> Result<StatusProtocol, String> {
+        match value.to_lowercase().as_str() {
+            "ppla" => Ok(StatusProtocol::Ppla),
+            "pplb" | "epl" | "epl2" => Ok(StatusProtocol::Pplb),
+            _ => Err(format!("Protocolo de status desconhecido: {}", value)),
+        }
+    }
+
+    // Comando que pede o status à impressora
+    pub fn request(&self) -> &'static [u8] {
+        match self {
+            StatusProtocol::Ppla => b"\x01A",
+            StatusProtocol::Pplb => b"~S,CHECK\r\n",
+        }
+    }
+}
+
+// Estado da impressora interpretado a partir da resposta
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PrinterStatus {
+    pub ready: bool,       // Pronta para imprimir (nenhuma condição abaixo ativa)
+    pub paper_out: bool,   // Sem papel (no PPLA também indica falha de mídia, como cabeça aberta)
+    pub ribbon_out: bool,  // Sem ribbon
+    pub head_open: bool,   // Cabeça de impressão aberta (somente PPLB informa separadamente)
+    pub paused: bool,      // Impressora em pausa
+    pub buffer_full: bool, // Interpretador ocupado, sem espaço para novos dados
+    pub printing: bool,    // Imprimindo no momento
+    pub paper_jam: bool,   // Papel enroscado (PPLB)
+    pub error: bool,       // Outro erro informado pela impressora
+    pub raw: String,       // Resposta recebida, para diagnóstico
+}
+
+impl PrinterStatus {
+    fn update_ready(mut self) -> Self {
+        self.ready = !(self.paper_out
+            || self.ribbon_out
+            || self.head_open
+            || self.paused
+            || self.buffer_full
+            || self.paper_jam
+            || self.error);
+        self
+    }
+}
+
+// Remove STX, CR, LF e espaços que cercam a resposta
+fn clean_response(response: &[u8]) -> String {
+    String::from_utf8_lossy(response)
+        .trim_matches(|c: char| c == '\x02' || c == '\x03' || c.is_whitespace())
+        .to_string()
+}
+
+// Interpreta a resposta do <SOH>A do PPLA:
+// a = interpretador ocupado, b = sem papel/falha, c = sem ribbon/falha,
+// d = imprimindo lote, e = imprimindo, f = pausa, g = etiqueta apresentada, h = sempre N
+pub fn parse_ppla_status(response: &[u8]) -> Result<PrinterStatus, String> {
+    let raw = clean_response(response);
+    let flags: Vec<char> = raw.chars().collect();
+
+    if flags.len() < 8 || !flags[..8].iter().all(|c| *c == 'Y' || *c == 'N') {
+        return Err(format!("Resposta de status PPLA inválida: {:?}", raw));
+    }
+
+    let flag = |index: usize| flags[index] == 'Y';
+
+    Ok(PrinterStatus {
+        buffer_full: flag(0),
+        paper_out: flag(1),
+        ribbon_out: flag(2),
+        printing: flag(3) || flag(4),
+        paused: flag(5),
+        raw,
+        ..Default::default()
+    }
+    .update_ready())
+}
+
+// Interpreta a resposta do ~S,CHECK do PPLB (código hexadecimal):
+// 01 = cabeça aberta, 02 = papel enroscado, 04 = sem papel, 08 = sem ribbon,
+// 10 = pausa, 20 = imprimindo, 80 = outro erro; 00 = pronta
+pub fn parse_pplb_status(response: &[u8]) -> Result<PrinterStatus, String> {
+    let raw = clean_response(response);
+
+    let code = u8::from_str_radix(&raw, 16)
+        .map_err(|_| format!("Resposta de status PPLB inválida: {:?}", raw))?;
+
+    Ok(PrinterStatus {
+        head_open: code & 0x01 != 0,
+        paper_jam: code & 0x02 != 0,
+        paper_out: code & 0x04 != 0,
+        ribbon_out: code & 0x08 != 0,
+        paused: code & 0x10 != 0,
+        printing: code & 0x20 != 0,
+        error: code & 0x80 != 0,
+        raw,
+        ..Default::default()
+    }
+    .update_ready())
+}
+
+pub fn parse_status(protocol: StatusProtocol, response: &[u8]) -> Result<PrinterStatus, String> {
+    match protocol {
+        StatusProtocol::Ppla => parse_ppla_status(response),
+        StatusProtocol::Pplb => parse_pplb_status(response),
+    }
+}
+
+// Lê a resposta até o fim da linha ou até o tempo limite
+fn read_response(transport: &mut dyn PrinterTransport, timeout: Duration) -> Result<Vec<u8>, String> {
+    let deadline = Instant::now() + timeout;
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 64];
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+
+        let count = transport.read(&mut buffer, remaining)?;
+        if count == 0 {
+            // Nada mais chegou dentro do tempo restante
+            if !response.is_empty() {
+                break;
+            }
+            // Conexões que devolvem fim de arquivo na hora não podem virar espera ativa
+            std::thread::sleep(Duration::from_millis(20));
+            continue;
+        }
+
+        response.extend_from_slice(&buffer[..count]);
+        if response.iter().any(|b| *b == b'\r' || *b == b'\n') {
+            break;
+        }
+    }
+
+    Ok(response)
+}
+
+// Envia o pedido de status e interpreta a resposta
+pub fn query_status(
+    transport: &mut dyn PrinterTransport,
+    protocol: StatusProtocol,
+    timeout: Duration,
+) -> Result<PrinterStatus, String> {
+    if !transport.supports_read() {
+        return Err(format!(
+            "Não é possível consultar o status ({}): a conexão não é bidirecional",
+            transport.describe()
+        ));
+    }
+
+    transport.send(protocol.request())?;
+
+    let response = read_response(transport, timeout)?;
+    if response.is_empty() {
+        return Err(format!("Sem resposta ao pedido de status ({})", transport.describe()));
+    }
+
+    parse_status(protocol, &response)
+}
+
+// Pede o status numa conexão já aberta, tentando os protocolos na ordem
+pub fn query_protocols(
+    transport: &mut dyn PrinterTransport,
+    protocols: &[StatusProtocol],
+    timeout: Duration,
+) -> Result<PrinterStatus, String> {
+    let mut last_error = "Nenhum protocolo de status informado".to_string();
+
+    for protocol in protocols {
+        match query_status(transport, *protocol, timeout) {
+            Ok(status) => return Ok(status),
+            Err(e) => {
+                println!("Falha ao consultar status ({:?}): {}", protocol, e);
+                last_error = e;
+            }
+        }
+    }
+
+    Err(last_error)
+}
+
+// Abre o destino, pede o status (tentando os protocolos na ordem) e fecha a conexão.
+// Quem chama deve ter reservado a impressora com transport::lock_printer().
+pub fn read_printer_status(
+    target: &PrinterTarget,
+    config: &PrinterConfig,
+    protocols: &[StatusProtocol],
+) -> Result<PrinterStatus, String> {
+    let mut transport = transport::open_transport(target, config, &PrintJobInfo::new("Status"))?;
+    let result = query_protocols(transport.as_mut(), protocols, STATUS_TIMEOUT);
+    let _ = transport.close();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+
+    // Respostas do <SOH>A como chegam da Argox: STX, oito flags e CR
+    const PPLA_READY: &[u8] = b"\x02NNNNNNNN\r";
+    const PPLA_PAPER_OUT: &[u8] = b"\x02NYNNNNNN\r";
+    const PPLA_RIBBON_OUT: &[u8] = b"\x02NNYNNNNN\r";
+    const PPLA_PAUSED_PRINTING: &[u8] = b"\x02NNNYNYNN\r";
+
+    #[test]
+    fn ppla_pronta() {
+        let status = parse_ppla_status(PPLA_READY).unwrap();
+
+        assert!(status.ready);
+        assert_eq!(status.raw, "NNNNNNNN");
+    }
+
+    #[test]
+    fn ppla_sem_papel_e_cabeca_aberta() {
+        // O PPLA não separa cabeça aberta: ela aparece como falha de mídia (flag b)
+        let status = parse_ppla_status(PPLA_PAPER_OUT).unwrap();
+
+        assert!(status.paper_out);
+        assert!(!status.head_open);
+        assert!(!status.ready);
+    }
+
+    #[test]
+    fn ppla_sem_ribbon() {
+        let status = parse_ppla_status(PPLA_RIBBON_OUT).unwrap();
+
+        assert!(status.ribbon_out);
+        assert!(!status.paper_out);
+        assert!(!status.ready);
+    }
+
+    #[test]
+    fn ppla_em_pausa_imprimindo() {
+        let status = parse_ppla_status(PPLA_PAUSED_PRINTING).unwrap();
+
+        assert!(status.printing);
+        assert!(status.paused);
+        assert!(!status.ready);
+    }
+
+    #[test]
+    fn ppla_resposta_cortada_ou_invalida() {
+        for response in [&b"\x02NNNN"[..], b"", b"\r\n", b"\x02NNNNXNNN\r", b"\xff\xfe\xfd\xfc\xfb\xfa\xf9\xf8"] {
+            let error = parse_ppla_status(response).unwrap_err();
+            assert!(error.starts_with("Resposta de status PPLA inválida"), "{:?}: {}", response, error);
+        }
+    }
+
+    #[test]
+    fn pplb_pronta() {
+        let status = parse_pplb_status(b"00\r\n").unwrap();
+
+        assert!(status.ready);
+        assert_eq!(status.raw, "00");
+    }
+
+    #[test]
+    fn pplb_cabeca_aberta_sem_papel_e_sem_ribbon() {
+        let head_open = parse_pplb_status(b"01\r\n").unwrap();
+        assert!(head_open.head_open && !head_open.ready);
+
+        let paper_out = parse_pplb_status(b"04\r\n").unwrap();
+        assert!(paper_out.paper_out && !paper_out.head_open && !paper_out.ready);
+
+        let ribbon_out = parse_pplb_status(b"08\r\n").unwrap();
+        assert!(ribbon_out.ribbon_out && !ribbon_out.ready);
+
+        // Várias condições ao mesmo tempo: cabeça aberta + sem papel + outro erro
+        let combined = parse_pplb_status(b"\x0285\x03\r\n").unwrap();
+        assert!(combined.head_open && combined.paper_out && combined.error);
+    }
+
+    #[test]
+    fn pplb_imprimindo_continua_pronta() {
+        let status = parse_pplb_status(b"20\r\n").unwrap();
+
+        assert!(status.printing);
+        assert!(status.ready);
+    }
+
+    #[test]
+    fn pplb_resposta_cortada_ou_invalida() {
+        for response in [&b""[..], b"\r\n", b"0G\r\n", b"123\r\n", b"\xff\xfe"] {
+            let error = parse_pplb_status(response).unwrap_err();
+            assert!(error.starts_with("Resposta de status PPLB inválida"), "{:?}: {}", response, error);
+        }
+    }
+
+    #[test]
+    fn consulta_envia_o_pedido_e_interpreta_a_resposta() {
+        let printer = MemoryTransport::new().with_response(PPLA_PAPER_OUT);
+        let mut transport = printer.clone();
+
+        let status = query_status(&mut transport, StatusProtocol::Ppla, Duration::from_millis(200)).unwrap();
+
+        assert!(status.paper_out);
+        assert_eq!(printer.sent(), b"\x01A");
+    }
+
+    #[test]
+    fn consulta_sem_resposta_gera_erro() {
+        let mut transport = MemoryTransport::new();
+
+        let error = query_status(&mut transport, StatusProtocol::Pplb, Duration::from_millis(50)).unwrap_err();
+
+        assert_eq!(error, "Sem resposta ao pedido de status (impressora em memória)");
+    }
+}
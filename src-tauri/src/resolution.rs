@@ -0,0 +1,107 @@
+use crate::PrinterConfig;
+
+// Resolução da cabeça de impressão. As medidas em milímetros (perfis de mídia e modelos)
+// são convertidas para os dots da impressora na geração.
+// Como no manual do TSPL, 203 dpi conta como 8 dots por mm e 300 dpi como 12.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resolution {
+    dpi: u32,
+}
+
+// Resoluções aceitas e os dots por milímetro de cada uma
+const SUPPORTED: [(u32, u32); 2] = [(203, 8), (300, 12)];
+
+impl Resolution {
+    // Resolução das configurações criadas antes da escolha de resolução e das fontes dos modelos
+    pub const DEFAULT_DPI: u32 = 203;
+
+    pub fn new(dpi: u32) -> Result<Resolution, String> {
+        if SUPPORTED.iter().any(|(supported, _)| *supported == dpi) {
+            Ok(Resolution { dpi })
+        } else {
+            Err(format!("Resolução de {} dpi não suportada (use 203 ou 300)", dpi))
+        }
+    }
+
+    // Resolução configurada para a impressora
+    pub fn of_printer(config: &PrinterConfig) -> Result<Resolution, String> {
+        Resolution::new(config.dpi)
+    }
+
+    pub fn dpi(&self) -> u32 {
+        self.dpi
+    }
+
+    pub fn dots_per_mm(&self) -> u32 {
+        SUPPORTED
+            .iter()
+            .find(|(dpi, _)| *dpi == self.dpi)
+            .map(|(_, dots)| *dots)
+            .unwrap_or(8)
+    }
+
+    // Milímetros -> dots
+    pub fn dots(&self, mm: f64) -> u32 {
+        (mm * self.dots_per_mm() as f64).round().max(0.0) as u32
+    }
+
+    // Dots -> milímetros
+    pub fn mm(&self, dots: u32) -> f64 {
+        dots as f64 / self.dots_per_mm() as f64
+    }
+
+    // Quanto uma medida da resolução `from` cresce nesta resolução
+    pub fn factor(&self, from: Resolution) -> f64 {
+        self.dots_per_mm() as f64 / from.dots_per_mm() as f64
+    }
+}
+
+impl Default for Resolution {
+    fn default() -> Self {
+        Resolution {
+            dpi: Resolution::DEFAULT_DPI,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dpi(value: u32) -> Resolution {
+        Resolution::new(value).unwrap()
+    }
+
+    #[test]
+    fn resolucoes_aceitas() {
+        assert_eq!(dpi(203).dots_per_mm(), 8);
+        assert_eq!(dpi(300).dots_per_mm(), 12);
+        assert_eq!(Resolution::default(), dpi(Resolution::DEFAULT_DPI));
+        assert_eq!(Resolution::new(600).unwrap_err(), "Resolução de 600 dpi não suportada (use 203 ou 300)");
+    }
+
+    #[test]
+    fn milimetros_e_dots() {
+        assert_eq!(dpi(203).dots(33.0), 264);
+        assert_eq!(dpi(300).dots(33.0), 396);
+        assert_eq!(dpi(203).dots(3.1), 25);
+        assert_eq!(dpi(203).dots(-1.0), 0);
+        assert_eq!(dpi(300).mm(396), 33.0);
+    }
+
+    #[test]
+    fn fator_entre_resolucoes() {
+        assert_eq!(dpi(300).factor(dpi(203)), 1.5);
+        assert_eq!(dpi(203).factor(dpi(203)), 1.0);
+    }
+
+    #[test]
+    fn milimetros_de_dots_de_203_voltam_exatos_a_203_e_a_300() {
+        let (low, high) = (dpi(203), dpi(300));
+
+        for dots in 0..2000 {
+            assert_eq!(low.dots(low.mm(dots)), dots);
+            assert_eq!(high.dots(low.mm(dots)), (dots as f64 * 1.5).round() as u32);
+        }
+    }
+}
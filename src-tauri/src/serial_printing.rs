@@ -0,0 +1,195 @@
+use std::io::{ErrorKind, Read, Write};
+use std::time::Duration;
+
+use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
+
+use crate::transport::PrinterTransport;
+use crate::PrinterConfig;
+
+// Reconhece portas seriais: "serial:<porta>", "COM1", "/dev/ttyS0", "/dev/ttyUSB0" ou "/dev/pts/3"
+pub fn parse_serial_port(value: &str) -> Option<String> {
+    if let Some(port) = value.strip_prefix("serial:") {
+        return Some(port.trim().to_string());
+    }
+
+    // get() em vez de fatiar: nomes de fila com acento ("Ação") não podem cortar um caractere ao meio
+    let is_com_port = value.get(..3).is_some_and(|prefix| prefix.eq_ignore_ascii_case("COM"))
+        && value.get(3..).is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+
+    if is_com_port || value.starts_with("/dev/tty") || value.starts_with("/dev/pts/") {
+        return Some(value.to_string());
+    }
+
+    None
+}
+
+fn data_bits(value: u8) -> Result<DataBits, String> {
+    match value {
+        5 => Ok(DataBits::Five),
+        6 => Ok(DataBits::Six),
+        7 => Ok(DataBits::Seven),
+        8 => Ok(DataBits::Eight),
+        _ => Err(format!("Bits de dados inválidos: {} (use 5, 6, 7 ou 8)", value)),
+    }
+}
+
+fn parity(value: &str) -> Result<Parity, String> {
+    match value.to_lowercase().as_str() {
+        "none" | "nenhuma" | "n" => Ok(Parity::None),
+        "even" | "par" | "e" => Ok(Parity::Even),
+        "odd" | "impar" | "ímpar" | "o" => Ok(Parity::Odd),
+        _ => Err(format!("Paridade inválida: {} (use none, even ou odd)", value)),
+    }
+}
+
+fn stop_bits(value: u8) -> Result<StopBits, String> {
+    match value {
+        1 => Ok(StopBits::One),
+        2 => Ok(StopBits::Two),
+        _ => Err(format!("Stop bits inválidos: {} (use 1 ou 2)", value)),
+    }
+}
+
+fn flow_control(value: &str) -> Result<FlowControl, String> {
+    match value.to_lowercase().as_str() {
+        "none" | "nenhum" => Ok(FlowControl::None),
+        "software" | "xon/xoff" | "xonxoff" => Ok(FlowControl::Software),
+        "hardware" | "rts/cts" | "rtscts" => Ok(FlowControl::Hardware),
+        _ => Err(format!("Controle de fluxo inválido: {} (use none, software ou hardware)", value)),
+    }
+}
+
+// Lista as portas seriais disponíveis no sistema
+pub fn list_serial_ports() -> Result<Vec<String>, String> {
+    let ports = serialport::available_ports()
+        .map_err(|e| format!("Erro ao listar portas seriais: {}", e))?;
+
+    Ok(ports.into_iter().map(|port| port.port_name).collect())
+}
+
+// Transporte serial (RS-232 ou adaptador USB-serial)
+pub struct SerialTransport {
+    port_name: String,
+    port: Box<dyn SerialPort>,
+}
+
+impl SerialTransport {
+    pub fn open(port_name: &str, config: &PrinterConfig) -> Result<Self, String> {
+        let port = serialport::new(port_name, config.baud_rate)
+            .data_bits(data_bits(config.data_bits)?)
+            .parity(parity(&config.parity)?)
+            .stop_bits(stop_bits(config.stop_bits)?)
+            .flow_control(flow_control(&config.flow_control)?)
+            .timeout(Duration::from_millis(config.write_timeout_ms as u64))
+            .open()
+            .map_err(|e| format!("Erro ao abrir porta serial '{}': {}", port_name, e))?;
+
+        println!(
+            "Porta serial {} aberta ({} baud, {} bits, paridade {}, {} stop bits, fluxo {})",
+            port_name, config.baud_rate, config.data_bits, config.parity, config.stop_bits, config.flow_control
+        );
+
+        Ok(Self {
+            port_name: port_name.to_string(),
+            port,
+        })
+    }
+}
+
+impl PrinterTransport for SerialTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        self.port
+            .write_all(data)
+            .and_then(|_| self.port.flush())
+            .map_err(|e| format!("Erro ao enviar dados para '{}': {}", self.port_name, e))
+    }
+
+    fn read(&mut self, buffer: &mut [u8], timeout: Duration) -> Result<usize, String> {
+        self.port
+            .set_timeout(timeout)
+            .map_err(|e| format!("Erro ao configurar tempo limite de leitura: {}", e))?;
+
+        match self.port.read(buffer) {
+            Ok(count) => Ok(count),
+            // Sem resposta dentro do tempo limite
+            Err(e) if e.kind() == ErrorKind::TimedOut || e.kind() == ErrorKind::WouldBlock => Ok(0),
+            Err(e) => Err(format!("Erro ao ler resposta de '{}': {}", self.port_name, e)),
+        }
+    }
+
+    fn supports_read(&self) -> bool {
+        true
+    }
+
+    fn describe(&self) -> String {
+        format!("porta serial '{}'", self.port_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reconhece_portas_seriais() {
+        assert_eq!(parse_serial_port("COM1").as_deref(), Some("COM1"));
+        assert_eq!(parse_serial_port("com12").as_deref(), Some("com12"));
+        assert_eq!(parse_serial_port("serial: /dev/ttyACM0").as_deref(), Some("/dev/ttyACM0"));
+        assert_eq!(parse_serial_port("/dev/ttyUSB0").as_deref(), Some("/dev/ttyUSB0"));
+        assert_eq!(parse_serial_port("/dev/pts/3").as_deref(), Some("/dev/pts/3"));
+        assert_eq!(parse_serial_port("COM"), None);
+        assert_eq!(parse_serial_port("COMPARTILHADA"), None);
+    }
+
+    #[test]
+    fn nomes_de_fila_com_acento_nao_sao_portas() {
+        for name in ["Ação", "ÇÃ…", "Cô", "CO\u{301}M1", "É"] {
+            assert_eq!(parse_serial_port(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn configuracao_invalida_gera_erro() {
+        assert!(data_bits(9).is_err());
+        assert!(parity("mark").is_err());
+        assert!(stop_bits(3).is_err());
+        assert!(flow_control("dtr").is_err());
+        assert_eq!(parity("Ímpar"), Ok(Parity::Odd));
+        assert_eq!(flow_control("XON/XOFF"), Ok(FlowControl::Software));
+    }
+
+    // Par de pseudoterminais: o lado escravo faz o papel da porta serial e o mestre, da impressora
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn envia_e_le_por_um_pseudoterminal() {
+        use std::ffi::CStr;
+        use std::fs::File;
+        use std::os::unix::io::FromRawFd;
+
+        let mut master = 0;
+        let mut slave = 0;
+        let mut name = [0 as libc::c_char; 128];
+        // SAFETY: os ponteiros apontam para variáveis locais válidas; termios e winsize nulos usam o padrão
+        let result = unsafe {
+            libc::openpty(&mut master, &mut slave, name.as_mut_ptr(), std::ptr::null(), std::ptr::null())
+        };
+        assert_eq!(result, 0, "openpty falhou: {}", std::io::Error::last_os_error());
+        // SAFETY: openpty devolveu descritores abertos que passam a pertencer aos File
+        let (mut printer, _slave) = unsafe { (File::from_raw_fd(master), File::from_raw_fd(slave)) };
+        // SAFETY: openpty grava no buffer o caminho do escravo terminado em zero
+        let port_name = unsafe { CStr::from_ptr(name.as_ptr()) }.to_string_lossy().to_string();
+        assert_eq!(parse_serial_port(&port_name).as_deref(), Some(port_name.as_str()));
+
+        let mut transport = SerialTransport::open(&port_name, &PrinterConfig::default()).unwrap();
+        transport.send(b"N\r\nP1\r\n").unwrap();
+        let mut received = [0u8; 7];
+        printer.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"N\r\nP1\r\n");
+
+        // Resposta da "impressora" lida pela porta
+        printer.write_all(b"@0\r\n").unwrap();
+        let mut buffer = [0u8; 16];
+        let count = transport.read(&mut buffer, Duration::from_secs(2)).unwrap();
+        assert_eq!(&buffer[..count], b"@0\r\n");
+    }
+}
@@ -0,0 +1,51 @@
+// Dados compartilhados pelos testes da geração de etiquetas
+
+use crate::label_render::{self, LabelBatch, LabelLanguage};
+use crate::label_template::LabelTemplate;
+use crate::media_profile::Media;
+use crate::{PrinterConfig, Product};
+
+// Produto só com os campos usados nas etiquetas
+pub fn product(code: &str, name: &str, barcode: &str) -> Product {
+    Product {
+        id: None,
+        product_code: code.to_string(),
+        name: name.to_string(),
+        name_short: name.to_string(),
+        barcode: barcode.to_string(),
+        description: None,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+// Lote gerado com a configuração padrão e a mídia do próprio modelo
+pub fn build(language: LabelLanguage, template: &LabelTemplate, products: &[Option<Product>]) -> LabelBatch {
+    let config = PrinterConfig::default();
+    let media = Media::from_config(template, &config).unwrap();
+    label_render::build_label_batch(language, template, &media, &config, products).unwrap()
+}
+
+// Fileira de referência do modelo padrão: um nome que cabe, um que não cabe na fonte 2 e uma posição vazia.
+// Com as fontes do PPLA o nome longo é cortado; no TSPL a fonte 1 (8 dots) ainda comporta o nome inteiro.
+pub fn default_template_batch(language: LabelLanguage) -> LabelBatch {
+    let products = [
+        Some(product("ARR-001", "Arruela lisa 1/4", "7891234567895")),
+        Some(product("PRF-010", "Parafuso sextavado galvanizado", "7890000000017")),
+        None,
+    ];
+
+    let batch = build(language, &LabelTemplate::default_template(), &products);
+    let warning = match language {
+        LabelLanguage::Tspl => "não cabe em 264 dots com a fonte 2; impresso com a fonte 1",
+        _ => "não cabe em 264 dots nem com a fonte 1; texto cortado",
+    };
+    assert_eq!(batch.warnings, [format!("Produto PRF-010: \"Parafuso sextavado galvanizado\" {}", warning)]);
+    batch
+}
+
+// Compara com a saída de referência mostrando o texto, para a diferença ficar legível
+pub fn assert_golden(data: &[u8], golden: &[u8]) {
+    assert_eq!(String::from_utf8_lossy(data), String::from_utf8_lossy(golden));
+    assert_eq!(data, golden);
+}
@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+
+use crate::label_metrics::{self, FontMetrics};
+
+// O que fazer quando o texto não cabe na largura disponível
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitMode {
+    Shrink,   // Usa uma fonte menor (e corta se nem a menor couber)
+    Wrap,     // Quebra em mais linhas, cortando a última se passar de max_lines
+    Truncate, // Corta o texto e acrescenta o marcador
+}
+
+// Ajuste de um texto a uma caixa; medidas em mm no modelo e em dots da impressora na geração
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextFit<U = f64> {
+    pub mode: FitMode,
+    #[serde(default)]
+    pub width: Option<U>, // Largura da caixa (sem valor: até o fim da coluna)
+    #[serde(default = "default_max_lines")]
+    pub max_lines: u32, // Linhas da caixa no modo wrap
+    #[serde(default)]
+    pub line_spacing: U, // Espaço extra entre linhas
+    #[serde(default = "default_marker")]
+    pub marker: String, // Indicação de texto cortado
+}
+
+fn default_max_lines() -> u32 {
+    2
+}
+
+fn default_marker() -> String {
+    "...".to_string()
+}
+
+impl<U: Copy> TextFit<U> {
+    // Mesmo ajuste com as medidas convertidas (mm <-> dots)
+    pub fn map_units<V>(&self, convert: impl Fn(U) -> V) -> TextFit<V> {
+        TextFit {
+            mode: self.mode,
+            width: self.width.map(&convert),
+            max_lines: self.max_lines,
+            line_spacing: convert(self.line_spacing),
+            marker: self.marker.clone(),
+        }
+    }
+}
+
+impl TextFit {
+    pub fn validate(&self) -> Result<(), String> {
+        if matches!(self.width, Some(width) if width <= 0.0) {
+            return Err("A largura da caixa de texto deve ser maior que zero".to_string());
+        }
+        if self.max_lines == 0 {
+            return Err("A caixa de texto precisa de pelo menos uma linha".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Resultado do ajuste: fonte usada, linhas e o aviso para o operador (se o texto foi alterado)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FittedText {
+    pub font: String,
+    pub lines: Vec<String>,
+    pub warning: Option<String>,
+}
+
+// Largura do texto na fonte dada
+fn measure(fonts: &FontMetrics, font: &str, h_mult: u32, content: &str) -> u32 {
+    fonts
+        .text_size(font, h_mult, 1, content)
+        .map(|(width, _)| width)
+        .unwrap_or(u32::MAX)
+}
+
+// Maior início do texto que cabe na largura junto com o marcador
+fn truncate(fonts: &FontMetrics, font: &str, h_mult: u32, content: &str, marker: &str, width: u32) -> String {
+    let chars: Vec<char> = content.chars().collect();
+
+    for end in (0..chars.len()).rev() {
+        let prefix: String = chars[..end].iter().collect();
+        let candidate = format!("{}{}", prefix.trim_end(), marker);
+        if measure(fonts, font, h_mult, &candidate) <= width {
+            return candidate;
+        }
+    }
+
+    // Nem o marcador cabe: fica só o que couber do texto
+    let mut result = String::new();
+    for c in chars {
+        result.push(c);
+        if measure(fonts, font, h_mult, &result) > width {
+            result.pop();
+            break;
+        }
+    }
+    result
+}
+
+// Quebra nas palavras; palavras maiores que a caixa são quebradas nos caracteres
+fn wrap(fonts: &FontMetrics, font: &str, h_mult: u32, content: &str, width: u32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in content.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+
+        if measure(fonts, font, h_mult, &candidate) <= width {
+            current = candidate;
+            continue;
+        }
+
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+
+        for c in word.chars() {
+            current.push(c);
+            if measure(fonts, font, h_mult, &current) > width && current.chars().count() > 1 {
+                current.pop();
+                lines.push(std::mem::replace(&mut current, c.to_string()));
+            }
+        }
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+// Ajusta o texto à largura disponível (em dots) conforme o modo escolhido, medindo com as fontes da linguagem
+pub fn fit_text(fonts: &FontMetrics, fit: &TextFit<u32>, font: &str, h_mult: u32, content: &str, width: u32) -> FittedText {
+    let width = fit.width.unwrap_or(width);
+
+    if measure(fonts, font, h_mult, content) <= width {
+        return FittedText {
+            font: font.to_string(),
+            lines: vec![content.to_string()],
+            warning: None,
+        };
+    }
+
+    match fit.mode {
+        FitMode::Shrink => {
+            // Fontes menores que a atual, da maior para a menor
+            let smaller = label_metrics::FONTS
+                .iter()
+                .rev()
+                .skip_while(|candidate| **candidate != font)
+                .skip(1);
+
+            for candidate in smaller {
+                if measure(fonts, candidate, h_mult, content) <= width {
+                    return FittedText {
+                        font: candidate.to_string(),
+                        lines: vec![content.to_string()],
+                        warning: Some(format!(
+                            "\"{}\" não cabe em {} dots com a fonte {}; impresso com a fonte {}",
+                            content, width, font, candidate
+                        )),
+                    };
+                }
+            }
+
+            let smallest = label_metrics::FONTS[0];
+            FittedText {
+                font: smallest.to_string(),
+                lines: vec![truncate(fonts, smallest, h_mult, content, &fit.marker, width)],
+                warning: Some(format!(
+                    "\"{}\" não cabe em {} dots nem com a fonte {}; texto cortado",
+                    content, width, smallest
+                )),
+            }
+        }
+        FitMode::Wrap => {
+            let mut lines = wrap(fonts, font, h_mult, content, width);
+            let max_lines = fit.max_lines.max(1) as usize;
+
+            let warning = if lines.len() > max_lines {
+                let rest = lines.split_off(max_lines - 1).join(" ");
+                lines.push(truncate(fonts, font, h_mult, &rest, &fit.marker, width));
+                format!(
+                    "\"{}\" não cabe em {} linha(s) de {} dots; texto cortado",
+                    content, max_lines, width
+                )
+            } else {
+                format!("\"{}\" quebrado em {} linhas para caber em {} dots", content, lines.len(), width)
+            };
+
+            FittedText {
+                font: font.to_string(),
+                lines,
+                warning: Some(warning),
+            }
+        }
+        FitMode::Truncate => FittedText {
+            font: font.to_string(),
+            lines: vec![truncate(fonts, font, h_mult, content, &fit.marker, width)],
+            warning: Some(format!("\"{}\" não cabe em {} dots; texto cortado", content, width)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::label_metrics::PPLA_FONTS;
+
+    // Células: fonte 1 = 10 dots, fonte 2 = 12, fonte 3 = 14 (sem o espaço depois do último caractere)
+    fn fit(mode: FitMode, max_lines: u32, marker: &str) -> TextFit<u32> {
+        TextFit {
+            mode,
+            width: None,
+            max_lines,
+            line_spacing: 0,
+            marker: marker.to_string(),
+        }
+    }
+
+    #[test]
+    fn texto_que_cabe_nao_muda() {
+        let fitted = fit_text(&PPLA_FONTS, &fit(FitMode::Truncate, 1, "..."), "2", 1, "ARR-001", 82);
+
+        assert_eq!(fitted, FittedText { font: "2".to_string(), lines: vec!["ARR-001".to_string()], warning: None });
+    }
+
+    #[test]
+    fn reduz_para_a_maior_fonte_que_cabe() {
+        // "ABCDEFGHIJ": 138 dots na fonte 3, 118 na 2
+        let fitted = fit_text(&PPLA_FONTS, &fit(FitMode::Shrink, 1, "..."), "3", 1, "ABCDEFGHIJ", 120);
+
+        assert_eq!(fitted.font, "2");
+        assert_eq!(fitted.lines, ["ABCDEFGHIJ"]);
+        assert_eq!(fitted.warning.as_deref(), Some("\"ABCDEFGHIJ\" não cabe em 120 dots com a fonte 3; impresso com a fonte 2"));
+    }
+
+    #[test]
+    fn reducao_sem_fonte_que_caiba_corta_na_menor() {
+        // Nem a fonte 1 (98 dots) cabe em 50: fica "AB..." (48 dots) na fonte 1
+        let fitted = fit_text(&PPLA_FONTS, &fit(FitMode::Shrink, 1, "..."), "3", 1, "ABCDEFGHIJ", 50);
+
+        assert_eq!(fitted.font, "1");
+        assert_eq!(fitted.lines, ["AB..."]);
+        assert_eq!(fitted.warning.as_deref(), Some("\"ABCDEFGHIJ\" não cabe em 50 dots nem com a fonte 1; texto cortado"));
+    }
+
+    #[test]
+    fn quebra_nas_palavras_dentro_das_linhas() {
+        let fitted = fit_text(&PPLA_FONTS, &fit(FitMode::Wrap, 2, "..."), "2", 1, "ARRUELA LISA", 100);
+
+        assert_eq!(fitted.lines, ["ARRUELA", "LISA"]);
+        assert_eq!(fitted.warning.as_deref(), Some("\"ARRUELA LISA\" quebrado em 2 linhas para caber em 100 dots"));
+    }
+
+    #[test]
+    fn quebra_alem_de_max_lines_corta_a_ultima_linha() {
+        // Quatro linhas de palavras; a segunda recebe o resto ("LISA ZINCADA GRANDE") cortado em 100 dots ("LISA Z..." teria 106)
+        let fitted = fit_text(&PPLA_FONTS, &fit(FitMode::Wrap, 2, "..."), "2", 1, "ARRUELA LISA ZINCADA GRANDE", 100);
+
+        assert_eq!(fitted.lines, ["ARRUELA", "LISA..."]);
+        assert_eq!(
+            fitted.warning.as_deref(),
+            Some("\"ARRUELA LISA ZINCADA GRANDE\" não cabe em 2 linha(s) de 100 dots; texto cortado")
+        );
+    }
+
+    #[test]
+    fn palavra_maior_que_a_caixa_e_quebrada_nos_caracteres() {
+        let fitted = fit_text(&PPLA_FONTS, &fit(FitMode::Wrap, 3, "..."), "2", 1, "ABCDEFGHIJ", 50);
+
+        assert_eq!(fitted.lines, ["ABCD", "EFGH", "IJ"]);
+    }
+
+    #[test]
+    fn marcador_maior_que_a_caixa_fica_de_fora() {
+        // "[cortado]" ocupa 106 dots: sobra só o início do texto que cabe em 50
+        let fitted = fit_text(&PPLA_FONTS, &fit(FitMode::Truncate, 1, "[cortado]"), "2", 1, "ABCDEFGH", 50);
+
+        assert_eq!(fitted.lines, ["ABCD"]);
+        assert_eq!(fitted.warning.as_deref(), Some("\"ABCDEFGH\" não cabe em 50 dots; texto cortado"));
+    }
+
+    #[test]
+    fn largura_da_caixa_substitui_a_da_coluna() {
+        let mut truncate = fit(FitMode::Truncate, 1, "...");
+        truncate.width = Some(70);
+
+        let fitted = fit_text(&PPLA_FONTS, &truncate, "2", 1, "ABCDEFGHIJ", 264);
+
+        assert_eq!(fitted.lines, ["ABC..."]);
+    }
+
+    #[test]
+    fn caixa_sem_largura_ou_sem_linhas_e_recusada() {
+        let mut empty = fit(FitMode::Wrap, 2, "...").map_units(f64::from);
+        empty.width = Some(0.0);
+        assert_eq!(empty.validate().unwrap_err(), "A largura da caixa de texto deve ser maior que zero");
+
+        assert_eq!(fit(FitMode::Wrap, 0, "...").map_units(f64::from).validate().unwrap_err(), "A caixa de texto precisa de pelo menos uma linha");
+    }
+}
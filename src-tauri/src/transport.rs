@@ -0,0 +1,438 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+#[cfg(test)]
+use std::sync::Arc;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+#[cfg(unix)]
+use crate::cups_printing::CupsTransport;
+use crate::file_printer::{VirtualFilePrinter, VIRTUAL_PRINTER_NAME};
+use crate::network_printing::TcpTransport;
+use crate::printer::UsbPrinter;
+use crate::serial_printing::SerialTransport;
+#[cfg(target_os = "linux")]
+use crate::usb_lp_printing::UsbLpTransport;
+#[cfg(windows)]
+use crate::windows_printing::WindowsSpoolerTransport;
+use crate::PrinterConfig;
+
+// Garante que só uma conversa com a impressora aconteça por vez
+// (trabalhos de impressão e consultas de status não podem intercalar bytes)
+static PRINTER_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+// Aguarda a impressora ficar livre
+pub fn lock_printer() -> MutexGuard<'static, ()> {
+    PRINTER_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Reserva a impressora somente se ela estiver livre (usado pelo monitor de status)
+pub fn try_lock_printer() -> Option<MutexGuard<'static, ()>> {
+    PRINTER_LOCK.try_lock().ok()
+}
+
+// Abstração de uma conexão com a impressora.
+// O código das etiquetas só gera bytes; quem decide por onde eles saem é o transporte.
+pub trait PrinterTransport: Send {
+    // Envia os bytes para a impressora
+    fn send(&mut self, data: &[u8]) -> Result<(), String>;
+
+    // Lê a resposta da impressora (somente conexões bidirecionais)
+    fn read(&mut self, _buffer: &mut [u8], _timeout: Duration) -> Result<usize, String> {
+        Err(format!("{} não permite leitura de resposta", self.describe()))
+    }
+
+    // Indica se a conexão permite ler respostas da impressora
+    fn supports_read(&self) -> bool {
+        false
+    }
+
+    // Descrição legível da conexão, usada em logs e mensagens de erro
+    fn describe(&self) -> String;
+
+    // Libera a conexão
+    fn close(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Destino de impressão, interpretado a partir do nome da impressora
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrinterTarget {
+    Spooler(String), // Fila de impressão do sistema (spooler do Windows ou CUPS)
+    Usb,             // Argox OS-2140 direto pela USB (rusb)
+    Tcp(String),     // Impressora de rede, socket RAW ("tcp://host:porta")
+    UsbLp(PathBuf),  // Dispositivo da classe impressora no Linux ("/dev/usb/lp0")
+    Serial(String),  // Porta serial ("COM1", "/dev/ttyUSB0", "serial:<porta>")
+    File(PathBuf),   // Grava os bytes em arquivo ("file://caminho")
+    Virtual,         // Impressora virtual: um .prn + .json por trabalho na pasta configurada
+}
+
+// Identificação do trabalho enviado
+#[derive(Debug, Clone, Serialize)]
+pub struct PrintJobInfo {
+    pub job_id: String,        // Id único do trabalho (data e hora do envio)
+    pub document_name: String, // Nome do documento no spooler
+    pub product_ids: Vec<i64>, // Produtos impressos no trabalho
+}
+
+impl PrintJobInfo {
+    pub fn new(document_name: &str) -> Self {
+        Self {
+            job_id: chrono::Local::now().format("%Y%m%d-%H%M%S-%3f").to_string(),
+            document_name: document_name.to_string(),
+            product_ids: Vec::new(),
+        }
+    }
+
+    pub fn with_product_ids(mut self, product_ids: Vec<i64>) -> Self {
+        self.product_ids = product_ids;
+        self
+    }
+}
+
+impl PrinterTarget {
+    pub fn parse(value: &str) -> PrinterTarget {
+        let value = value.trim();
+
+        if value == VIRTUAL_PRINTER_NAME {
+            return PrinterTarget::Virtual;
+        }
+
+        if value.eq_ignore_ascii_case("usb") || value.eq_ignore_ascii_case("usb://") {
+            return PrinterTarget::Usb;
+        }
+
+        if let Some(address) = value.strip_prefix("tcp://") {
+            return PrinterTarget::Tcp(crate::network_printing::normalize_address(address));
+        }
+
+        if let Some(path) = value.strip_prefix("file://") {
+            return PrinterTarget::File(PathBuf::from(path));
+        }
+
+        if let Some(path) = parse_usb_lp_path(value) {
+            return PrinterTarget::UsbLp(path);
+        }
+
+        if let Some(port) = crate::serial_printing::parse_serial_port(value) {
+            return PrinterTarget::Serial(port);
+        }
+
+        PrinterTarget::Spooler(value.to_string())
+    }
+
+    // Nome do destino no mesmo formato aceito por parse(), usado no histórico e na interface
+    pub fn display_name(&self) -> String {
+        match self {
+            PrinterTarget::Spooler(name) => name.clone(),
+            PrinterTarget::Usb => "usb".to_string(),
+            PrinterTarget::Tcp(address) => format!("tcp://{}", address),
+            PrinterTarget::UsbLp(path) => path.display().to_string(),
+            PrinterTarget::Serial(port) => port.clone(),
+            PrinterTarget::File(path) => format!("file://{}", path.display()),
+            PrinterTarget::Virtual => VIRTUAL_PRINTER_NAME.to_string(),
+        }
+    }
+}
+
+// Reconhece o caminho de um dispositivo usblp nos formatos aceitos:
+// "usblp:<caminho>", "/dev/usb/lp0" ou o nome da lista, "Argox OS-2140 (/dev/usb/lp0)"
+fn parse_usb_lp_path(value: &str) -> Option<PathBuf> {
+    if let Some(path) = value.strip_prefix("usblp:") {
+        return Some(PathBuf::from(path.trim()));
+    }
+
+    if value.starts_with("/dev/usb/lp") {
+        return Some(PathBuf::from(value));
+    }
+
+    value
+        .strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once('('))
+        .map(|(_, path)| path.trim())
+        .filter(|path| path.starts_with("/dev/usb/lp"))
+        .map(PathBuf::from)
+}
+
+// Abre o transporte correspondente ao destino
+pub fn open_transport(
+    target: &PrinterTarget,
+    config: &PrinterConfig,
+    job: &PrintJobInfo,
+) -> Result<Box<dyn PrinterTransport>, String> {
+    let document_name = job.document_name.as_str();
+
+    match target {
+        #[cfg(windows)]
+        PrinterTarget::Spooler(name) => Ok(Box::new(WindowsSpoolerTransport::new(name, document_name))),
+        #[cfg(unix)]
+        PrinterTarget::Spooler(name) => Ok(Box::new(CupsTransport::new(name, document_name))),
+        #[cfg(not(any(windows, unix)))]
+        PrinterTarget::Spooler(name) => Err(format!("Fila de impressão '{}' não suportada neste sistema", name)),
+        PrinterTarget::Usb => Ok(Box::new(UsbPrinter::new()?)),
+        PrinterTarget::Tcp(address) => Ok(Box::new(TcpTransport::connect(
+            address,
+            Duration::from_millis(config.connect_timeout_ms as u64),
+            Duration::from_millis(config.write_timeout_ms as u64),
+        )?)),
+        #[cfg(target_os = "linux")]
+        PrinterTarget::UsbLp(path) => Ok(Box::new(UsbLpTransport::open(path)?)),
+        #[cfg(not(target_os = "linux"))]
+        PrinterTarget::UsbLp(path) => Err(format!("Dispositivo '{}' só é suportado no Linux", path.display())),
+        PrinterTarget::Serial(port) => Ok(Box::new(SerialTransport::open(port, config)?)),
+        PrinterTarget::File(path) => Ok(Box::new(FileTransport::new(path.clone()))),
+        PrinterTarget::Virtual => Ok(Box::new(VirtualFilePrinter::new(config, job)?)),
+    }
+}
+
+// Lista as filas de impressão do sistema (spooler do Windows ou CUPS)
+pub fn list_system_printers(silent: bool) -> Result<Vec<String>, String> {
+    #[cfg(windows)]
+    {
+        if silent {
+            crate::windows_printing::list_windows_printers_silent()
+        } else {
+            crate::windows_printing::list_windows_printers()
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        // O lpstat não abre nenhuma interface, então não há modo silencioso
+        let _ = silent;
+        crate::cups_printing::list_cups_printers()
+    }
+
+    #[cfg(not(any(windows, unix)))]
+    {
+        let _ = silent;
+        Ok(Vec::new())
+    }
+}
+
+// Lista as impressoras ligadas diretamente, sem spooler (ex.: /dev/usb/lpN no Linux)
+pub fn list_direct_printers() -> Vec<String> {
+    #[cfg(target_os = "linux")]
+    {
+        crate::usb_lp_printing::list_usb_lp_devices()
+            .iter()
+            .map(|device| device.display_name())
+            .collect()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Vec::new()
+    }
+}
+
+// Envia um trabalho completo e fecha a conexão, mesmo em caso de erro no envio
+pub fn send_job(transport: &mut dyn PrinterTransport, data: &[u8]) -> Result<(), String> {
+    println!("Enviando {} bytes para {}", data.len(), transport.describe());
+
+    let result = transport.send(data);
+    let closed = transport.close();
+
+    result?;
+    closed
+}
+
+// Abre o destino, envia o trabalho e fecha a conexão
+pub fn print_to_target(
+    target: &PrinterTarget,
+    config: &PrinterConfig,
+    job: &PrintJobInfo,
+    data: &[u8],
+) -> Result<(), String> {
+    let _printer = lock_printer();
+    let mut transport = open_transport(target, config, job)?;
+    send_job(transport.as_mut(), data)
+}
+
+// Transporte em memória: guarda tudo o que foi enviado e devolve respostas pré-definidas.
+// Permite exercitar o caminho de impressão inteiro sem impressora.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MemoryTransport {
+    sent: Arc<Mutex<Vec<u8>>>,
+    responses: Arc<Mutex<Vec<u8>>>,
+}
+
+#[cfg(test)]
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Define os bytes que a "impressora" devolverá nas próximas leituras
+    pub fn with_response(self, response: &[u8]) -> Self {
+        self.responses.lock().unwrap().extend_from_slice(response);
+        self
+    }
+
+    // Cópia de todos os bytes enviados até agora
+    pub fn sent(&self) -> Vec<u8> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl PrinterTransport for MemoryTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        self.sent.lock().unwrap().extend_from_slice(data);
+        Ok(())
+    }
+
+    fn read(&mut self, buffer: &mut [u8], _timeout: Duration) -> Result<usize, String> {
+        let mut responses = self.responses.lock().unwrap();
+        let count = buffer.len().min(responses.len());
+        buffer[..count].copy_from_slice(&responses[..count]);
+        responses.drain(..count);
+        Ok(count)
+    }
+
+    fn supports_read(&self) -> bool {
+        true
+    }
+
+    fn describe(&self) -> String {
+        "impressora em memória".to_string()
+    }
+}
+
+// Transporte em arquivo: acrescenta os bytes ao final do arquivo indicado
+pub struct FileTransport {
+    path: PathBuf,
+}
+
+impl FileTransport {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl PrinterTransport for FileTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Erro ao abrir arquivo '{}': {}", self.path.display(), e))?;
+
+        file.write_all(data)
+            .map_err(|e| format!("Erro ao gravar arquivo '{}': {}", self.path.display(), e))
+    }
+
+    fn describe(&self) -> String {
+        format!("arquivo '{}'", self.path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Transporte que falha no envio, para conferir que a conexão é fechada mesmo assim
+    struct FailingTransport {
+        closed: bool,
+    }
+
+    impl PrinterTransport for FailingTransport {
+        fn send(&mut self, _data: &[u8]) -> Result<(), String> {
+            Err("falha simulada".to_string())
+        }
+
+        fn describe(&self) -> String {
+            "impressora com defeito".to_string()
+        }
+
+        fn close(&mut self) -> Result<(), String> {
+            self.closed = true;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn send_job_entrega_os_bytes_ao_transporte() {
+        let memory = MemoryTransport::new();
+        let mut transport = memory.clone();
+
+        send_job(&mut transport, b"N\r\nP1\r\n").unwrap();
+        send_job(&mut transport, b"N\r\nP2\r\n").unwrap();
+
+        assert_eq!(memory.sent(), b"N\r\nP1\r\nN\r\nP2\r\n");
+    }
+
+    #[test]
+    fn send_job_fecha_a_conexao_quando_o_envio_falha() {
+        let mut transport = FailingTransport { closed: false };
+
+        assert_eq!(send_job(&mut transport, b"N\r\n"), Err("falha simulada".to_string()));
+        assert!(transport.closed);
+    }
+
+    #[test]
+    fn memory_transport_devolve_as_respostas_em_partes() {
+        let mut transport = MemoryTransport::new().with_response(b"ABCDE");
+        let mut buffer = [0u8; 3];
+
+        assert_eq!(transport.read(&mut buffer, Duration::from_millis(10)), Ok(3));
+        assert_eq!(&buffer, b"ABC");
+        assert_eq!(transport.read(&mut buffer, Duration::from_millis(10)), Ok(2));
+        assert_eq!(&buffer[..2], b"DE");
+        assert_eq!(transport.read(&mut buffer, Duration::from_millis(10)), Ok(0));
+    }
+
+    #[test]
+    fn print_to_target_grava_o_trabalho_em_arquivo() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("saida.prn");
+        let target = PrinterTarget::parse(&format!("file://{}", path.display()));
+        let config = PrinterConfig::default();
+        let job = PrintJobInfo::new("Etiquetas");
+
+        assert_eq!(target, PrinterTarget::File(path.clone()));
+        print_to_target(&target, &config, &job, b"N\r\nP1\r\n").unwrap();
+        print_to_target(&target, &config, &job, b"N\r\nP1\r\n").unwrap();
+
+        // Cada trabalho é acrescentado ao final do arquivo
+        assert_eq!(std::fs::read(&path).unwrap(), b"N\r\nP1\r\nN\r\nP1\r\n");
+    }
+
+    #[test]
+    fn print_to_target_informa_erro_do_arquivo() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = PrinterTarget::File(dir.path().join("pasta-inexistente").join("saida.prn"));
+
+        let result = print_to_target(&target, &PrinterConfig::default(), &PrintJobInfo::new("Etiquetas"), b"N\r\n");
+
+        assert!(result.unwrap_err().starts_with("Erro ao abrir arquivo"));
+    }
+
+    #[test]
+    fn parse_reconhece_cada_destino() {
+        assert_eq!(PrinterTarget::parse("usb"), PrinterTarget::Usb);
+        assert_eq!(PrinterTarget::parse(VIRTUAL_PRINTER_NAME), PrinterTarget::Virtual);
+        assert_eq!(PrinterTarget::parse("tcp://10.0.0.5"), PrinterTarget::Tcp("10.0.0.5:9100".to_string()));
+        assert_eq!(PrinterTarget::parse("/dev/usb/lp0"), PrinterTarget::UsbLp(PathBuf::from("/dev/usb/lp0")));
+        assert_eq!(
+            PrinterTarget::parse("Argox OS-2140 (/dev/usb/lp1)"),
+            PrinterTarget::UsbLp(PathBuf::from("/dev/usb/lp1"))
+        );
+        assert_eq!(PrinterTarget::parse("COM3"), PrinterTarget::Serial("COM3".to_string()));
+        assert_eq!(PrinterTarget::parse("Argox_OS-2140"), PrinterTarget::Spooler("Argox_OS-2140".to_string()));
+    }
+
+    #[test]
+    fn display_name_volta_ao_mesmo_destino() {
+        for name in ["usb", "tcp://10.0.0.5:9100", "/dev/usb/lp0", "COM1", "file:///tmp/x.prn", "Argox"] {
+            let target = PrinterTarget::parse(name);
+            assert_eq!(PrinterTarget::parse(&target.display_name()), target);
+        }
+    }
+}
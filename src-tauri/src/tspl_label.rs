@@ -0,0 +1,213 @@
+use crate::barcode::{self, Symbology};
+use crate::code_page::CodePage;
+use crate::label_escape;
+use crate::label_metrics::{FontMetrics, TSPL_FONTS};
+use crate::label_render::LabelWriter;
+use crate::label_template::{LabelTemplate, TemplateElement};
+use crate::media_profile::{Media, SensorType};
+use crate::resolution::Resolution;
+use crate::PrinterConfig;
+
+// Dots -> milímetros na resolução da impressora, no formato aceito pelo SIZE e pelo GAP
+fn mm(resolution: Resolution, dots: u32) -> String {
+    format!("{:.1} mm", resolution.mm(dots))
+}
+
+// Velocidade 1-4 do PPLA -> polegadas por segundo do TSPL (e do ZPL)
+pub fn speed_ips(speed: u8) -> u8 {
+    speed.clamp(1, 4) + 1
+}
+
+// Tipo do código no comando BARCODE; QR e DataMatrix têm comandos próprios
+fn barcode_type(symbology: Symbology) -> &'static str {
+    match symbology {
+        Symbology::Ean13 => "EAN13",
+        Symbology::Ean8 => "EAN8",
+        Symbology::UpcA => "UPCA",
+        Symbology::Code128 => "128",
+        Symbology::Itf14 => "ITF14",
+        Symbology::Qr => "QRCODE",
+        Symbology::DataMatrix => "DMATRIX",
+    }
+}
+
+// Gerador TSPL (TSC, Elgin L42 e compatíveis)
+pub struct TsplWriter;
+
+impl LabelWriter for TsplWriter {
+    fn begin(&self, out: &mut Vec<u8>, media: &Media, config: &PrinterConfig) -> Result<(), String> {
+        out.extend_from_slice(format!("SIZE {}, {}\r\n", mm(media.resolution, media.width), mm(media.resolution, media.height)).as_bytes()); // Largura da fita e altura da etiqueta
+        let sensor = match media.sensor {
+            SensorType::Gap => format!("GAP {}, 0 mm\r\n", mm(media.resolution, media.gap)), // Espaço entre etiquetas
+            SensorType::BlackMark => format!("BLINE {}, 0 mm\r\n", mm(media.resolution, media.gap)), // Altura da marca preta
+            SensorType::Continuous => "GAP 0 mm, 0 mm\r\n".to_string(), // Papel contínuo
+        };
+        out.extend_from_slice(sensor.as_bytes());
+        out.extend_from_slice(format!("DENSITY {}\r\n", config.darkness).as_bytes()); // Densidade (0-15)
+        out.extend_from_slice(format!("SPEED {}\r\n", speed_ips(config.speed)).as_bytes()); // Velocidade
+        out.extend_from_slice(b"DIRECTION 0\r\n"); // Direção normal
+        out.extend_from_slice(b"REFERENCE 0,0\r\n"); // Origem no canto da fita
+        out.extend_from_slice(b"CLS\r\n"); // Limpa a imagem anterior
+        Ok(())
+    }
+
+    fn select_code_page(&self, out: &mut Vec<u8>, page: CodePage) -> Result<(), String> {
+        out.extend_from_slice(format!("CODEPAGE {}\r\n", page.tspl_name()).as_bytes());
+        Ok(())
+    }
+
+    fn element(&self, out: &mut Vec<u8>, element: &TemplateElement<u32>) -> Result<(), String> {
+        let command = match element {
+            TemplateElement::Text { x, y, rotation, font, h_mult, v_mult, reverse, content, .. } => {
+                let mut command = format!(
+                    "TEXT {},{},\"{}\",{},{},{},{}\r\n",
+                    x,
+                    y,
+                    font,
+                    rotation,
+                    h_mult,
+                    v_mult,
+                    label_escape::tspl_quoted(content)
+                );
+
+                // Inverte a área ocupada pelo texto (somente texto sem rotação)
+                if *reverse && *rotation == 0 {
+                    let (width, height) = TSPL_FONTS.text_size(font, *h_mult, *v_mult, content)?;
+                    command.push_str(&format!("REVERSE {},{},{},{}\r\n", x, y, width, height));
+                }
+
+                command
+            }
+            TemplateElement::Barcode { x, y, rotation, symbology, narrow, wide, height, human_readable, content, .. } => {
+                let symbology = Symbology::parse(symbology)?;
+                let data = label_escape::barcode_data(symbology, content)?;
+
+                match symbology {
+                    // Correção M, módulo do tamanho da barra fina, entrada automática
+                    Symbology::Qr => format!(
+                        "QRCODE {},{},M,{},A,{},{}\r\n",
+                        x,
+                        y,
+                        narrow,
+                        rotation,
+                        label_escape::tspl_quoted(&data)
+                    ),
+                    // A área do DMATRIX é o quadrado ocupado pelo símbolo
+                    Symbology::DataMatrix => {
+                        let side = barcode::side_2d(symbology, &data).unwrap_or(144) * narrow;
+                        format!(
+                            "DMATRIX {},{},{},{},x{},{}\r\n",
+                            x,
+                            y,
+                            side,
+                            side,
+                            narrow,
+                            label_escape::tspl_quoted(&data)
+                        )
+                    }
+                    _ => format!(
+                        "BARCODE {},{},\"{}\",{},{},{},{},{},{}\r\n",
+                        x,
+                        y,
+                        barcode_type(symbology),
+                        height,
+                        if *human_readable { 1 } else { 0 },
+                        rotation,
+                        narrow,
+                        wide,
+                        label_escape::tspl_quoted(&data)
+                    ),
+                }
+            }
+            TemplateElement::Line { x, y, width, height } => format!("BAR {},{},{},{}\r\n", x, y, width, height),
+            TemplateElement::Box { x, y, width, height, thickness } => {
+                format!("BOX {},{},{},{},{}\r\n", x, y, x + width, y + height, thickness)
+            }
+            TemplateElement::Image { x, y, name } => {
+                format!("PUTBMP {},{},\"{}\"\r\n", x, y, label_escape::graphic_name(name)?)
+            }
+        };
+
+        out.extend_from_slice(command.as_bytes());
+        Ok(())
+    }
+
+    fn end(&self, out: &mut Vec<u8>, _template: &LabelTemplate<u32>, copies: u32) -> Result<(), String> {
+        out.extend_from_slice(format!("PRINT 1,{}\r\n", copies).as_bytes());
+        Ok(())
+    }
+
+    fn fonts(&self) -> &'static FontMetrics {
+        &TSPL_FONTS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::label_render::LabelLanguage;
+    use crate::test_support;
+
+    // Saída de referência do modelo padrão: qualquer diferença muda o que a impressora recebe
+    const DEFAULT_TEMPLATE_GOLDEN: &[u8] = include_bytes!("fixtures/default_template.tspl");
+
+    fn header(width: u32, height: u32, gap: u32, sensor: SensorType, dpi: u32) -> String {
+        let media = Media {
+            width,
+            height,
+            gap,
+            sensor,
+            top: 0,
+            resolution: Resolution::new(dpi).unwrap(),
+        };
+        let mut out = Vec::new();
+        TsplWriter.begin(&mut out, &media, &PrinterConfig::default()).unwrap();
+        String::from_utf8(out).unwrap().lines().take(2).collect::<Vec<_>>().join("|")
+    }
+
+    #[test]
+    fn modelo_padrao_gera_a_saida_de_referencia() {
+        let batch = test_support::default_template_batch(LabelLanguage::Tspl);
+
+        test_support::assert_golden(&batch.data, DEFAULT_TEMPLATE_GOLDEN);
+    }
+
+    #[test]
+    fn tamanho_e_gap_em_milimetros_na_resolucao_da_impressora() {
+        // 203 dpi: 8 dots por mm; 300 dpi: 12
+        assert_eq!(header(840, 176, 24, SensorType::Gap, 203), "SIZE 105.0 mm, 22.0 mm|GAP 3.0 mm, 0 mm");
+        assert_eq!(header(1260, 264, 36, SensorType::Gap, 300), "SIZE 105.0 mm, 22.0 mm|GAP 3.0 mm, 0 mm");
+        assert_eq!(header(400, 244, 20, SensorType::BlackMark, 203), "SIZE 50.0 mm, 30.5 mm|BLINE 2.5 mm, 0 mm");
+        assert_eq!(header(600, 360, 0, SensorType::Continuous, 300), "SIZE 50.0 mm, 30.0 mm|GAP 0 mm, 0 mm");
+    }
+
+    #[test]
+    fn aspas_usam_o_escape_do_tspl() {
+        assert_eq!(label_escape::tspl_quoted("ARR-001"), "\"ARR-001\"");
+        assert_eq!(label_escape::tspl_quoted("Porca 1/4\" \\ M6"), "\"Porca 1/4\\[\"] \\ M6\"");
+        // Quebras de linha não podem encerrar o comando
+        assert_eq!(label_escape::tspl_quoted("A\r\nPRINT 1"), "\"A  PRINT 1\"");
+    }
+
+    #[test]
+    fn texto_invertido_usa_a_celula_da_fonte_do_tspl() {
+        let element = TemplateElement::Text {
+            x: 10,
+            y: 20,
+            rotation: 0,
+            font: "3".to_string(),
+            h_mult: 2,
+            v_mult: 1,
+            reverse: true,
+            align: Default::default(),
+            valign: Default::default(),
+            fit: None,
+            content: "ABC".to_string(),
+        };
+        let mut out = Vec::new();
+        TsplWriter.element(&mut out, &element).unwrap();
+
+        // Fonte 3: 16 x 24 dots por caractere
+        assert_eq!(out, b"TEXT 10,20,\"3\",0,2,1,\"ABC\"\r\nREVERSE 10,20,96,24\r\n");
+    }
+}
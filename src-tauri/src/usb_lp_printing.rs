@@ -0,0 +1,226 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::transport::PrinterTransport;
+
+// Diretório dos dispositivos da classe impressora USB (driver usblp)
+const USB_LP_DIR: &str = "/dev/usb";
+
+// Dispositivo /dev/usb/lpN encontrado na máquina
+#[derive(Debug, Clone)]
+pub struct UsbLpDevice {
+    pub path: PathBuf,
+    pub device_id: Option<String>, // IEEE-1284 device ID, ex.: "MFG:Argox;MDL:OS-2140;CMD:PPLA;"
+}
+
+impl UsbLpDevice {
+    // Nome exibido na lista de impressoras, ex.: "Argox OS-2140 (/dev/usb/lp0)"
+    pub fn display_name(&self) -> String {
+        match self.device_id.as_deref().and_then(model_from_device_id) {
+            Some(model) => format!("{} ({})", model, self.path.display()),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+// Extrai um campo do device ID IEEE-1284 (as chaves têm forma longa e abreviada)
+pub fn device_id_field(device_id: &str, keys: &[&str]) -> Option<String> {
+    device_id
+        .split(';')
+        .filter_map(|pair| pair.split_once(':'))
+        .find(|(key, _)| keys.iter().any(|k| key.trim().eq_ignore_ascii_case(k)))
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// Monta "Fabricante Modelo" a partir do device ID
+pub fn model_from_device_id(device_id: &str) -> Option<String> {
+    let manufacturer = device_id_field(device_id, &["MFG", "MANUFACTURER"]);
+    let model = device_id_field(device_id, &["MDL", "MODEL"]);
+
+    match (manufacturer, model) {
+        // Alguns modelos já repetem o fabricante no nome do modelo
+        (Some(mfg), Some(mdl)) if mdl.to_lowercase().starts_with(&mfg.to_lowercase()) => Some(mdl),
+        (Some(mfg), Some(mdl)) => Some(format!("{} {}", mfg, mdl)),
+        (None, Some(mdl)) => Some(mdl),
+        (Some(mfg), None) => Some(mfg),
+        (None, None) => None,
+    }
+}
+
+// Lê o device ID exposto pelo usblp no sysfs
+fn read_device_id(node_name: &str) -> Option<String> {
+    [
+        format!("/sys/class/usbmisc/{}/device/ieee1284_id", node_name),
+        format!("/sys/class/usb/{}/device/ieee1284_id", node_name),
+    ]
+    .iter()
+    .find_map(|path| fs::read_to_string(path).ok())
+    .map(|id| id.trim().to_string())
+    .filter(|id| !id.is_empty())
+}
+
+// Enumera os dispositivos /dev/usb/lpN
+pub fn list_usb_lp_devices() -> Vec<UsbLpDevice> {
+    let entries = match fs::read_dir(USB_LP_DIR) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut devices: Vec<UsbLpDevice> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_lp = name
+                .strip_prefix("lp")
+                .map(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+                .unwrap_or(false);
+
+            if !is_lp {
+                return None;
+            }
+
+            Some(UsbLpDevice {
+                path: entry.path(),
+                device_id: read_device_id(&name),
+            })
+        })
+        .collect();
+
+    devices.sort_by(|a, b| a.path.cmp(&b.path));
+    devices
+}
+
+// Transporte direto para o dispositivo de caractere, sem spooler.
+// Um FIFO ou arquivo comum também pode ser usado no lugar do dispositivo.
+pub struct UsbLpTransport {
+    path: PathBuf,
+    file: File,
+}
+
+impl UsbLpTransport {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        // Leitura e escrita: o usblp devolve o status da impressora na leitura
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(path)
+            .map_err(|e| format!("Erro ao abrir dispositivo '{}': {}", path.display(), e))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+        })
+    }
+
+    // Espera até haver dados para leitura ou o tempo limite acabar
+    fn wait_readable(&self, timeout: Duration) -> Result<bool, String> {
+        let mut poll_fd = libc::pollfd {
+            fd: self.file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
+
+        // SAFETY: poll_fd aponta para um descritor válido durante toda a chamada
+        let result = unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) };
+        if result < 0 {
+            return Err(format!(
+                "Erro ao aguardar resposta de '{}': {}",
+                self.path.display(),
+                std::io::Error::last_os_error()
+            ));
+        }
+
+        Ok(result > 0 && poll_fd.revents & libc::POLLIN != 0)
+    }
+}
+
+impl PrinterTransport for UsbLpTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        self.file
+            .write_all(data)
+            .and_then(|_| self.file.flush())
+            .map_err(|e| format!("Erro ao enviar dados para '{}': {}", self.path.display(), e))
+    }
+
+    fn read(&mut self, buffer: &mut [u8], timeout: Duration) -> Result<usize, String> {
+        if !self.wait_readable(timeout)? {
+            return Ok(0);
+        }
+
+        self.file
+            .read(buffer)
+            .map_err(|e| format!("Erro ao ler resposta de '{}': {}", self.path.display(), e))
+    }
+
+    fn supports_read(&self) -> bool {
+        true
+    }
+
+    fn describe(&self) -> String {
+        format!("dispositivo USB '{}'", self.path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    #[test]
+    fn grava_o_trabalho_em_arquivo_comum() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lp0");
+        fs::write(&path, b"").unwrap();
+
+        let mut transport = UsbLpTransport::open(&path).unwrap();
+        crate::transport::send_job(&mut transport, b"N\r\nP1\r\n").unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"N\r\nP1\r\n");
+    }
+
+    #[test]
+    fn envia_e_le_por_um_fifo() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lp0");
+        let c_path = CString::new(path.as_os_str().as_bytes()).unwrap();
+        // SAFETY: c_path é um caminho válido terminado em zero
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        // Aberto para leitura e escrita, o FIFO não bloqueia esperando o outro lado
+        let mut transport = UsbLpTransport::open(&path).unwrap();
+        let mut buffer = [0u8; 16];
+        assert_eq!(transport.read(&mut buffer, Duration::from_millis(50)), Ok(0));
+
+        let mut printer = File::open(&path).unwrap();
+        transport.send(b"N\r\nP1\r\n").unwrap();
+        let mut received = [0u8; 7];
+        printer.read_exact(&mut received).unwrap();
+        assert_eq!(&received, b"N\r\nP1\r\n");
+    }
+
+    #[test]
+    fn dispositivo_inexistente_gera_erro() {
+        let error = UsbLpTransport::open(Path::new("/dev/usb/lp-inexistente")).err().unwrap();
+
+        assert!(error.starts_with("Erro ao abrir dispositivo '/dev/usb/lp-inexistente'"), "{}", error);
+    }
+
+    #[test]
+    fn nome_do_modelo_vem_do_device_id() {
+        assert_eq!(
+            model_from_device_id("MFG:Argox;MDL:OS-2140;CMD:PPLA;").as_deref(),
+            Some("Argox OS-2140")
+        );
+        assert_eq!(
+            model_from_device_id("MANUFACTURER:Zebra;MODEL:Zebra ZD220;").as_deref(),
+            Some("Zebra ZD220")
+        );
+        assert_eq!(model_from_device_id("CMD:PPLA;"), None);
+    }
+}
@@ -9,6 +9,8 @@ use windows_sys::Win32::Graphics::Printing::{
     PRINTER_ENUM_LOCAL, PRINTER_ENUM_CONNECTIONS,
 };
 
+use crate::transport::PrinterTransport;
+
 // Definição da estrutura PRINTER_INFO_2W
 #[repr(C)]
 struct PRINTER_INFO_2W {
@@ -145,6 +147,31 @@ pub fn print_to_windows_printer(
     }
 }
 
+// Transporte pelo spooler do Windows: cada envio vira um documento RAW na fila
+pub struct WindowsSpoolerTransport {
+    printer_name: String,
+    document_name: String,
+}
+
+impl WindowsSpoolerTransport {
+    pub fn new(printer_name: &str, document_name: &str) -> Self {
+        Self {
+            printer_name: printer_name.to_string(),
+            document_name: document_name.to_string(),
+        }
+    }
+}
+
+impl PrinterTransport for WindowsSpoolerTransport {
+    fn send(&mut self, data: &[u8]) -> Result<(), String> {
+        print_to_windows_printer(&self.printer_name, &self.document_name, data)
+    }
+
+    fn describe(&self) -> String {
+        format!("impressora Windows '{}'", self.printer_name)
+    }
+}
+
 // Função alternativa usando o comando de sistema
 pub fn print_using_system_command(
     printer_name: &str,
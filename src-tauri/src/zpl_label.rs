@@ -0,0 +1,223 @@
+use crate::barcode::{self, Symbology};
+use crate::label_render::LabelWriter;
+use crate::code_page::CodePage;
+use crate::label_escape;
+use crate::label_metrics::{self, CHAR_SPACING};
+use crate::label_template::{LabelTemplate, TemplateElement};
+use crate::media_profile::{Media, SensorType};
+use crate::tspl_label::speed_ips;
+use crate::PrinterConfig;
+
+// Rotação em graus -> orientação do ZPL
+fn orientation(rotation: u16) -> char {
+    match rotation {
+        90 => 'R',
+        180 => 'I',
+        270 => 'B',
+        _ => 'N',
+    }
+}
+
+// Tamanho (largura, altura) em dots do caractere das fontes 1 a 5 do PPLA, para manter o mesmo
+// tamanho de texto (e o mesmo alinhamento) com a fonte escalável ^A0 da Zebra
+fn font_size(font: &str) -> Result<(u32, u32), String> {
+    let (width, height) = label_metrics::font_cell(font).ok_or_else(|| format!("Fonte inválida: {}", font))?;
+    Ok((width - CHAR_SPACING, height))
+}
+
+// Gerador ZPL II (Zebra)
+pub struct ZplWriter;
+
+impl LabelWriter for ZplWriter {
+    fn begin(&self, out: &mut Vec<u8>, media: &Media, config: &PrinterConfig) -> Result<(), String> {
+        out.extend_from_slice(b"^XA\n"); // Início do formato
+        out.extend_from_slice(b"^LH0,0\n"); // Origem no canto da etiqueta
+        out.extend_from_slice(format!("^PW{}\n", media.width).as_bytes()); // Largura da fita
+        out.extend_from_slice(format!("^LL{}\n", media.height).as_bytes()); // Altura da etiqueta
+        let tracking = match media.sensor {
+            SensorType::Gap => 'Y',
+            SensorType::BlackMark => 'M',
+            SensorType::Continuous => 'N',
+        };
+        out.extend_from_slice(format!("^MN{}\n", tracking).as_bytes()); // Sensor: gap, marca preta ou contínuo
+        out.extend_from_slice(format!("~SD{:02}\n", config.darkness as u32 * 2).as_bytes()); // Densidade (0-30 no ZPL)
+        out.extend_from_slice(format!("^PR{}\n", speed_ips(config.speed)).as_bytes()); // Velocidade em polegadas por segundo
+        Ok(())
+    }
+
+    fn select_code_page(&self, out: &mut Vec<u8>, page: CodePage) -> Result<(), String> {
+        out.extend_from_slice(format!("^CI{}\n", page.zpl_ci()).as_bytes()); // Conjunto de caracteres
+        Ok(())
+    }
+
+    fn element(&self, out: &mut Vec<u8>, element: &TemplateElement<u32>) -> Result<(), String> {
+        let command = match element {
+            TemplateElement::Text { x, y, rotation, font, h_mult, v_mult, reverse, content, .. } => {
+                let (width, height) = font_size(font)?;
+                format!(
+                    "^FO{},{}^A0{},{},{}{}{}\n",
+                    x,
+                    y,
+                    orientation(*rotation),
+                    height * v_mult,
+                    width * h_mult,
+                    if *reverse { "^FR" } else { "" },
+                    label_escape::zpl_field(content)
+                )
+            }
+            TemplateElement::Barcode { x, y, rotation, symbology, narrow, wide, height, human_readable, content, .. } => {
+                let symbology = Symbology::parse(symbology)?;
+                let data = label_escape::barcode_data(symbology, content)?;
+                let o = orientation(*rotation);
+                let hr = if *human_readable { "Y" } else { "N" };
+
+                let (command, data) = match symbology {
+                    Symbology::Ean13 => (format!("^BE{},{},{},N", o, height, hr), data),
+                    Symbology::Ean8 => (format!("^B8{},{},{},N", o, height, hr), data),
+                    Symbology::UpcA => (format!("^BU{},{},{},N,Y", o, height, hr), data),
+                    // ">" inicia os códigos de controle do ^BC; ">>" não existe, então o próprio ">" vai como "><"
+                    Symbology::Code128 => (format!("^BC{},{},{},N,N", o, height, hr), data.replace('>', "><")),
+                    Symbology::Itf14 => (format!("^B2{},{},{},N,N", o, height, hr), data),
+                    // Modelo 2, correção M; os dados levam o prefixo de correção e modo ("MA,")
+                    Symbology::Qr => (format!("^BQN,2,{}", narrow.min(&10)), format!("MA,{}", data)),
+                    Symbology::DataMatrix => (format!("^BX{},{},200", o, narrow), data),
+                };
+
+                format!(
+                    "^FO{},{}^BY{},{}.0{}{}\n",
+                    x,
+                    y,
+                    narrow,
+                    barcode::wide_ratio(*narrow, *wide),
+                    command,
+                    label_escape::zpl_field(&data)
+                )
+            }
+            TemplateElement::Line { x, y, width, height } => {
+                format!("^FO{},{}^GB{},{},{}^FS\n", x, y, width, height, width.min(height))
+            }
+            TemplateElement::Box { x, y, width, height, thickness } => {
+                format!("^FO{},{}^GB{},{},{}^FS\n", x, y, width, height, thickness)
+            }
+            TemplateElement::Image { x, y, name } => {
+                format!("^FO{},{}^XG{},1,1^FS\n", x, y, label_escape::graphic_name(name)?)
+            }
+        };
+
+        out.extend_from_slice(command.as_bytes());
+        Ok(())
+    }
+
+    fn end(&self, out: &mut Vec<u8>, _template: &LabelTemplate<u32>, copies: u32) -> Result<(), String> {
+        out.extend_from_slice(format!("^PQ{}\n", copies).as_bytes()); // Quantidade de cópias
+        out.extend_from_slice(b"^XZ\n"); // Fim do formato
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::label_render::LabelLanguage;
+    use crate::label_template::{HorizontalAlign, VerticalAlign};
+    use crate::resolution::Resolution;
+    use crate::test_support;
+
+    // Saída de referência do modelo padrão: qualquer diferença muda o que a impressora recebe
+    const DEFAULT_TEMPLATE_GOLDEN: &[u8] = include_bytes!("fixtures/default_template.zpl");
+
+    fn text(content: &str) -> TemplateElement<u32> {
+        TemplateElement::Text {
+            x: 10,
+            y: 20,
+            rotation: 0,
+            font: "2".to_string(),
+            h_mult: 1,
+            v_mult: 1,
+            reverse: false,
+            align: HorizontalAlign::Left,
+            valign: VerticalAlign::Top,
+            fit: None,
+            content: content.to_string(),
+        }
+    }
+
+    fn code128(content: &str) -> TemplateElement<u32> {
+        TemplateElement::Barcode {
+            x: 10,
+            y: 20,
+            rotation: 0,
+            symbology: "code128".to_string(),
+            narrow: 2,
+            wide: 6,
+            height: 45,
+            human_readable: true,
+            align: HorizontalAlign::Left,
+            valign: VerticalAlign::Top,
+            content: content.to_string(),
+        }
+    }
+
+    fn element(element: &TemplateElement<u32>) -> String {
+        let mut out = Vec::new();
+        ZplWriter.element(&mut out, element).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn modelo_padrao_gera_a_saida_de_referencia() {
+        let batch = test_support::default_template_batch(LabelLanguage::Zpl);
+
+        test_support::assert_golden(&batch.data, DEFAULT_TEMPLATE_GOLDEN);
+    }
+
+    #[test]
+    fn circunflexo_til_e_sublinhado_vao_em_hexadecimal() {
+        assert_eq!(element(&text("Porca M6")), "^FO10,20^A0N,16,10^FDPorca M6^FS\n");
+        assert_eq!(element(&text("A^B~C_D")), "^FO10,20^A0N,16,10^FH_^FDA_5EB_7EC_5FD^FS\n");
+    }
+
+    #[test]
+    fn maior_que_no_code128_vai_como_codigo_de_controle() {
+        assert_eq!(element(&code128("A>B")), "^FO10,20^BY2,3.0^BCN,45,Y,N,N^FDA><B^FS\n");
+        assert_eq!(element(&code128("A^B")), "^FO10,20^BY2,3.0^BCN,45,Y,N,N^FH_^FDA_5EB^FS\n");
+    }
+
+    #[test]
+    fn densidade_velocidade_e_copias() {
+        let media = Media {
+            width: 400,
+            height: 240,
+            gap: 24,
+            sensor: SensorType::BlackMark,
+            top: 0,
+            resolution: Resolution::default(),
+        };
+        let header = |darkness, speed| {
+            let mut out = Vec::new();
+            ZplWriter.begin(&mut out, &media, &PrinterConfig { darkness, speed, ..PrinterConfig::default() }).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(header(8, 2), "^XA\n^LH0,0\n^PW400\n^LL240\n^MNM\n~SD16\n^PR3\n");
+        assert!(header(1, 1).ends_with("~SD02\n^PR2\n"));
+        assert!(header(15, 9).ends_with("~SD30\n^PR5\n"));
+
+        let mut out = Vec::new();
+        ZplWriter.end(&mut out, &LabelTemplate::default_template().in_dots(Resolution::default(), &mut Vec::new()), 12).unwrap();
+        assert_eq!(out, b"^PQ12\n^XZ\n");
+    }
+
+    #[test]
+    fn conjunto_de_caracteres_segue_a_pagina_de_codigo() {
+        let ci = |page| {
+            let mut out = Vec::new();
+            ZplWriter.select_code_page(&mut out, page).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(ci(CodePage::Cp850), "^CI13\n");
+        assert_eq!(ci(CodePage::Cp1252), "^CI27\n");
+        assert_eq!(ci(CodePage::Iso8859_1), "^CI27\n");
+    }
+}
@@ -9,10 +9,36 @@ import { Input } from "@/components/ui/input"
 import { Label } from "@/components/ui/label"
 import { Printer, RefreshCcw, Settings, AlertCircle, Save } from "lucide-react"
 import { getVersion } from "@tauri-apps/api/app"
+import { invoke } from "@tauri-apps/api/tauri"
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select"
 import { usePrinter } from "@/contexts/printer-context"
 import { toast } from "sonner"
 
+// Perfil de mídia (rolo de etiquetas), medidas em milímetros
+interface MediaProfile {
+  id: number
+  name: string
+  description?: string | null
+  liner_width: number
+  label_width: number
+  label_height: number
+  columns: number
+  horizontal_gap: number
+  vertical_gap: number
+  left_margin: number
+  top_margin: number
+  sensor: "gap" | "black_mark" | "continuous"
+}
+
+// Valor do Select para "sem perfil" (o Select não aceita valor vazio)
+const NO_MEDIA_PROFILE = "none"
+
+const SENSOR_LABELS: Record<MediaProfile["sensor"], string> = {
+  gap: "gap entre etiquetas",
+  black_mark: "marca preta",
+  continuous: "papel contínuo",
+}
+
 export default function ConfiguracaoPage() {
   const {
     printers,
@@ -29,6 +55,18 @@ export default function ConfiguracaoPage() {
   const [version, setVersion] = useState<string>("")
   const [debugInfo, setDebugInfo] = useState<string>("")
   const [localSelectedPrinter, setLocalSelectedPrinter] = useState<string>("")
+  const [mediaProfiles, setMediaProfiles] = useState<MediaProfile[]>([])
+
+  useEffect(() => {
+    invoke<MediaProfile[]>("list_media_profiles")
+      .then(setMediaProfiles)
+      .catch((error) => console.error("Erro ao carregar perfis de mídia:", error))
+  }, [])
+
+  const selectedProfile = mediaProfiles.find((profile) => profile.id === config.media_profile_id)
+  // 203 dpi = 8 dots por mm, 300 dpi = 12 dots por mm; a cabeça de impressão tem 108mm
+  const dotsPerMm = config.dpi === 300 ? 12 : 8
+  const printheadWidth = 108 * dotsPerMm
 
   useEffect(() => {
     const loadVersion = async () => {
@@ -236,6 +274,52 @@ export default function ConfiguracaoPage() {
                 <p className="text-sm text-muted-foreground">Velocidade de impressão (1-4)</p>
               </div>
 
+              <div className="space-y-2">
+                <Label>Resolução</Label>
+                <Select
+                  value={String(config.dpi ?? 203)}
+                  onValueChange={(value) => updateConfig({ dpi: Number(value) as 203 | 300 })}
+                >
+                  <SelectTrigger className="w-full">
+                    <SelectValue />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value="203">203 dpi (8 dots/mm)</SelectItem>
+                    <SelectItem value="300">300 dpi (12 dots/mm)</SelectItem>
+                  </SelectContent>
+                </Select>
+                <p className="text-sm text-muted-foreground">
+                  Modelos e perfis de mídia são convertidos para a resolução da impressora e saem no mesmo tamanho.
+                </p>
+              </div>
+
+              <div className="space-y-2">
+                <Label>Perfil de Mídia</Label>
+                <Select
+                  value={config.media_profile_id ? String(config.media_profile_id) : NO_MEDIA_PROFILE}
+                  onValueChange={(value) =>
+                    updateConfig({ media_profile_id: value === NO_MEDIA_PROFILE ? null : Number(value) })
+                  }
+                >
+                  <SelectTrigger className="w-full">
+                    <SelectValue placeholder="Selecione o rolo de etiquetas" />
+                  </SelectTrigger>
+                  <SelectContent>
+                    <SelectItem value={NO_MEDIA_PROFILE}>Sem perfil (largura e altura abaixo)</SelectItem>
+                    {mediaProfiles.map((profile) => (
+                      <SelectItem key={profile.id} value={String(profile.id)}>
+                        {profile.name}
+                      </SelectItem>
+                    ))}
+                  </SelectContent>
+                </Select>
+                <p className="text-sm text-muted-foreground">
+                  {selectedProfile
+                    ? `Liner de ${selectedProfile.liner_width}mm, ${selectedProfile.columns} coluna(s) de ${selectedProfile.label_width}mm x ${selectedProfile.label_height}mm, sensor de ${SENSOR_LABELS[selectedProfile.sensor]}. O perfil define o tamanho e as colunas das etiquetas.`
+                    : "Sem perfil, o tamanho vem da largura e altura abaixo e as colunas vêm do modelo de etiqueta."}
+                </p>
+              </div>
+
               <div className="grid grid-cols-2 gap-4">
                 <div className="space-y-2">
                   <Label>Largura (dots)</Label>
@@ -243,9 +327,12 @@ export default function ConfiguracaoPage() {
                     type="number"
                     value={config.width}
                     onChange={(e) => updateConfig({ width: Number.parseInt(e.target.value) || 0 })}
-                    min={0}
+                    min={1}
+                    max={printheadWidth}
                   />
-                  <p className="text-sm text-muted-foreground">8 dots = 1mm</p>
+                  <p className="text-sm text-muted-foreground">
+                    {dotsPerMm} dots = 1mm (máximo {printheadWidth}, largura da cabeça de impressão)
+                  </p>
                 </div>
 
                 <div className="space-y-2">
@@ -254,9 +341,9 @@ export default function ConfiguracaoPage() {
                     type="number"
                     value={config.height}
                     onChange={(e) => updateConfig({ height: Number.parseInt(e.target.value) || 0 })}
-                    min={0}
+                    min={1}
                   />
-                  <p className="text-sm text-muted-foreground">8 dots = 1mm</p>
+                  <p className="text-sm text-muted-foreground">{dotsPerMm} dots = 1mm</p>
                 </div>
               </div>
 
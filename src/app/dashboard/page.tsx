@@ -62,7 +62,7 @@ export default function HomePage() {
 
       setStats({
         totalProducts: products.length,
-        totalLabels: printHistory.length,
+        totalLabels: printHistory.reduce((total, job) => total + (job.quantity ?? 1), 0),
         recentPrints: printHistory.slice(0, 5),
         recentProducts: products
           .sort((a, b) => new Date(b.created_at).getTime() - new Date(a.created_at).getTime())
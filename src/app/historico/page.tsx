@@ -15,6 +15,7 @@ interface PrintJob {
   product_code: string
   created_at: string
   status: string
+  quantity: number
 }
 
 export default function HistoricoPage() {
@@ -82,6 +83,7 @@ export default function HistoricoPage() {
                 <TableHead>Data</TableHead>
                 <TableHead>Produto</TableHead>
                 <TableHead>Código</TableHead>
+                <TableHead className="text-right">Qtd.</TableHead>
                 <TableHead className="text-right">Status</TableHead>
               </TableRow>
             </TableHeader>
@@ -92,6 +94,7 @@ export default function HistoricoPage() {
                     <TableCell>{new Date(job.created_at).toLocaleString()}</TableCell>
                     <TableCell>{job.product_name}</TableCell>
                     <TableCell>{job.product_code}</TableCell>
+                    <TableCell className="text-right">{job.quantity}</TableCell>
                     <TableCell className="text-right">
                       <Badge variant={job.status === "completed" ? "default" : "secondary"}>
                        {job.status === "completed" ? "Concluído" : job.status}
@@ -101,7 +104,7 @@ export default function HistoricoPage() {
                 ))
               ) : (
                 <TableRow>
-                  <TableCell colSpan={4} className="text-center py-4">
+                  <TableCell colSpan={5} className="text-center py-4">
                     {searchTerm ? "Nenhum resultado encontrado" : "Nenhum histórico de impressão"}
                   </TableCell>
                 </TableRow>
@@ -34,6 +34,15 @@ interface PrintJob {
   product_code: string
   created_at: string
   status: string
+  printer_name?: string | null
+  attempts?: number | null
+  quantity: number
+}
+
+interface PrintOutcome {
+  printer: string
+  attempts: number
+  used_fallback: boolean
 }
 
 interface SelectedProduct extends Product {
@@ -199,47 +208,27 @@ export default function ImpressaoPage() {
 
     setLoading(true)
     try {
-      let totalPrinted = 0
-      const totalToPrint = Object.values(selectedProducts).reduce((acc, product) => {
-        const quantity = product.quantity === '' ? 0 : Number(product.quantity);
-        return acc + quantity;
-      }, 0);
-
-      // Cria a fila de impressão com exatamente a quantidade especificada
-      const printQueue: (Product | null)[] = []
-      for (const productId in selectedProducts) {
-        const product = selectedProducts[productId]
-        const quantity = product.quantity === '' ? 1 : Number(product.quantity);
-
-        for (let i = 0; i < quantity; i++) {
-          printQueue.push(product)
-        }
-      }
-
-      // Imprime em grupos de 3 (isso é limitação física da impressora)
-      for (let i = 0; i < printQueue.length; i += 3) {
-        const batch = printQueue.slice(i, i + 3)
-        // Se o batch tiver menos que 3 etiquetas, completa com null
-        while (batch.length < 3) {
-          batch.push(null)
-        }
-
-        await invoke("print_label_batch", {
-          products: batch,
-          printerName: selectedPrinter,
-        })
-
-        totalPrinted += batch.filter((p) => p !== null).length
+      // Um item por produto com a quantidade pedida; o backend distribui as cópias nas fileiras
+      const items = Object.values(selectedProducts)
+        .map((product) => ({
+          product_id: product.id,
+          quantity: product.quantity === '' ? 1 : Number(product.quantity),
+        }))
+        .filter((item) => item.quantity > 0)
+
+      const outcome = await invoke<PrintOutcome>("print_label_quantities", {
+        items,
+        printerName: selectedPrinter,
+      })
 
-        // Atualiza o progresso
-        toast.info("Imprimindo...", {
-          description: `Etiqueta ${totalPrinted} de ${totalToPrint}`,
+      if (outcome.used_fallback) {
+        toast.warning("Impressora reserva utilizada", {
+          description: `Etiquetas enviadas para ${outcome.printer} após ${outcome.attempts} tentativa(s)`,
         })
-
-        // Espera 1 segundo entre impressões
-        await new Promise((resolve) => setTimeout(resolve, 1000))
       }
 
+      const totalPrinted = items.reduce((total, item) => total + item.quantity, 0)
+
       toast.success("Sucesso", {
         description: `${totalPrinted} etiqueta(s) impressa(s) com sucesso!`,
       })
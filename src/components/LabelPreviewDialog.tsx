@@ -10,9 +10,8 @@ import {
   DialogFooter,
 } from "@/components/ui/dialog"
 import { Button } from "@/components/ui/button"
-import { cn } from "@/lib/utils"
-import { useState } from "react"
-import { Eye, ZoomIn, ZoomOut, Printer, Loader2 } from "lucide-react"
+import { useEffect, useState } from "react"
+import { Eye, ZoomIn, ZoomOut, Printer, Loader2, AlertTriangle } from "lucide-react"
 import { invoke } from "@tauri-apps/api/tauri"
 import { toast } from "sonner"
 import { usePrinter } from "@/contexts/printer-context"
@@ -32,59 +31,26 @@ interface LabelPreviewDialogProps {
   onPrintSuccess?: () => void
 }
 
-// Função para calcular dígito verificador EAN-13
-function calculateEAN13CheckDigit(code: string): number {
-  const digits = code.slice(0, 12).split("").map(Number)
-  const sum = digits.reduce((acc, digit, index) => {
-    const multiplier = index % 2 === 0 ? 1 : 3
-    return acc + digit * multiplier
-  }, 0)
-  const checkDigit = (10 - (sum % 10)) % 10
-  return checkDigit
+// Imagem de uma etiqueta desenhada pelo backend a partir dos comandos da impressora
+interface PreviewImage {
+  data_url: string
+  width: number
+  height: number
+  copies: number
 }
 
-// Função para gerar o padrão de barras EAN-13
-function getEAN13Encoding(code: string): string {
-  const leftHandPatterns = [
-    ["0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011", "0110111", "0001011"],
-    ["0100111", "0110011", "0011011", "0100001", "0011101", "0111001", "0000101", "0010001", "0001001", "0010111"],
-  ]
-  const rightHandPattern = [
-    "1110010",
-    "1100110",
-    "1101100",
-    "1000010",
-    "1011100",
-    "1001110",
-    "1010000",
-    "1000100",
-    "1001000",
-    "1110100",
-  ]
-
-  const paddedCode = code.slice(0, 12).padStart(12, "0")
-  const checkDigit = calculateEAN13CheckDigit(paddedCode)
-  const fullCode = paddedCode + checkDigit
-
-  const firstDigit = Number.parseInt(fullCode[0])
-  let pattern = "101"
-
-  for (let i = 1; i <= 6; i++) {
-    const digit = Number.parseInt(fullCode[i])
-    const patternSet = firstDigit & (1 << (5 - (i - 1))) ? 1 : 0
-    pattern += leftHandPatterns[patternSet][digit]
-  }
-
-  pattern += "01010"
-
-  for (let i = 7; i <= 12; i++) {
-    const digit = Number.parseInt(fullCode[i])
-    pattern += rightHandPattern[digit]
-  }
-
-  pattern += "101"
+interface PreviewWarning {
+  line: number
+  offset: number
+  message: string
+}
 
-  return pattern
+interface LabelPreview {
+  format: string
+  dpi: number
+  images: PreviewImage[]
+  warnings: PreviewWarning[]
+  layout_warnings: string[] // Textos reduzidos, quebrados ou cortados para caber
 }
 
 export function LabelPreviewDialog({ products, disabled = false, onPrintSuccess }: LabelPreviewDialogProps) {
@@ -100,14 +66,40 @@ export function LabelPreviewDialog({ products, disabled = false, onPrintSuccess
   const uniqueProducts = new Set(validProducts.map((p) => p.id)).size
   const totalEtiquetas = validProducts.length // Já vem preparado com a quantidade correta do componente pai
 
-  // Função simplificada para organizar os produtos em linhas
-  const organizeProductRows = () => {
-    const rows: Product[][] = []
-    for (let i = 0; i < validProducts.length; i += 3) {
-      rows.push(validProducts.slice(i, i + 3))
+  // Imagens geradas pelo backend (uma por fileira de etiquetas)
+  const [previews, setPreviews] = useState<LabelPreview[]>([])
+  const [loadingPreview, setLoadingPreview] = useState(false)
+  const [previewError, setPreviewError] = useState<string | null>(null)
+
+  // Renderiza no backend os mesmos comandos que serão enviados à impressora.
+  // O backend divide o lote em fileiras; posições null ficam em branco.
+  useEffect(() => {
+    if (!open) return
+
+    let cancelled = false
+    const loadPreview = async () => {
+      setLoadingPreview(true)
+      setPreviewError(null)
+      try {
+        const result = await invoke<LabelPreview>("preview_label_batch", { products, format: "png" })
+        if (!cancelled) setPreviews([result])
+      } catch (error) {
+        console.error("Erro ao gerar preview:", error)
+        if (!cancelled) {
+          setPreviews([])
+          setPreviewError(String(error))
+        }
+      } finally {
+        if (!cancelled) setLoadingPreview(false)
+      }
     }
-    return rows
-  }
+
+    loadPreview()
+    return () => {
+      cancelled = true
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [open, products])
 
   // Função para alternar o zoom
   const toggleZoom = () => {
@@ -117,35 +109,13 @@ export function LabelPreviewDialog({ products, disabled = false, onPrintSuccess
   // Função para converter mm em pixels com escala dinâmica
   const mmToPx = (mm: number) => mm * 3.7795275591 * previewScale
 
-  // Função para gerar o código de barras
-  const generateBarcode = (barcode: string) => {
-    const pattern = getEAN13Encoding(barcode)
-    const height = mmToPx(22 * 0.35)
-    const width = mmToPx(33 * 0.9)
-    const moduleWidth = width / 95
+  // Converte dots da impressora em pixels de tela
+  const dotsToPx = (dots: number, dpi: number) => mmToPx((dots * 25.4) / dpi)
 
-    const bars = pattern.split("").map((bit, i) => {
-      const x = i * moduleWidth
-      return bit === "1" ? <rect key={i} x={x} y={0} width={moduleWidth} height={height * 0.8} fill="black" /> : null
-    })
-
-    return (
-      <svg viewBox={`0 0 ${width} ${height}`} width={width} height={height} className="max-w-full">
-        <rect x={0} y={0} width={width} height={height} fill="white" />
-        <g>{bars}</g>
-        <text
-          x={width / 2}
-          y={height * 1}
-          textAnchor="middle"
-          fill="black"
-          fontSize={mmToPx(1.8)}
-          className="font-mono"
-        >
-          {barcode}
-        </text>
-      </svg>
-    )
-  }
+  const previewWarnings = previews.flatMap((preview) => [
+    ...preview.layout_warnings,
+    ...preview.warnings.map((warning) => warning.message),
+  ])
 
   // Função para imprimir as etiquetas
   const handlePrint = async () => {
@@ -158,37 +128,13 @@ export function LabelPreviewDialog({ products, disabled = false, onPrintSuccess
 
     setPrinting(true)
     try {
-      let totalPrinted = 0
-      const totalToPrint = validProducts.length
-
-      // Imprime em grupos de 3 (isso é limitação física da impressora)
-      for (let i = 0; i < validProducts.length; i += 3) {
-        // Cria um batch com os produtos atuais (até 3)
-        const currentBatch = validProducts.slice(i, i + 3)
-
-        // Cria um novo array com tipagem explícita que permite null
-        const batch: (Product | null)[] = [...currentBatch]
-
-        // Se o batch tiver menos que 3 etiquetas, completa com null
-        while (batch.length < 3) {
-          batch.push(null)
-        }
-
-        await invoke("print_label_batch", {
-          products: batch,
-          printerName: selectedPrinter,
-        })
-
-        totalPrinted += batch.filter((p) => p !== null).length
+      // O backend divide o lote em fileiras (uma por linha de colunas da etiqueta) num único trabalho
+      await invoke("print_label_batch", {
+        products,
+        printerName: selectedPrinter,
+      })
 
-        // Atualiza o progresso
-        toast.info("Imprimindo...", {
-          description: `Etiqueta ${totalPrinted} de ${totalToPrint}`,
-        })
-
-        // Espera 1 segundo entre impressões
-        await new Promise((resolve) => setTimeout(resolve, 1000))
-      }
+      const totalPrinted = validProducts.length
 
       toast.success("Sucesso", {
         description: `${totalPrinted} etiqueta(s) impressa(s) com sucesso!`,
@@ -255,59 +201,40 @@ export function LabelPreviewDialog({ products, disabled = false, onPrintSuccess
               padding: `${1 * previewScale}rem`,
             }}
           >
-            <div className="flex flex-col gap-4">
-              {organizeProductRows().map((row, rowIndex) => (
-                <div
-                  key={rowIndex}
-                  className="grid grid-cols-3 mx-auto"
-                  style={{
-                    gap: `${0.5 * previewScale}rem`,
-                    maxWidth: `${33 * 3.7795275591 * previewScale * 3 + 0.5 * previewScale * 16 * 2}px`,
-                  }}
-                >
-                  {row.map((product, index) => (
-                    <div
+            <div className="flex flex-col gap-4 items-center">
+              {loadingPreview && <Loader2 className="h-6 w-6 animate-spin text-white" />}
+              {previewError && <p className="text-sm text-red-400">{previewError}</p>}
+              {!loadingPreview &&
+                previews.map((preview, rowIndex) =>
+                  preview.images.map((image, index) => (
+                    <img
                       key={`${rowIndex}-${index}`}
-                      className={cn(
-                        "shadow-lg rounded-lg flex flex-col items-center justify-between transition-all duration-200",
-                        "relative overflow-hidden",
-                        "bg-white text-black",
-                      )}
+                      src={image.data_url}
+                      alt={`Etiquetas da linha ${rowIndex + 1}`}
+                      className="shadow-lg bg-white"
                       style={{
-                        width: mmToPx(33),
-                        height: mmToPx(22),
-                        padding: mmToPx(0.5),
+                        width: dotsToPx(image.width, preview.dpi),
+                        height: dotsToPx(image.height, preview.dpi),
+                        imageRendering: "pixelated",
                       }}
-                    >
-                      <div className="flex-1 flex flex-col justify-start items-center gap-[0.15rem] w-full">
-                        <div
-                          className="w-full text-center font-bold tracking-wide text-black"
-                          style={{ fontSize: `${0.6 * previewScale}rem` }}
-                        >
-                          ESTRELA METAIS
-                        </div>
-                        <div
-                          className="w-full text-center font-medium text-black"
-                          style={{ fontSize: `${0.6 * previewScale}rem` }}
-                        >
-                          {product.name_short}
-                        </div>
-                        <div
-                          className="w-full text-center font-medium text-black"
-                          style={{ fontSize: `${0.6 * previewScale}rem` }}
-                        >
-                          {product.product_code}
-                        </div>
-                      </div>
-                      <div className="mt-auto w-full flex justify-center">{generateBarcode(product.barcode)}</div>
-                    </div>
-                  ))}
-                </div>
-              ))}
+                    />
+                  )),
+                )}
             </div>
           </div>
         </div>
 
+        {previewWarnings.length > 0 && (
+          <div className="text-sm text-amber-500 space-y-1">
+            {previewWarnings.map((warning, index) => (
+              <p key={index} className="flex items-center gap-2">
+                <AlertTriangle className="h-4 w-4 shrink-0" />
+                {warning}
+              </p>
+            ))}
+          </div>
+        )}
+
         <div className="text-center text-sm text-muted-foreground space-y-1">
           <p>
             Produtos diferentes: {uniqueProducts}
@@ -11,6 +11,35 @@ interface PrinterStatus {
   isConnected: boolean
   port: string
   lastConnection: string | null
+  state: string | null
+}
+
+// Estado retornado pelo comando get_printer_status
+interface PrinterState {
+  ready: boolean
+  paper_out: boolean
+  ribbon_out: boolean
+  head_open: boolean
+  paused: boolean
+  buffer_full: boolean
+  printing: boolean
+  paper_jam: boolean
+  error: boolean
+  raw: string
+}
+
+const describePrinterState = (state: PrinterState) => {
+  const problems: string[] = []
+  if (state.head_open) problems.push("Cabeça aberta")
+  if (state.paper_out) problems.push("Sem papel")
+  if (state.paper_jam) problems.push("Papel enroscado")
+  if (state.ribbon_out) problems.push("Sem ribbon")
+  if (state.paused) problems.push("Em pausa")
+  if (state.buffer_full) problems.push("Buffer cheio")
+  if (state.error) problems.push("Erro na impressora")
+
+  if (problems.length > 0) return problems.join(", ")
+  return state.printing ? "Imprimindo" : "Pronta"
 }
 
 export function PrinterStatus() {
@@ -19,6 +48,7 @@ export function PrinterStatus() {
     isConnected: false,
     port: "",
     lastConnection: null,
+    state: null,
   })
 
   const checkPrinterConnection = async () => {
@@ -36,10 +66,20 @@ export function PrinterStatus() {
         currentSelectedPrinter: selectedPrinter,
       })
 
+      // Estado real da impressora (somente conexões bidirecionais respondem)
+      let state: string | null = null
+      try {
+        const printerState = await invoke<PrinterState>("get_printer_status", { printerName: selectedPrinter || null })
+        state = describePrinterState(printerState)
+      } catch (error) {
+        console.log("Status detalhado indisponível:", error)
+      }
+
       setStatus({
         isConnected,
         port,
         lastConnection: isConnected ? new Date().toLocaleString() : null,
+        state,
       })
     } catch (error) {
       console.error("Erro ao verificar status da impressora:", error)
@@ -47,6 +87,7 @@ export function PrinterStatus() {
         isConnected: false,
         port: "Erro",
         lastConnection: null,
+        state: null,
       })
     }
   }
@@ -102,6 +143,7 @@ export function PrinterStatus() {
             )}
             <p className="text-xs text-muted-foreground">Impressora: {selectedPrinter}</p>
             {status.port && <p className="text-xs text-muted-foreground">Porta: {status.port}</p>}
+            {status.state && <p className="text-xs text-muted-foreground">Estado: {status.state}</p>}
             {status.lastConnection && (
               <p className="text-xs text-muted-foreground">Última conexão: {status.lastConnection}</p>
             )}
@@ -12,6 +12,15 @@ interface PrinterConfig {
   speed: number
   port: string
   selected_printer?: string | null // Alterado para corresponder ao tipo retornado pelo backend
+  failure_policy?: "fail" | "retry" | "fallback" // O que fazer quando o envio falha
+  max_retries?: number
+  retry_backoff_ms?: number
+  fallback_printer?: string | null // Impressora reserva da política "fallback"
+  language?: "ppla" | "pplb" | "zpl" | "tspl" // Linguagem de comandos da impressora
+  code_page?: "cp850" | "cp1252" | "iso-8859-1" // Página de código dos textos acentuados
+  code_page_fallback?: "strip" | "question" // Caracteres sem correspondência: sem acento ou "?"
+  media_profile_id?: number | null // Perfil de mídia (sem perfil: largura e altura abaixo)
+  dpi?: 203 | 300 // Resolução da cabeça de impressão
 }
 
 interface PrinterContextType {